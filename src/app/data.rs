@@ -14,57 +14,75 @@
  * limitations under the License.
  */
 
-use super::state::{GraphOptions, PanelOptions, PanelState, PanelType, YAxisMode};
+use super::interpolate::interpolate;
+use super::state::{
+    GraphOptions, NullSample, PanelOptions, PanelState, PanelType, QueryResolution, YAxisMode,
+};
+use super::variables::Variable;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::time::Duration;
 
-const PANEL_RESOLUTION_POINTS: u32 = 200;
+/// Points per series requested when a panel's width is not known yet.
+pub(crate) const PANEL_RESOLUTION_POINTS: u32 = 200;
 
+/// Prometheus rejects range queries returning more points per series.
+const MAX_POINTS_PER_SERIES: u64 = 11_000;
+
+/// Steps that automatically computed query steps are rounded up to.
+const NICE_INTERVALS: &[u64] = &[
+    1, 2, 5, 10, 15, 30, 60, 120, 300, 600, 900, 1800, 3600, 7200, 10800, 21600, 43200, 86400,
+];
+
+/// Expands builtin and template variables in `expr`.
+///
+/// See [`interpolate`] for the supported reference syntax and formats.
 pub(crate) fn expand_expr(
     expr: &str,
     range: Duration,
     step: Duration,
-    vars: &HashMap<String, String>,
+    vars: &HashMap<String, Variable>,
 ) -> String {
-    let mut s = expr.to_string();
-
-    let interval = interval_duration(range, step);
-    s = replace_builtin(&s, "__interval_ms", &interval.as_millis().to_string());
-    s = replace_builtin(&s, "__interval", &format_prom_duration(interval));
-    s = replace_builtin(&s, "__range_ms", &range.as_millis().to_string());
-    s = replace_builtin(&s, "__range_s", &range.as_secs().to_string());
-    s = replace_builtin(&s, "__range", &format_prom_duration(range));
-
-    let interval_secs = std::cmp::max(step.as_secs() * 4, 60);
-    let interval_param = format!("{}s", interval_secs);
-    s = replace_builtin(
-        &s,
-        "__rate_interval_ms",
-        &(interval_secs * 1000).to_string(),
-    );
-    s = replace_builtin(&s, "__rate_interval", &interval_param);
-
-    for (k, v) in vars {
-        s = s.replace(&format!("${{{}}}", k), v);
-        s = s.replace(&format!("${}", k), v);
-    }
-
-    s
-}
-
-fn replace_builtin(expr: &str, name: &str, value: &str) -> String {
-    expr.replace(&format!("${{{}}}", name), value)
-        .replace(&format!("${}", name), value)
+    let rate_interval = std::cmp::max(step.as_secs() * 4, 60);
+    let builtins: HashMap<&str, Variable> = [
+        ("__interval", format_prom_duration(step)),
+        ("__interval_ms", step.as_millis().to_string()),
+        ("__range", format_prom_duration(range)),
+        ("__range_ms", range.as_millis().to_string()),
+        ("__range_s", range.as_secs().to_string()),
+        ("__rate_interval", format!("{}s", rate_interval)),
+        ("__rate_interval_ms", (rate_interval * 1000).to_string()),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name, Variable::single(value)))
+    .collect();
+
+    interpolate(expr, |name| builtins.get(name).or_else(|| vars.get(name)))
 }
 
-fn interval_duration(range: Duration, step: Duration) -> Duration {
-    let resolution = range / PANEL_RESOLUTION_POINTS;
-    let interval = resolution.max(step);
-    interval.max(Duration::from_secs(1))
+/// Computes a Grafana-style range query step.
+///
+/// The range is split into `max_data_points` intervals, rounded up to a nice
+/// duration and multiplied by `interval_factor`. The result never drops below
+/// `min_step` or below what Prometheus' per-series point limit allows.
+pub(crate) fn query_step(
+    range: Duration,
+    max_data_points: u32,
+    min_step: Duration,
+    interval_factor: u32,
+) -> Duration {
+    let raw = range.as_secs().div_ceil(u64::from(max_data_points.max(1)));
+    let interval = NICE_INTERVALS
+        .iter()
+        .copied()
+        .find(|nice| *nice >= raw)
+        .unwrap_or_else(|| raw.div_ceil(86400) * 86400);
+    let step = Duration::from_secs(interval * u64::from(interval_factor.max(1)));
+    let safe = Duration::from_secs(range.as_secs().div_ceil(MAX_POINTS_PER_SERIES));
+    step.max(min_step).max(safe).max(Duration::from_secs(1))
 }
 
-fn format_prom_duration(duration: Duration) -> String {
+pub(crate) fn format_prom_duration(duration: Duration) -> String {
     let secs = duration.as_secs();
     if secs == 0 {
         return format!("{}ms", duration.as_millis().max(1));
@@ -74,11 +92,11 @@ fn format_prom_duration(duration: Duration) -> String {
     const HOUR: u64 = 60 * 60;
     const MINUTE: u64 = 60;
 
-    if secs % DAY == 0 {
+    if secs.is_multiple_of(DAY) {
         format!("{}d", secs / DAY)
-    } else if secs % HOUR == 0 {
+    } else if secs.is_multiple_of(HOUR) {
         format!("{}h", secs / HOUR)
-    } else if secs % MINUTE == 0 {
+    } else if secs.is_multiple_of(MINUTE) {
         format!("{}m", secs / MINUTE)
     } else {
         format!("{}s", secs)
@@ -93,6 +111,52 @@ pub(crate) fn format_legend(fmt: &str, metric: &HashMap<String, String>) -> Stri
     out
 }
 
+/// Splits the samples of a query into finite points and nulls. Non-finite
+/// samples are nulls, as is the first missing step of every interval of
+/// more than `gap` seconds between samples. With `null_as_zero`, such
+/// intervals get zero points at both ends instead.
+pub(crate) fn split_nulls(
+    samples: Vec<(f64, f64)>,
+    step: f64,
+    gap: f64,
+    null_as_zero: bool,
+) -> (Vec<(f64, f64)>, Vec<NullSample>) {
+    let mut points = Vec::with_capacity(samples.len());
+    let mut nulls = Vec::new();
+    let mut previous: Option<f64> = None;
+
+    for (ts, value) in samples {
+        if let Some(previous) = previous
+            && ts - previous > gap
+        {
+            let first = (previous + step).min((previous + ts) / 2.0);
+            if null_as_zero {
+                let last = (ts - step).max(first);
+                points.push((first, 0.0));
+                if last > first {
+                    points.push((last, 0.0));
+                }
+            } else {
+                nulls.push(NullSample {
+                    ts: first,
+                    value: None,
+                });
+            }
+        }
+        previous = Some(ts);
+
+        if value.is_finite() {
+            points.push((ts, value));
+        } else {
+            nulls.push(NullSample {
+                ts,
+                value: Some(value),
+            });
+        }
+    }
+    (points, nulls)
+}
+
 /// Downsamples data points to a maximum number of points using max-pooling.
 /// This preserves peaks which is important for metrics.
 pub(crate) fn downsample(points: Vec<(f64, f64)>, max_points: usize) -> Vec<(f64, f64)> {
@@ -131,19 +195,29 @@ pub(crate) fn default_queries(mut provided: Vec<String>) -> Vec<PanelState> {
             exprs: vec![q],
             legends: vec![None],
             query_modes: vec![crate::app::QueryMode::Range],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: QueryResolution::default(),
             series: vec![],
             last_error: None,
             last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
             last_samples: 0,
             grid: None,
             y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
             panel_type: PanelType::Graph,
             thresholds: None,
             min: None,
             max: None,
             autogrid: None,
             display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
             options: PanelOptions::Graph(GraphOptions::default()),
+            repeat: None,
+            scoped_vars: HashMap::new(),
         })
         .collect()
 }
@@ -175,8 +249,8 @@ mod tests {
     #[test]
     fn test_expand_expr_vars() {
         let mut vars = HashMap::new();
-        vars.insert("job".to_string(), "node-exporter".to_string());
-        vars.insert("instance".to_string(), "localhost:9100".to_string());
+        vars.insert("job".to_string(), Variable::single("node-exporter"));
+        vars.insert("instance".to_string(), Variable::single("localhost:9100"));
 
         let step = Duration::from_secs(15);
 
@@ -197,18 +271,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_expr_multi_value_vars() {
+        let mut vars = HashMap::new();
+        vars.insert(
+            "instance".to_string(),
+            Variable::with_values(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+        );
+
+        let expr = "up{instance=~\"$instance\"}";
+        let expanded = expand_expr(
+            expr,
+            Duration::from_secs(300),
+            Duration::from_secs(15),
+            &vars,
+        );
+        assert_eq!(expanded, "up{instance=~\"(a|b|c)\"}");
+    }
+
     #[test]
     fn test_expand_expr_builtin_intervals_and_range() {
         let vars = HashMap::new();
         let range = Duration::from_secs(24 * 60 * 60);
-        let step = Duration::from_secs(60);
+        let step = Duration::from_secs(600);
         let expr = "rate(http_requests_total[$__interval]) offset $__range";
         let expanded = expand_expr(expr, range, step, &vars);
-        assert_eq!(expanded, "rate(http_requests_total[432s]) offset 1d");
+        assert_eq!(expanded, "rate(http_requests_total[10m]) offset 1d");
 
         let expr = "sum_over_time(up[${__range_s}s]) / $__interval_ms / $__range_ms";
         let expanded = expand_expr(expr, range, step, &vars);
-        assert_eq!(expanded, "sum_over_time(up[86400s]) / 432000 / 86400000");
+        assert_eq!(expanded, "sum_over_time(up[86400s]) / 600000 / 86400000");
+    }
+
+    #[test]
+    fn test_query_step_adapts_to_range_and_width() {
+        let min_step = Duration::from_secs(5);
+        let hour = Duration::from_secs(3600);
+
+        // 5m over 200 columns stays at the minimum step.
+        assert_eq!(
+            query_step(Duration::from_secs(300), 200, min_step, 1),
+            min_step
+        );
+        // 1h over 100 columns: 36s rounds up to 1m.
+        assert_eq!(query_step(hour, 100, min_step, 1), Duration::from_secs(60));
+        // intervalFactor multiplies the rounded interval.
+        assert_eq!(query_step(hour, 100, min_step, 2), Duration::from_secs(120));
+        // 7d over 100 columns: 6048s rounds up to 2h.
+        assert_eq!(
+            query_step(hour * 24 * 7, 100, min_step, 1),
+            Duration::from_secs(7200)
+        );
+        // Huge maxDataPoints is capped by Prometheus' per-series limit.
+        assert_eq!(
+            query_step(hour * 24 * 7, 1_000_000, Duration::from_secs(1), 1),
+            Duration::from_secs(55)
+        );
     }
 
     #[test]
@@ -224,6 +342,43 @@ mod tests {
         assert_eq!(format_legend(fmt2, &metric), "Static Text");
     }
 
+    #[test]
+    fn test_split_nulls_marks_gaps_and_non_finite_samples() {
+        let samples = vec![
+            (0.0, 1.0),
+            (10.0, f64::NAN),
+            (20.0, 2.0),
+            (60.0, 3.0),
+            (70.0, f64::INFINITY),
+        ];
+
+        let (points, nulls) = split_nulls(samples.clone(), 10.0, 15.0, false);
+        assert_eq!(points, vec![(0.0, 1.0), (20.0, 2.0), (60.0, 3.0)]);
+        assert_eq!(nulls.len(), 3);
+        assert!(nulls[0].ts == 10.0 && nulls[0].value.unwrap().is_nan());
+        assert_eq!(
+            nulls[1],
+            NullSample {
+                ts: 30.0,
+                value: None,
+            }
+        );
+        assert_eq!(nulls[2].value, Some(f64::INFINITY));
+
+        let (points, nulls) = split_nulls(samples, 10.0, 15.0, true);
+        assert_eq!(
+            points,
+            vec![
+                (0.0, 1.0),
+                (20.0, 2.0),
+                (30.0, 0.0),
+                (50.0, 0.0),
+                (60.0, 3.0)
+            ]
+        );
+        assert_eq!(nulls.len(), 2);
+    }
+
     #[test]
     fn test_downsample() {
         let points: Vec<(f64, f64)> = (0..1000).map(|i| (i as f64, i as f64)).collect();
@@ -0,0 +1,226 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::variables::Variable;
+use crate::prom::PromClient;
+use std::collections::HashMap;
+
+/// A named Prometheus datasource.
+#[derive(Debug, Clone)]
+pub(crate) struct Datasource {
+    /// Name used in config, on the CLI and in the footer.
+    pub(crate) name: String,
+    /// Grafana datasource UID this entry answers to, if any.
+    pub(crate) uid: Option<String>,
+    /// Client used for every query sent to this datasource.
+    pub(crate) client: PromClient,
+}
+
+/// Registry of Prometheus datasources, one of which is the default.
+///
+/// Panels, targets and template variables carry the raw Grafana datasource
+/// reference; the registry maps it to a client at query time so switching the
+/// default datasource takes effect on the next refresh.
+#[derive(Debug, Clone)]
+pub(crate) struct DatasourceRegistry {
+    entries: Vec<Datasource>,
+    default: usize,
+}
+
+impl DatasourceRegistry {
+    /// Creates a registry with a single datasource named `default`.
+    #[cfg(test)]
+    pub(crate) fn single(client: PromClient) -> Self {
+        Self {
+            entries: vec![Datasource {
+                name: "default".to_string(),
+                uid: None,
+                client,
+            }],
+            default: 0,
+        }
+    }
+
+    /// Creates a registry from `entries`, using the entry at `default` when a
+    /// reference cannot be resolved. Returns `None` when `entries` is empty.
+    pub(crate) fn new(entries: Vec<Datasource>, default: usize) -> Option<Self> {
+        if entries.is_empty() {
+            return None;
+        }
+        let default = default.min(entries.len() - 1);
+        Some(Self { entries, default })
+    }
+
+    pub(crate) fn entries(&self) -> &[Datasource] {
+        &self.entries
+    }
+
+    pub(crate) fn default_datasource(&self) -> &Datasource {
+        &self.entries[self.default]
+    }
+
+    /// Makes the next datasource the default, wrapping around.
+    pub(crate) fn cycle_default(&mut self) -> &Datasource {
+        self.default = (self.default + 1) % self.entries.len();
+        self.default_datasource()
+    }
+
+    /// Makes the datasource matching `reference` (UID or name) the default.
+    pub(crate) fn select_default(&mut self, reference: &str) -> Option<&Datasource> {
+        self.default = self.position(reference)?;
+        Some(self.default_datasource())
+    }
+
+    /// Finds a datasource by UID or name.
+    pub(crate) fn position(&self, reference: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|ds| ds.uid.as_deref() == Some(reference))
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .position(|ds| ds.name.eq_ignore_ascii_case(reference))
+            })
+    }
+
+    /// Resolves a Grafana datasource reference to a datasource.
+    ///
+    /// References may be a UID, a name or a variable such as `${DS_PROMETHEUS}`.
+    /// Missing, unknown and special references (`default`, `-- Mixed --`) fall
+    /// back to the default datasource.
+    pub(crate) fn resolve(
+        &self,
+        reference: Option<&str>,
+        vars: &HashMap<String, Variable>,
+    ) -> &Datasource {
+        let Some(reference) = reference.map(str::trim).filter(|r| !r.is_empty()) else {
+            return self.default_datasource();
+        };
+        let reference = match variable_reference(reference) {
+            Some(name) => match vars.get(name).and_then(Variable::first) {
+                Some(value) => value,
+                None => return self.default_datasource(),
+            },
+            None => reference,
+        };
+
+        self.position(reference)
+            .map(|idx| &self.entries[idx])
+            .unwrap_or_else(|| self.default_datasource())
+    }
+}
+
+/// Returns the variable name when `reference` is a single variable reference
+/// (`$name`, `${name}`, `${name:format}` or `[[name]]`).
+fn variable_reference(reference: &str) -> Option<&str> {
+    let name = if let Some(inner) = reference
+        .strip_prefix("${")
+        .and_then(|r| r.strip_suffix('}'))
+    {
+        inner.split(':').next().unwrap_or(inner)
+    } else if let Some(inner) = reference
+        .strip_prefix("[[")
+        .and_then(|r| r.strip_suffix("]]"))
+    {
+        inner.split(':').next().unwrap_or(inner)
+    } else {
+        reference.strip_prefix('$')?
+    };
+
+    (!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .then_some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> DatasourceRegistry {
+        DatasourceRegistry::new(
+            vec![
+                Datasource {
+                    name: "prod".to_string(),
+                    uid: Some("P1".to_string()),
+                    client: PromClient::new("http://prod:9090".to_string()),
+                },
+                Datasource {
+                    name: "staging".to_string(),
+                    uid: Some("S1".to_string()),
+                    client: PromClient::new("http://staging:9090".to_string()),
+                },
+            ],
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_by_uid_name_and_variable() {
+        let registry = registry();
+        let vars = HashMap::from([("datasource".to_string(), Variable::single("S1"))]);
+
+        assert_eq!(registry.resolve(Some("S1"), &vars).name, "staging");
+        assert_eq!(registry.resolve(Some("Staging"), &vars).name, "staging");
+        assert_eq!(registry.resolve(Some("$datasource"), &vars).name, "staging");
+        assert_eq!(
+            registry.resolve(Some("${datasource}"), &vars).name,
+            "staging"
+        );
+        assert_eq!(
+            registry.resolve(Some("[[datasource]]"), &vars).name,
+            "staging"
+        );
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_default() {
+        let registry = registry();
+        let vars = HashMap::new();
+
+        assert_eq!(registry.resolve(None, &vars).name, "prod");
+        assert_eq!(
+            registry.resolve(Some("${DS_PROMETHEUS}"), &vars).name,
+            "prod"
+        );
+        assert_eq!(registry.resolve(Some("-- Mixed --"), &vars).name, "prod");
+        assert_eq!(registry.resolve(Some("unknown-uid"), &vars).name, "prod");
+    }
+
+    #[test]
+    fn test_cycle_default_wraps() {
+        let mut registry = registry();
+        let vars = HashMap::new();
+
+        assert_eq!(registry.cycle_default().name, "staging");
+        assert_eq!(registry.resolve(None, &vars).name, "staging");
+        assert_eq!(registry.cycle_default().name, "prod");
+    }
+
+    #[test]
+    fn test_select_default_by_name_or_uid() {
+        let mut registry = registry();
+
+        assert_eq!(registry.select_default("S1").unwrap().name, "staging");
+        assert_eq!(registry.select_default("prod").unwrap().name, "prod");
+        assert!(registry.select_default("missing").is_none());
+        assert_eq!(registry.default_datasource().name, "prod");
+    }
+
+    #[test]
+    fn test_new_rejects_empty_registry() {
+        assert!(DatasourceRegistry::new(Vec::new(), 0).is_none());
+    }
+}
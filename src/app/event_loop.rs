@@ -24,6 +24,9 @@ use ratatui::Terminal;
 use ratatui::layout::Rect;
 use std::time::Duration;
 
+/// How often to check for background refresh results while one is in flight.
+const REFRESH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 pub(crate) async fn run_app<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
     app: &mut AppState,
@@ -35,16 +38,22 @@ where
     let mut needs_draw = true;
 
     loop {
+        if app.apply_refresh_updates() {
+            needs_draw = true;
+            if !app.is_refreshing() {
+                capture_recording_after_change(terminal, app)?;
+            }
+        }
+
         if needs_draw {
-            terminal.draw(|f| ui::draw_ui(f, app))?;
+            let frame = terminal.draw(|f| ui::draw_ui(f, app))?;
+            app.update_panel_widths(frame.area);
             needs_draw = false;
         }
 
-        let timeout = app.refresh_every.saturating_sub(app.last_refresh.elapsed());
-
-        if event::poll(timeout)? {
+        if event::poll(poll_timeout(app))? {
             let action = match event::read()? {
-                Event::Key(key) => input::handle_key(key, app).await?,
+                Event::Key(key) => input::handle_key(key, app)?,
                 Event::Mouse(mouse) => {
                     let size = terminal.size()?;
                     input::handle_mouse(mouse, size, app)?
@@ -76,14 +85,24 @@ where
             }
         }
 
-        if app.last_refresh.elapsed() >= app.refresh_every {
-            app.refresh().await?;
+        if app.last_refresh.elapsed() >= app.refresh_every && app.request_refresh() {
             needs_draw = true;
-            capture_recording_after_change(terminal, app)?;
         }
     }
 }
 
+/// How long to wait for input before checking on refreshes again.
+///
+/// While a refresh is in flight no new one can start, so only its results are
+/// awaited; a refresh outlasting `refresh_every` must not make the wait zero.
+fn poll_timeout(app: &AppState) -> Duration {
+    if app.is_refreshing() {
+        REFRESH_POLL_INTERVAL
+    } else {
+        app.refresh_every.saturating_sub(app.last_refresh.elapsed())
+    }
+}
+
 fn terminal_viewport<B: ratatui::backend::Backend>(terminal: &Terminal<B>) -> Result<Rect>
 where
     <B as ratatui::backend::Backend>::Error: Send + Sync + 'static,
@@ -135,7 +154,9 @@ mod tests {
     fn test_app(export: ExportOptions) -> AppState {
         let now = chrono::Utc::now().timestamp() as f64;
         AppState::new(
-            PromClient::new("http://localhost:9090".to_string()),
+            crate::app::DatasourceRegistry::single(PromClient::new(
+                "http://localhost:9090".to_string(),
+            )),
             std::time::Duration::from_secs(100),
             std::time::Duration::from_secs(10),
             std::time::Duration::from_secs(1),
@@ -145,24 +166,35 @@ mod tests {
                 exprs: vec![],
                 legends: vec![],
                 query_modes: vec![],
+                datasources: vec![],
+                ref_ids: vec![],
+                resolution: crate::app::QueryResolution::default(),
                 series: vec![SeriesView {
                     name: "usage".to_string(),
                     value: Some(1.0),
                     points: vec![(now - 100.0, 0.0), (now, 1.0)],
                     visible: true,
+                    ..Default::default()
                 }],
                 last_error: None,
                 last_url: None,
+                last_step: None,
+                loading: false,
+                stale: false,
                 last_samples: 2,
                 grid: None,
                 y_axis_mode: YAxisMode::Auto,
+                legend_scroll: 0,
                 panel_type: PanelType::Graph,
                 thresholds: None,
                 min: None,
                 max: None,
                 autogrid: None,
                 display: crate::ui::DisplayFormat::default(),
+                overrides: vec![],
                 options: PanelOptions::Graph(GraphOptions::default()),
+                repeat: None,
+                scoped_vars: std::collections::HashMap::new(),
             }],
             0,
             Theme::default(),
@@ -195,6 +227,19 @@ mod tests {
         fs::remove_dir_all(dir).unwrap();
     }
 
+    #[tokio::test]
+    async fn test_poll_timeout_waits_while_refresh_outlasts_interval() {
+        let mut app = test_app(ExportOptions::default());
+        assert_eq!(poll_timeout(&app), Duration::ZERO);
+
+        assert!(app.request_refresh());
+        // The refresh is still running when the next one is due.
+        app.last_refresh -= app.refresh_every * 2;
+        assert!(!app.request_refresh());
+
+        assert_eq!(poll_timeout(&app), REFRESH_POLL_INTERVAL);
+    }
+
     #[test]
     fn test_finalize_recording_before_quit_writes_manifest() {
         let dir = test_export_dir("quit-recording");
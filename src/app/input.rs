@@ -14,7 +14,7 @@
  * limitations under the License.
  */
 
-use super::state::{AppMode, AppState, YAxisMode};
+use super::state::{AppMode, AppState, PanelOptions, YAxisMode};
 use crate::ui;
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
@@ -34,21 +34,29 @@ enum SharedKeyResult {
     Unhandled,
 }
 
-pub(super) async fn handle_key(key: KeyEvent, app: &mut AppState) -> Result<InputAction> {
+pub(super) fn handle_key(key: KeyEvent, app: &mut AppState) -> Result<InputAction> {
     if key.code == KeyCode::Char('e') && key.modifiers.contains(KeyModifiers::CONTROL) {
         return Ok(InputAction::ToggleRecording);
     }
 
-    if key.code == KeyCode::Char('e') && key.modifiers.is_empty() && app.mode != AppMode::Search {
+    if key.code == KeyCode::Char('e')
+        && key.modifiers.is_empty()
+        && !matches!(
+            app.mode,
+            AppMode::Search | AppMode::VariablePicker | AppMode::FilterEditor
+        )
+    {
         return Ok(InputAction::ExportCurrent);
     }
 
     let action = match app.mode {
         AppMode::Search => handle_search_key(key, app),
         AppMode::Inspect => handle_inspect_key(key, app),
-        AppMode::Fullscreen => handle_fullscreen_key(key, app).await?,
+        AppMode::Fullscreen => handle_fullscreen_key(key, app),
         AppMode::FullscreenInspect => handle_fullscreen_inspect_key(key, app),
-        AppMode::Normal => handle_normal_key(key, app).await?,
+        AppMode::Normal => handle_normal_key(key, app),
+        AppMode::VariablePicker => handle_variable_picker_key(key, app),
+        AppMode::FilterEditor => handle_filter_editor_key(key, app),
     };
     Ok(action)
 }
@@ -61,9 +69,26 @@ pub(super) fn handle_mouse(
     match mouse.kind {
         MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left) => {
             let rect = Rect::new(0, 0, terminal_size.width, terminal_size.height);
+            if app.mode == AppMode::Normal
+                && let Some(entry) = ui::variable_hit_test(app, rect, mouse.column, mouse.row)
+            {
+                match entry {
+                    ui::VariableBarEntry::Variable(idx) => app.open_variable_picker(idx),
+                    ui::VariableBarEntry::Filters => app.open_filter_editor(),
+                }
+                return Ok(InputAction::Redraw);
+            }
             if let Some((idx, panel_rect)) = ui::hit_test(app, rect, mouse.column, mouse.row) {
                 app.selected_panel = idx;
 
+                if app.mode == AppMode::Normal
+                    && matches!(mouse.kind, MouseEventKind::Down(_))
+                    && app.panels[idx].is_row()
+                {
+                    app.toggle_row(idx);
+                    return Ok(InputAction::Redraw);
+                }
+
                 match app.mode {
                     AppMode::Normal | AppMode::Inspect => {}
                     AppMode::Fullscreen | AppMode::FullscreenInspect => {
@@ -103,6 +128,7 @@ fn handle_search_key(key: KeyEvent, app: &mut AppState) -> InputAction {
         }
         KeyCode::Enter => {
             if let Some(&idx) = app.search_results.first() {
+                app.reveal_panel(idx);
                 app.selected_panel = idx;
                 app.mode = AppMode::Fullscreen;
                 app.search_query.clear();
@@ -122,6 +148,47 @@ fn handle_search_key(key: KeyEvent, app: &mut AppState) -> InputAction {
     InputAction::Redraw
 }
 
+fn handle_variable_picker_key(key: KeyEvent, app: &mut AppState) -> InputAction {
+    match key.code {
+        KeyCode::Esc => app.close_variable_picker(),
+        KeyCode::Enter => app.apply_variable_picker(),
+        KeyCode::Tab => app.cycle_variable_picker(1),
+        KeyCode::BackTab => app.cycle_variable_picker(-1),
+        _ => {
+            let Some(picker) = app.var_picker.as_mut() else {
+                app.close_variable_picker();
+                return InputAction::Redraw;
+            };
+            match key.code {
+                KeyCode::Up => picker.move_cursor(-1),
+                KeyCode::Down => picker.move_cursor(1),
+                KeyCode::Char(' ') => picker.toggle(),
+                KeyCode::Backspace => {
+                    let mut query = picker.query.clone();
+                    query.pop();
+                    picker.set_query(query);
+                }
+                KeyCode::Char(c) => picker.set_query(format!("{}{c}", picker.query)),
+                _ => {}
+            }
+        }
+    }
+    InputAction::Redraw
+}
+
+fn handle_filter_editor_key(key: KeyEvent, app: &mut AppState) -> InputAction {
+    match key.code {
+        KeyCode::Esc => app.close_filter_editor(),
+        KeyCode::Enter => app.apply_filter_editor(),
+        KeyCode::Backspace => {
+            app.filter_input.pop();
+        }
+        KeyCode::Char(c) => app.filter_input.push(c),
+        _ => {}
+    }
+    InputAction::Redraw
+}
+
 fn handle_inspect_key(key: KeyEvent, app: &mut AppState) -> InputAction {
     match key.code {
         KeyCode::Esc | KeyCode::Char('v') => {
@@ -142,8 +209,8 @@ fn handle_inspect_key(key: KeyEvent, app: &mut AppState) -> InputAction {
     }
 }
 
-async fn handle_fullscreen_key(key: KeyEvent, app: &mut AppState) -> Result<InputAction> {
-    let action = match key.code {
+fn handle_fullscreen_key(key: KeyEvent, app: &mut AppState) -> InputAction {
+    match key.code {
         KeyCode::Esc | KeyCode::Char('f') | KeyCode::Enter => {
             app.mode = AppMode::Normal;
             InputAction::Redraw
@@ -161,9 +228,8 @@ async fn handle_fullscreen_key(key: KeyEvent, app: &mut AppState) -> Result<Inpu
             app.select_next_panel();
             InputAction::Redraw
         }
-        _ => shared_key_action(handle_shared_keys(key, app).await?),
-    };
-    Ok(action)
+        _ => shared_key_action(handle_shared_keys(key, app)),
+    }
 }
 
 fn handle_fullscreen_inspect_key(key: KeyEvent, app: &mut AppState) -> InputAction {
@@ -190,8 +256,20 @@ fn handle_fullscreen_inspect_key(key: KeyEvent, app: &mut AppState) -> InputActi
     }
 }
 
-async fn handle_normal_key(key: KeyEvent, app: &mut AppState) -> Result<InputAction> {
-    let action = match key.code {
+fn handle_normal_key(key: KeyEvent, app: &mut AppState) -> InputAction {
+    let row_selected = app
+        .panels
+        .get(app.selected_panel)
+        .is_some_and(|p| p.is_row());
+    match key.code {
+        KeyCode::Char(' ') => {
+            app.toggle_row(app.selected_panel);
+            InputAction::Redraw
+        }
+        KeyCode::Char('f') if row_selected => {
+            app.toggle_row(app.selected_panel);
+            InputAction::Redraw
+        }
         KeyCode::Char('f') => {
             app.mode = AppMode::Fullscreen;
             InputAction::Redraw
@@ -233,58 +311,65 @@ async fn handle_normal_key(key: KeyEvent, app: &mut AppState) -> Result<InputAct
             app.debug_bar = !app.debug_bar;
             InputAction::Redraw
         }
+        KeyCode::Char('$') => {
+            app.open_variable_picker(0);
+            InputAction::Redraw
+        }
+        KeyCode::Char('F') => {
+            app.open_filter_editor();
+            InputAction::Redraw
+        }
         KeyCode::Char('/') => {
             app.mode = AppMode::Search;
             app.search_query.clear();
             app.search_results.clear();
             InputAction::Redraw
         }
-        _ => shared_key_action(handle_shared_keys(key, app).await?),
-    };
-    Ok(action)
+        _ => shared_key_action(handle_shared_keys(key, app)),
+    }
 }
 
-async fn handle_shared_keys(key: KeyEvent, app: &mut AppState) -> Result<SharedKeyResult> {
+fn handle_shared_keys(key: KeyEvent, app: &mut AppState) -> SharedKeyResult {
     match key.code {
-        KeyCode::Char('q') => Ok(SharedKeyResult::Quit),
+        KeyCode::Char('q') => SharedKeyResult::Quit,
         KeyCode::Char('r') | KeyCode::Char('R') => {
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Char('+') => {
             app.zoom_out();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Char('-') => {
             app.zoom_in();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Char('[') if key.modifiers.contains(KeyModifiers::SHIFT) => {
             app.pan_left();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Left if key.modifiers.contains(KeyModifiers::SHIFT) => {
             app.pan_left();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Char(']') if key.modifiers.contains(KeyModifiers::SHIFT) => {
             app.pan_right();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
             app.pan_right();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Char('0') => {
             app.reset_to_live();
-            app.refresh().await?;
-            Ok(SharedKeyResult::Handled)
+            app.restart_refresh();
+            SharedKeyResult::Handled
         }
         KeyCode::Char('y') => {
             if let Some(panel) = app.panels.get_mut(app.selected_panel) {
@@ -293,13 +378,36 @@ async fn handle_shared_keys(key: KeyEvent, app: &mut AppState) -> Result<SharedK
                     YAxisMode::ZeroBased => YAxisMode::Auto,
                 };
             }
-            Ok(SharedKeyResult::Handled)
+            SharedKeyResult::Handled
+        }
+        KeyCode::Char('Y') => {
+            if let Some(PanelOptions::Graph(options)) = app
+                .panels
+                .get_mut(app.selected_panel)
+                .map(|panel| &mut panel.options)
+            {
+                options.scale = options.scale.cycled();
+            }
+            SharedKeyResult::Handled
         }
         KeyCode::Char('g') => {
             app.autogrid_enabled = !app.autogrid_enabled;
-            Ok(SharedKeyResult::Handled)
+            SharedKeyResult::Handled
         }
-        _ => Ok(SharedKeyResult::Unhandled),
+        KeyCode::Char('J') => {
+            app.scroll_legend(1);
+            SharedKeyResult::Handled
+        }
+        KeyCode::Char('K') => {
+            app.scroll_legend(-1);
+            SharedKeyResult::Handled
+        }
+        KeyCode::Char('d') => {
+            app.cycle_datasource();
+            app.restart_refresh();
+            SharedKeyResult::Handled
+        }
+        _ => SharedKeyResult::Unhandled,
     }
 }
 
@@ -321,7 +429,7 @@ fn update_search_results(app: &mut AppState) {
         .panels
         .iter()
         .enumerate()
-        .filter(|(_, panel)| panel.title.to_lowercase().contains(&query))
+        .filter(|(_, panel)| !panel.is_row() && panel.title.to_lowercase().contains(&query))
         .map(|(i, _)| i)
         .collect();
 }
@@ -349,7 +457,9 @@ fn toggle_series_visibility(app: &mut AppState, c: char) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::{GraphOptions, PanelOptions, PanelState, PanelType, SeriesView};
+    use crate::app::{
+        DatasourceRegistry, GraphOptions, PanelOptions, PanelState, PanelType, SeriesView,
+    };
     use crate::export::ExportOptions;
     use crate::prom;
     use crate::theme::Theme;
@@ -365,7 +475,7 @@ mod tests {
 
     fn test_app() -> AppState {
         AppState::new(
-            prom::PromClient::new("http://localhost:9090".to_string()),
+            DatasourceRegistry::single(prom::PromClient::new("http://localhost:9090".to_string())),
             Duration::from_secs(3600),
             Duration::from_secs(60),
             Duration::from_millis(1000),
@@ -384,138 +494,248 @@ mod tests {
             exprs: vec![],
             legends: vec![],
             query_modes: vec![],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: crate::app::QueryResolution::default(),
             series: vec![
                 SeriesView {
                     name: "a".to_string(),
                     value: Some(1.0),
-                    points: vec![],
                     visible: true,
+                    ..Default::default()
                 },
                 SeriesView {
                     name: "b".to_string(),
                     value: Some(2.0),
-                    points: vec![],
                     visible: false,
+                    ..Default::default()
                 },
             ],
             last_error: None,
             last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
             last_samples: 0,
             grid: None,
             y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
             panel_type: PanelType::Graph,
             thresholds: None,
             min: None,
             max: None,
             autogrid: None,
             display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
             options: PanelOptions::Graph(GraphOptions::default()),
+            repeat: None,
+            scoped_vars: std::collections::HashMap::new(),
         }
     }
 
-    #[tokio::test]
-    async fn normal_navigation_updates_selected_panel() {
+    #[test]
+    fn normal_navigation_updates_selected_panel() {
         let mut app = test_app();
 
-        handle_key(key(KeyCode::Char('j')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('j')), &mut app).unwrap();
         assert_eq!(app.selected_panel, 1);
 
-        handle_key(key(KeyCode::Char('k')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('k')), &mut app).unwrap();
         assert_eq!(app.selected_panel, 0);
     }
 
-    #[tokio::test]
-    async fn export_shortcuts_return_export_actions() {
+    #[test]
+    fn export_shortcuts_return_export_actions() {
         let mut app = test_app();
 
-        let action = handle_key(key(KeyCode::Char('e')), &mut app).await.unwrap();
+        let action = handle_key(key(KeyCode::Char('e')), &mut app).unwrap();
         assert_eq!(action, InputAction::ExportCurrent);
 
-        let action = handle_key(ctrl_key(KeyCode::Char('e')), &mut app)
-            .await
-            .unwrap();
+        let action = handle_key(ctrl_key(KeyCode::Char('e')), &mut app).unwrap();
         assert_eq!(action, InputAction::ToggleRecording);
     }
 
-    #[tokio::test]
-    async fn search_mode_e_keeps_typing_but_ctrl_e_toggles_recording() {
+    #[test]
+    fn search_mode_e_keeps_typing_but_ctrl_e_toggles_recording() {
         let mut app = test_app();
         app.mode = AppMode::Search;
 
-        let action = handle_key(key(KeyCode::Char('e')), &mut app).await.unwrap();
+        let action = handle_key(key(KeyCode::Char('e')), &mut app).unwrap();
         assert_eq!(action, InputAction::Redraw);
         assert_eq!(app.search_query, "e");
 
-        let action = handle_key(ctrl_key(KeyCode::Char('e')), &mut app)
-            .await
-            .unwrap();
+        let action = handle_key(ctrl_key(KeyCode::Char('e')), &mut app).unwrap();
         assert_eq!(action, InputAction::ToggleRecording);
         assert_eq!(app.search_query, "e");
     }
 
-    #[tokio::test]
-    async fn normal_digit_keys_toggle_series_and_zero_shows_all() {
+    #[test]
+    fn normal_digit_keys_toggle_series_and_zero_shows_all() {
         let mut app = test_app();
 
-        handle_key(key(KeyCode::Char('1')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('1')), &mut app).unwrap();
         assert!(!app.panels[0].series[0].visible);
 
-        handle_key(key(KeyCode::Char('0')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('0')), &mut app).unwrap();
         assert!(app.panels[0].series.iter().all(|series| series.visible));
     }
 
-    #[tokio::test]
-    async fn search_keys_update_query_results_and_selection() {
+    #[test]
+    fn search_keys_update_query_results_and_selection() {
         let mut app = test_app();
 
-        handle_key(key(KeyCode::Char('/')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('/')), &mut app).unwrap();
         assert_eq!(app.mode, AppMode::Search);
 
-        handle_key(key(KeyCode::Char('m')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('m')), &mut app).unwrap();
         assert_eq!(app.search_query, "m");
         assert_eq!(app.search_results, vec![1]);
 
-        handle_key(key(KeyCode::Backspace), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Backspace), &mut app).unwrap();
         assert!(app.search_query.is_empty());
         assert!(app.search_results.is_empty());
 
-        handle_key(key(KeyCode::Char('c')), &mut app).await.unwrap();
-        handle_key(key(KeyCode::Enter), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('c')), &mut app).unwrap();
+        handle_key(key(KeyCode::Enter), &mut app).unwrap();
         assert_eq!(app.selected_panel, 0);
         assert_eq!(app.mode, AppMode::Fullscreen);
 
-        handle_key(key(KeyCode::Esc), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Esc), &mut app).unwrap();
         assert_eq!(app.mode, AppMode::Normal);
     }
 
-    #[tokio::test]
-    async fn fullscreen_keys_update_mode_and_selection() {
+    #[test]
+    fn fullscreen_keys_update_mode_and_selection() {
         let mut app = test_app();
         app.mode = AppMode::Fullscreen;
 
-        handle_key(key(KeyCode::PageDown), &mut app).await.unwrap();
+        handle_key(key(KeyCode::PageDown), &mut app).unwrap();
         assert_eq!(app.selected_panel, 1);
 
-        handle_key(key(KeyCode::PageUp), &mut app).await.unwrap();
+        handle_key(key(KeyCode::PageUp), &mut app).unwrap();
         assert_eq!(app.selected_panel, 0);
 
-        handle_key(key(KeyCode::Char('v')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('v')), &mut app).unwrap();
         assert_eq!(app.mode, AppMode::FullscreenInspect);
         assert!(app.cursor_x.is_some());
 
-        handle_key(key(KeyCode::Esc), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Esc), &mut app).unwrap();
         assert_eq!(app.mode, AppMode::Fullscreen);
         assert!(app.cursor_x.is_none());
     }
 
     #[tokio::test]
-    async fn shared_keys_toggle_autogrid_and_y_axis_mode() {
+    async fn datasource_key_cycles_default_and_datasource_vars() {
+        let mut app = test_app();
+        app.datasources = DatasourceRegistry::new(
+            vec![
+                crate::app::Datasource {
+                    name: "prod".to_string(),
+                    uid: None,
+                    client: prom::PromClient::new("http://127.0.0.1:9".to_string()),
+                },
+                crate::app::Datasource {
+                    name: "staging".to_string(),
+                    uid: None,
+                    client: prom::PromClient::new("http://127.0.0.1:9".to_string()),
+                },
+            ],
+            0,
+        )
+        .unwrap();
+        app.datasource_vars = vec!["datasource".to_string()];
+
+        handle_key(key(KeyCode::Char('d')), &mut app).unwrap();
+
+        assert_eq!(app.datasources.default_datasource().name, "staging");
+        assert_eq!(app.vars["datasource"].first(), Some("staging"));
+    }
+
+    #[tokio::test]
+    async fn variable_picker_keys_select_values_and_refresh() {
+        let mut app = test_app();
+        app.var_order = vec!["job".to_string(), "instance".to_string()];
+        app.vars
+            .insert("job".to_string(), crate::app::Variable::single("node"));
+        app.vars.insert(
+            "instance".to_string(),
+            crate::app::Variable {
+                current: vec!["a".to_string()],
+                multi: true,
+                options: vec!["a".to_string(), "b".to_string()],
+                ..Default::default()
+            },
+        );
+
+        handle_key(key(KeyCode::Char('$')), &mut app).unwrap();
+        assert_eq!(app.mode, AppMode::VariablePicker);
+        assert_eq!(app.var_picker.as_ref().unwrap().name, "job");
+
+        handle_key(key(KeyCode::Tab), &mut app).unwrap();
+        handle_key(key(KeyCode::Char('e')), &mut app).unwrap();
+        assert_eq!(app.var_picker.as_ref().unwrap().query, "e");
+        handle_key(key(KeyCode::Backspace), &mut app).unwrap();
+        handle_key(key(KeyCode::Down), &mut app).unwrap();
+        handle_key(key(KeyCode::Char(' ')), &mut app).unwrap();
+        handle_key(key(KeyCode::Enter), &mut app).unwrap();
+
+        assert_eq!(app.mode, AppMode::Normal);
+        assert!(app.var_picker.is_none());
+        assert_eq!(app.vars["instance"].current, vec!["a", "b"]);
+        assert!(app.is_refreshing());
+        assert!(app.panels.iter().all(|p| p.stale));
+    }
+
+    #[tokio::test]
+    async fn filter_editor_keys_replace_filters_and_refresh() {
         let mut app = test_app();
+        app.filters = vec![crate::app::LabelMatcher::new(
+            "cluster",
+            crate::app::MatchOp::Equal,
+            "eu-1",
+        )];
 
-        handle_key(key(KeyCode::Char('g')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('F')), &mut app).unwrap();
+        assert_eq!(app.mode, AppMode::FilterEditor);
+        assert_eq!(app.filter_input, r#"cluster="eu-1""#);
+
+        for c in ", env".chars() {
+            handle_key(key(KeyCode::Char(c)), &mut app).unwrap();
+        }
+        handle_key(key(KeyCode::Enter), &mut app).unwrap();
+        assert_eq!(app.mode, AppMode::FilterEditor);
+        assert!(app.filter_error.is_some());
+        assert!(!app.is_refreshing());
+
+        for c in "!=dev".chars() {
+            handle_key(key(KeyCode::Char(c)), &mut app).unwrap();
+        }
+        handle_key(key(KeyCode::Enter), &mut app).unwrap();
+
+        assert_eq!(app.mode, AppMode::Normal);
+        assert_eq!(
+            crate::app::format_matchers(&app.filters),
+            r#"cluster="eu-1", env!="dev""#
+        );
+        assert!(app.is_refreshing());
+        assert!(app.panels.iter().all(|p| p.stale));
+    }
+
+    #[test]
+    fn shared_keys_toggle_autogrid_and_y_axis_mode() {
+        let mut app = test_app();
+
+        handle_key(key(KeyCode::Char('g')), &mut app).unwrap();
         assert!(!app.autogrid_enabled);
 
-        handle_key(key(KeyCode::Char('y')), &mut app).await.unwrap();
+        handle_key(key(KeyCode::Char('y')), &mut app).unwrap();
         assert_eq!(app.panels[0].y_axis_mode, YAxisMode::ZeroBased);
+
+        handle_key(key(KeyCode::Char('Y')), &mut app).unwrap();
+        handle_key(key(KeyCode::Char('Y')), &mut app).unwrap();
+        assert_eq!(
+            app.panels[0].graph_options().scale,
+            crate::app::GraphScale::Log { base: 2.0 }
+        );
     }
 }
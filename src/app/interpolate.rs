@@ -0,0 +1,469 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Grafana variable interpolation.
+//!
+//! Expressions are split into literal text and variable references
+//! (`$var`, `${var}`, `${var:format}` and the legacy `[[var]]` /
+//! `[[var:format]]`), then each reference is replaced with its formatted
+//! value. Names are read greedily, so `$instance` never expands `$inst`.
+
+use super::state::TextMode;
+use super::variables::{Variable, regex_escape};
+
+/// A piece of an expression: literal text or a variable reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    Variable(VariableRef<'a>),
+}
+
+/// A variable reference found in an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VariableRef<'a> {
+    /// The reference as written, e.g. `${instance:csv}`.
+    pub(crate) raw: &'a str,
+    /// Byte offset of the reference in the expression.
+    pub(crate) start: usize,
+    pub(crate) name: &'a str,
+    /// Format modifier, without any format arguments.
+    pub(crate) format: Option<&'a str>,
+}
+
+/// Grafana variable format modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VariableFormat {
+    Csv,
+    Distributed,
+    DoubleQuote,
+    Glob,
+    Html,
+    Json,
+    Lucene,
+    PercentEncode,
+    Pipe,
+    QueryParam,
+    Raw,
+    Regex,
+    SingleQuote,
+    SqlString,
+    Text,
+}
+
+impl VariableFormat {
+    pub(crate) fn parse(format: &str) -> Option<Self> {
+        let format = match format {
+            "csv" => Self::Csv,
+            "distributed" => Self::Distributed,
+            "doublequote" => Self::DoubleQuote,
+            "glob" => Self::Glob,
+            "html" => Self::Html,
+            "json" => Self::Json,
+            "lucene" => Self::Lucene,
+            "percentencode" => Self::PercentEncode,
+            "pipe" => Self::Pipe,
+            "queryparam" => Self::QueryParam,
+            "raw" => Self::Raw,
+            "regex" => Self::Regex,
+            "singlequote" => Self::SingleQuote,
+            "sqlstring" => Self::SqlString,
+            "text" => Self::Text,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Formats the values of variable `name`.
+    ///
+    /// `array` tells whether the variable holds a list, as multi-value and
+    /// "All" variables do in Grafana, which changes how some formats wrap
+    /// a single value.
+    fn apply(self, name: &str, values: &[String], array: bool) -> String {
+        let quoted = |quote: char, escaped: &str| {
+            values
+                .iter()
+                .map(|v| {
+                    let v = v.replace(quote, escaped);
+                    format!("{quote}{v}{quote}")
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+
+        match self {
+            Self::Csv | Self::Raw => values.join(","),
+            Self::Pipe => values.join("|"),
+            Self::Distributed => match values {
+                [] => String::new(),
+                [first, rest @ ..] => std::iter::once(first.clone())
+                    .chain(rest.iter().map(|v| format!("{name}={v}")))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            },
+            Self::DoubleQuote => quoted('"', "\\\""),
+            Self::SingleQuote => quoted('\'', "\\'"),
+            Self::SqlString => quoted('\'', "''"),
+            Self::Glob => match values {
+                [value] => value.clone(),
+                values => format!("{{{}}}", values.join(",")),
+            },
+            Self::Html => html_escape(&values.join(", ")),
+            Self::Json => {
+                if array {
+                    serde_json::to_string(values).unwrap_or_default()
+                } else {
+                    serde_json::to_string(values.first().map_or("", String::as_str))
+                        .unwrap_or_default()
+                }
+            }
+            Self::Lucene => match values {
+                [value] => lucene_escape(value),
+                values => format!(
+                    "({})",
+                    values
+                        .iter()
+                        .map(|v| format!("\"{}\"", lucene_escape(v)))
+                        .collect::<Vec<_>>()
+                        .join(" OR ")
+                ),
+            },
+            Self::PercentEncode => {
+                if array {
+                    urlencoding::encode(&format!("{{{}}}", values.join(","))).into_owned()
+                } else {
+                    values
+                        .first()
+                        .map(|v| urlencoding::encode(v).into_owned())
+                        .unwrap_or_default()
+                }
+            }
+            Self::QueryParam => values
+                .iter()
+                .map(|v| format!("var-{name}={}", urlencoding::encode(v)))
+                .collect::<Vec<_>>()
+                .join("&"),
+            Self::Regex => match values {
+                [value] => regex_escape(value),
+                values => format!(
+                    "({})",
+                    values
+                        .iter()
+                        .map(|v| regex_escape(v))
+                        .collect::<Vec<_>>()
+                        .join("|")
+                ),
+            },
+            Self::Text => values.join(" + "),
+        }
+    }
+}
+
+/// Splits `expr` into literal text and variable references.
+pub(crate) fn tokenize(expr: &str) -> Vec<Token<'_>> {
+    let bytes = expr.as_bytes();
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        let Some(end) = reference_end(expr, idx) else {
+            idx += 1;
+            continue;
+        };
+        if let Some(reference) = parse_reference(&expr[idx..end], idx) {
+            if text_start < idx {
+                tokens.push(Token::Text(&expr[text_start..idx]));
+            }
+            tokens.push(Token::Variable(reference));
+            text_start = end;
+            idx = end;
+        } else {
+            idx += 1;
+        }
+    }
+    if text_start < expr.len() {
+        tokens.push(Token::Text(&expr[text_start..]));
+    }
+    tokens
+}
+
+/// End of the reference starting at byte `idx`, if one starts there.
+fn reference_end(expr: &str, idx: usize) -> Option<usize> {
+    // `None` inside multi-byte characters, which never start a reference.
+    let rest = expr.get(idx..)?;
+    if let Some(inner) = rest.strip_prefix("${") {
+        return inner.find('}').map(|close| idx + 2 + close + 1);
+    }
+    if let Some(inner) = rest.strip_prefix("[[") {
+        return inner.find("]]").map(|close| idx + 2 + close + 2);
+    }
+    if let Some(inner) = rest.strip_prefix('$') {
+        let len = inner
+            .find(|ch: char| !is_name_char(ch))
+            .unwrap_or(inner.len());
+        return (len > 0).then_some(idx + 1 + len);
+    }
+    None
+}
+
+fn parse_reference(raw: &str, start: usize) -> Option<VariableRef<'_>> {
+    let inner = raw
+        .strip_prefix("${")
+        .and_then(|inner| inner.strip_suffix('}'))
+        .or_else(|| {
+            raw.strip_prefix("[[")
+                .and_then(|inner| inner.strip_suffix("]]"))
+        });
+    let (name, format) = match inner {
+        Some(inner) => match inner.split_once(':') {
+            Some((name, format)) => {
+                // Arguments such as `${__from:date:iso}` are not used.
+                let format = format.split(':').next().unwrap_or_default();
+                (name, Some(format))
+            }
+            None => (inner, None),
+        },
+        None => (&raw[1..], None),
+    };
+    if name.is_empty() || !name.chars().all(is_name_char) {
+        return None;
+    }
+    Some(VariableRef {
+        raw,
+        start,
+        name,
+        format,
+    })
+}
+
+fn is_name_char(ch: char) -> bool {
+    ch == '_' || ch.is_ascii_alphanumeric()
+}
+
+/// Replaces every variable reference in `expr` with its formatted value.
+///
+/// References to unknown variables, or to variables without a value, are
+/// left as written. Unknown format modifiers fall back to the default
+/// Prometheus formatting.
+pub(crate) fn interpolate<'v>(expr: &str, lookup: impl Fn(&str) -> Option<&'v Variable>) -> String {
+    interpolate_as(expr, None, lookup)
+}
+
+/// Replaces every variable reference in text panel content shown in `mode`.
+///
+/// In Markdown and HTML, references without a format modifier use Grafana's
+/// `html` format, which joins several values with `, ` and escapes markup;
+/// rendering decodes the escapes again. Code is shown verbatim, so it gets
+/// the `raw` values.
+pub(crate) fn interpolate_text<'v>(
+    text: &str,
+    mode: TextMode,
+    lookup: impl Fn(&str) -> Option<&'v Variable>,
+) -> String {
+    let format = match mode {
+        TextMode::Markdown | TextMode::Html => VariableFormat::Html,
+        TextMode::Code => VariableFormat::Raw,
+    };
+    interpolate_as(text, Some(format), lookup)
+}
+
+fn interpolate_as<'v>(
+    expr: &str,
+    default: Option<VariableFormat>,
+    lookup: impl Fn(&str) -> Option<&'v Variable>,
+) -> String {
+    let mut out = String::with_capacity(expr.len());
+    for token in tokenize(expr) {
+        match token {
+            Token::Text(text) => out.push_str(text),
+            Token::Variable(reference) => {
+                let value = lookup(reference.name).and_then(|var| {
+                    let format = reference.format.and_then(VariableFormat::parse).or(default);
+                    format_variable(reference.name, var, format)
+                });
+                out.push_str(value.as_deref().unwrap_or(reference.raw));
+            }
+        }
+    }
+    out
+}
+
+/// Formats `var` with `format`, or the default Prometheus formatting.
+pub(crate) fn format_variable(
+    name: &str,
+    var: &Variable,
+    format: Option<VariableFormat>,
+) -> Option<String> {
+    let Some(format) = format else {
+        return var.interpolate();
+    };
+    if format == VariableFormat::Text {
+        return Some(var.display());
+    }
+    if var.all {
+        if let Some(all_value) = &var.all_value {
+            return Some(all_value.clone());
+        }
+        if var.options.is_empty() {
+            return Some(".*".to_string());
+        }
+    }
+
+    let values = var.values();
+    if values.is_empty() {
+        return None;
+    }
+    Some(format.apply(name, values, var.multi || var.include_all))
+}
+
+/// Escapes the characters HTML gives a meaning to.
+fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Escapes Lucene query syntax characters.
+fn lucene_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        if ch.is_whitespace() || "!*+-=<>&|()[]{}^~?:\\/\"".contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn vars() -> HashMap<String, Variable> {
+        HashMap::from([
+            ("inst".to_string(), Variable::single("short")),
+            ("instance".to_string(), Variable::single("node-1:9100")),
+            (
+                "job".to_string(),
+                Variable::with_values(vec!["api.v1".to_string(), "db".to_string()]),
+            ),
+        ])
+    }
+
+    fn expand(expr: &str) -> String {
+        let vars = vars();
+        interpolate(expr, |name| vars.get(name))
+    }
+
+    #[test]
+    fn test_tokenize_reads_longest_names_and_formats() {
+        let tokens = tokenize("a{i=\"$instance\"} ${job:csv:x} [[inst]] $ ${}");
+        let refs: Vec<_> = tokens
+            .iter()
+            .filter_map(|token| match token {
+                Token::Variable(reference) => Some((reference.name, reference.format)),
+                Token::Text(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            refs,
+            vec![("instance", None), ("job", Some("csv")), ("inst", None)]
+        );
+        assert_eq!(
+            tokens.last(),
+            Some(&Token::Text(" $ ${}")),
+            "incomplete references stay literal text"
+        );
+    }
+
+    #[test]
+    fn test_interpolate_does_not_clobber_longer_names() {
+        assert_eq!(expand("$instance/$inst/${inst}"), "node-1:9100/short/short");
+        assert_eq!(expand("$instance_name"), "$instance_name");
+        assert_eq!(expand("µs{i=\"$inst\"}"), "µs{i=\"short\"}");
+    }
+
+    #[test]
+    fn test_interpolate_format_modifiers() {
+        let cases = [
+            ("${job}", r"(api\\.v1|db)"),
+            ("${job:regex}", r"(api\\.v1|db)"),
+            ("${job:pipe}", "api.v1|db"),
+            ("${job:csv}", "api.v1,db"),
+            ("${job:raw}", "api.v1,db"),
+            ("${job:json}", r#"["api.v1","db"]"#),
+            ("${job:glob}", "{api.v1,db}"),
+            ("${job:distributed}", "api.v1,job=db"),
+            ("${job:doublequote}", r#""api.v1","db""#),
+            ("${job:singlequote}", "'api.v1','db'"),
+            ("${job:sqlstring}", "'api.v1','db'"),
+            ("${job:lucene}", r#"("api.v1" OR "db")"#),
+            ("${job:percentencode}", "%7Bapi.v1%2Cdb%7D"),
+            ("${job:queryparam}", "var-job=api.v1&var-job=db"),
+            ("${job:text}", "api.v1 + db"),
+            ("${job:html}", "api.v1, db"),
+            ("[[job:pipe]]", "api.v1|db"),
+            ("${instance:regex}", "node-1:9100"),
+            ("${instance:json}", r#""node-1:9100""#),
+            ("${instance:lucene}", r"node\-1\:9100"),
+            ("${instance:percentencode}", "node-1%3A9100"),
+            ("${instance:bogus}", "node-1:9100"),
+            ("${missing:csv}", "${missing:csv}"),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(expand(expr), expected, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_interpolate_text_escapes_html_only_outside_code() {
+        let vars = HashMap::from([("q".to_string(), Variable::single("a < b & c"))]);
+        let text = |mode| interpolate_text("q: $q", mode, |name| vars.get(name));
+
+        assert_eq!(text(TextMode::Markdown), "q: a &lt; b &amp; c");
+        assert_eq!(text(TextMode::Html), "q: a &lt; b &amp; c");
+        assert_eq!(text(TextMode::Code), "q: a < b & c");
+    }
+
+    #[test]
+    fn test_format_all_uses_all_value_verbatim() {
+        let var = Variable {
+            all: true,
+            include_all: true,
+            all_value: Some(".+".to_string()),
+            options: vec!["a".to_string()],
+            ..Variable::default()
+        };
+        assert_eq!(
+            format_variable("v", &var, Some(VariableFormat::Csv)).as_deref(),
+            Some(".+")
+        );
+        assert_eq!(
+            format_variable("v", &var, Some(VariableFormat::Text)).as_deref(),
+            Some("All")
+        );
+    }
+}
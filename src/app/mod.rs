@@ -15,16 +15,34 @@
  */
 
 mod data;
+mod datasources;
 mod event_loop;
 mod input;
+mod interpolate;
+mod overrides;
+mod promql;
+mod reduce;
+mod refresh;
+mod repeat;
 mod state;
 mod variables;
 
 pub(crate) use data::{default_queries, parse_duration};
+pub(crate) use datasources::{Datasource, DatasourceRegistry};
 pub(crate) use event_loop::run_app;
+pub(crate) use interpolate::{Token, VariableFormat, interpolate_text, tokenize};
+pub(crate) use overrides::{FieldMatcher, FieldOverride, OverrideProperty};
+pub(crate) use promql::{LabelMatcher, MatchOp, format_matchers};
+pub(crate) use reduce::{FieldSelector, ReduceOptions, Reducer};
 #[allow(unused_imports)]
 pub(crate) use state::{
-    AppMode, AppState, GraphAxisPlacement, GraphDrawStyle, GraphOptions, GraphPointMode,
-    GraphStackingMode, GridUnit, PanelOptions, PanelState, PanelType, QueryMode, SeriesView,
-    ThresholdMode, ThresholdStep, Thresholds, YAxisMode,
+    AppMode, AppState, DEFAULT_STACKING_GROUP, GraphAxisPlacement, GraphDrawStyle,
+    GraphLineInterpolation, GraphOptions, GraphPointMode, GraphScale, GraphStackingMode, GridUnit,
+    HeatmapColor, HeatmapColorMode, HeatmapOptions, LegendDisplayMode, LegendOptions,
+    LegendPlacement, NullSample, PanelOptions, PanelRepeat, PanelState, PanelType, QueryMode,
+    QueryResolution, RepeatDirection, SeriesView, SpanNulls, TextMode, TextOptions, ThresholdMode,
+    ThresholdStep, Thresholds, YAxisMode,
+};
+pub(crate) use variables::{
+    ALL_VALUE, AUTO_INTERVAL, Variable, VariableKind, VariablePicker, option_label,
 };
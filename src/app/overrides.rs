@@ -0,0 +1,235 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::state::{GraphAxisPlacement, GraphStackingMode, SeriesView, Thresholds};
+use crate::ui::{DisplayFormat, GrafanaRegex, ValueMapping};
+use ratatui::style::Color;
+
+/// A Grafana field override: properties set on the series its matcher selects.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FieldOverride {
+    pub(crate) matcher: FieldMatcher,
+    pub(crate) properties: Vec<OverrideProperty>,
+}
+
+/// Selects the series a field override applies to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FieldMatcher {
+    /// `byName`: the series with this name.
+    Name(String),
+    /// `byNames`: the listed series, or every other series when `exclude` is set.
+    Names { names: Vec<String>, exclude: bool },
+    /// `byRegexp`: series whose name matches.
+    Regex(GrafanaRegex),
+    /// `byFrameRefID`: series returned by the target with this `refId`.
+    RefId(String),
+    /// `byType`: series whose values have this field type. Prometheus series
+    /// are `number` fields.
+    Type(String),
+}
+
+/// A field override property supported by Grafatui.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OverrideProperty {
+    DisplayName(String),
+    Unit(String),
+    Decimals(usize),
+    NoValue(String),
+    Mappings(Vec<ValueMapping>),
+    /// A `fixed` color mode color.
+    Color(Color),
+    AxisPlacement(GraphAxisPlacement),
+    /// `custom.stacking`: the stacking mode and group of the series.
+    Stacking {
+        mode: GraphStackingMode,
+        group: String,
+    },
+    /// `custom.hideFrom`: hides the series from the legend or the chart.
+    HideFrom {
+        legend: bool,
+        viz: bool,
+    },
+    Thresholds(Thresholds),
+}
+
+/// Field override properties resolved for one series.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SeriesOverrides {
+    /// Replaces the panel display format when the unit, decimals, noValue or
+    /// mappings are overridden.
+    pub(crate) display: Option<DisplayFormat>,
+    pub(crate) color: Option<Color>,
+    pub(crate) axis_placement: Option<GraphAxisPlacement>,
+    pub(crate) stacking: Option<GraphStackingMode>,
+    pub(crate) stacking_group: Option<String>,
+    pub(crate) thresholds: Option<Thresholds>,
+    pub(crate) hide_from_legend: bool,
+}
+
+impl FieldMatcher {
+    fn matches(&self, series: &SeriesView) -> bool {
+        match self {
+            Self::Name(name) => series.name == *name,
+            Self::Names { names, exclude } => names.contains(&series.name) != *exclude,
+            Self::Regex(regex) => regex.is_match(&series.name),
+            Self::RefId(ref_id) => series.ref_id == *ref_id,
+            Self::Type(kind) => kind == "number",
+        }
+    }
+}
+
+/// Applies field overrides to freshly queried series. Overrides apply in
+/// order, so a later one wins for the same property. Series are matched by
+/// their queried names, before a `displayName` override renames them.
+pub(crate) fn apply_overrides(
+    overrides: &[FieldOverride],
+    display: &DisplayFormat,
+    series: &mut [SeriesView],
+) {
+    for s in series {
+        let matched: Vec<_> = overrides
+            .iter()
+            .filter(|o| o.matcher.matches(s))
+            .flat_map(|o| &o.properties)
+            .collect();
+        for property in matched {
+            let resolved = &mut s.overrides;
+            match property {
+                OverrideProperty::DisplayName(name) => s.name = name.clone(),
+                OverrideProperty::Unit(unit) => {
+                    series_display(resolved, display).unit = Some(unit.clone());
+                }
+                OverrideProperty::Decimals(decimals) => {
+                    series_display(resolved, display).decimals = Some(*decimals);
+                }
+                OverrideProperty::NoValue(text) => {
+                    series_display(resolved, display).no_value = Some(text.clone());
+                }
+                OverrideProperty::Mappings(mappings) => {
+                    series_display(resolved, display).mappings = mappings.clone();
+                }
+                OverrideProperty::Color(color) => resolved.color = Some(*color),
+                OverrideProperty::AxisPlacement(placement) => {
+                    resolved.axis_placement = Some(*placement);
+                }
+                OverrideProperty::Stacking { mode, group } => {
+                    resolved.stacking = Some(*mode);
+                    resolved.stacking_group = Some(group.clone());
+                }
+                OverrideProperty::HideFrom { legend, viz } => {
+                    resolved.hide_from_legend = *legend;
+                    s.visible = !viz;
+                }
+                OverrideProperty::Thresholds(thresholds) => {
+                    resolved.thresholds = Some(thresholds.clone());
+                }
+            }
+        }
+    }
+}
+
+fn series_display<'a>(
+    resolved: &'a mut SeriesOverrides,
+    display: &DisplayFormat,
+) -> &'a mut DisplayFormat {
+    resolved.display.get_or_insert_with(|| display.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(name: &str, ref_id: &str) -> SeriesView {
+        SeriesView {
+            name: name.to_string(),
+            value: Some(1.0),
+            visible: true,
+            ref_id: ref_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_overrides_apply_to_matching_series_in_order() {
+        let overrides = vec![
+            FieldOverride {
+                matcher: FieldMatcher::RefId("B".to_string()),
+                properties: vec![
+                    OverrideProperty::Unit("percent".to_string()),
+                    OverrideProperty::Color(Color::Red),
+                ],
+            },
+            FieldOverride {
+                matcher: FieldMatcher::Regex(GrafanaRegex::new("disk.*").unwrap()),
+                properties: vec![
+                    OverrideProperty::Color(Color::Blue),
+                    OverrideProperty::DisplayName("Disk".to_string()),
+                ],
+            },
+            FieldOverride {
+                matcher: FieldMatcher::Names {
+                    names: vec!["disk_used".to_string(), "mem".to_string()],
+                    exclude: true,
+                },
+                properties: vec![OverrideProperty::HideFrom {
+                    legend: true,
+                    viz: true,
+                }],
+            },
+        ];
+        let display = DisplayFormat {
+            unit: Some("bytes".to_string()),
+            ..Default::default()
+        };
+        let mut all = vec![
+            series("mem", "A"),
+            series("disk_used", "B"),
+            series("cpu", "C"),
+        ];
+
+        apply_overrides(&overrides, &display, &mut all);
+
+        assert_eq!(all[0].overrides, SeriesOverrides::default());
+        assert!(all[0].visible);
+
+        assert_eq!(all[1].name, "Disk");
+        assert_eq!(all[1].overrides.color, Some(Color::Blue));
+        let disk_display = all[1].overrides.display.as_ref().unwrap();
+        assert_eq!(disk_display.unit.as_deref(), Some("percent"));
+        // Matched by the queried name, not the display name it got.
+        assert!(all[1].visible);
+
+        assert!(!all[2].visible);
+        assert!(all[2].overrides.hide_from_legend);
+    }
+
+    #[test]
+    fn test_type_matcher_selects_number_fields() {
+        let mut all = vec![series("up", "A")];
+        let overrides = |kind: &str| {
+            vec![FieldOverride {
+                matcher: FieldMatcher::Type(kind.to_string()),
+                properties: vec![OverrideProperty::Decimals(3)],
+            }]
+        };
+
+        apply_overrides(&overrides("time"), &DisplayFormat::default(), &mut all);
+        assert_eq!(all[0].overrides.display, None);
+
+        apply_overrides(&overrides("number"), &DisplayFormat::default(), &mut all);
+        assert_eq!(all[0].overrides.display.as_ref().unwrap().decimals, Some(3));
+    }
+}
@@ -0,0 +1,493 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Ad-hoc filter injection into PromQL queries.
+//!
+//! Filters are label matchers added to every vector selector of a query. A
+//! small lexer locates the selectors, so string literals, function names,
+//! grouping labels and range durations are never rewritten.
+
+use anyhow::{Result, anyhow, bail};
+use std::fmt;
+
+/// Label matching operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MatchOp {
+    Equal,
+    NotEqual,
+    Regex,
+    NotRegex,
+}
+
+impl MatchOp {
+    /// Operators in the order they must be tried when parsing.
+    const ALL: [MatchOp; 4] = [
+        MatchOp::Regex,
+        MatchOp::NotRegex,
+        MatchOp::NotEqual,
+        MatchOp::Equal,
+    ];
+
+    /// Parses a PromQL matching operator such as `=~`.
+    pub(crate) fn parse(op: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == op)
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            MatchOp::Equal => "=",
+            MatchOp::NotEqual => "!=",
+            MatchOp::Regex => "=~",
+            MatchOp::NotRegex => "!~",
+        }
+    }
+}
+
+/// A label matcher such as `cluster="eu-1"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LabelMatcher {
+    pub(crate) name: String,
+    pub(crate) op: MatchOp,
+    pub(crate) value: String,
+}
+
+impl LabelMatcher {
+    pub(crate) fn new(name: impl Into<String>, op: MatchOp, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            op,
+            value: value.into(),
+        }
+    }
+
+    /// Parses `name<op>value`, e.g. `cluster=eu-1` or `env!~"dev.*"`.
+    ///
+    /// The value may be quoted; unquoted values are taken verbatim.
+    pub(crate) fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let name_end = s
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(s.len());
+        let name = &s[..name_end];
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            bail!("invalid label name in filter `{s}`");
+        }
+
+        let rest = s[name_end..].trim_start();
+        let (op, value) = MatchOp::ALL
+            .into_iter()
+            .find_map(|op| rest.strip_prefix(op.as_str()).map(|value| (op, value)))
+            .ok_or_else(|| anyhow!("expected `=`, `!=`, `=~` or `!~` after `{name}` in `{s}`"))?;
+
+        let value = value.trim();
+        let quoted =
+            value.len() >= 2 && value.starts_with(['"', '\'', '`']) && value.ends_with(&value[..1]);
+        let value = if quoted {
+            unquote(value)
+        } else {
+            value.to_string()
+        };
+        Ok(Self::new(name, op, value))
+    }
+}
+
+impl fmt::Display for LabelMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self
+            .value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        write!(f, "{}{}\"{}\"", self.name, self.op.as_str(), value)
+    }
+}
+
+/// Parses a comma-separated list of matchers, as typed in the filter editor.
+///
+/// Commas inside quoted values do not split matchers. An empty list is valid.
+pub(crate) fn parse_matchers(s: &str) -> Result<Vec<LabelMatcher>> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (idx, c) in s.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(q) if c == '\\' && q != '`' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if matches!(c, '"' | '\'' | '`') => quote = Some(c),
+            None if c == ',' => {
+                items.push(&s[start..idx]);
+                start = idx + 1;
+            }
+            None => {}
+        }
+    }
+    items.push(&s[start..]);
+
+    items
+        .into_iter()
+        .filter(|item| !item.trim().is_empty())
+        .map(LabelMatcher::parse)
+        .collect()
+}
+
+/// Formats matchers the way they are written inside a selector.
+pub(crate) fn format_matchers(matchers: &[LabelMatcher]) -> String {
+    matchers
+        .iter()
+        .map(LabelMatcher::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Adds `matchers` to every vector selector in `expr`.
+///
+/// Selectors that already carry an identical matcher are left alone.
+/// Malformed queries are rewritten as far as they can be lexed; Prometheus
+/// reports the syntax error.
+pub(crate) fn inject_matchers(expr: &str, matchers: &[LabelMatcher]) -> String {
+    if matchers.is_empty() {
+        return expr.to_string();
+    }
+
+    let tokens = lex(expr);
+    let mut inserts: Vec<(usize, String)> = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let next = tokens.get(i + 1).map(|t| t.kind);
+        match token.kind {
+            TokenKind::Ident => {
+                let word = token.text(expr);
+                if is_keyword(GROUPING_KEYWORDS, word) && next == Some(TokenKind::LeftParen) {
+                    // Label lists such as `by (job)` hold no selectors.
+                    i = closing(&tokens, i + 1) + 1;
+                    continue;
+                }
+                // Aggregations may put their grouping first: `sum by (job) (...)`.
+                let aggregation = tokens.get(i + 1).is_some_and(|next| {
+                    next.kind == TokenKind::Ident && is_keyword(&["by", "without"], next.text(expr))
+                });
+                let is_selector = !is_keyword(KEYWORDS, word)
+                    && !aggregation
+                    && next != Some(TokenKind::LeftParen)
+                    && next != Some(TokenKind::LeftBrace);
+                if is_selector {
+                    inserts.push((token.end, format!("{{{}}}", format_matchers(matchers))));
+                }
+            }
+            TokenKind::LeftBrace => {
+                let close = closing(&tokens, i);
+                let Some(close_token) = tokens.get(close) else {
+                    break;
+                };
+                let body = &tokens[i + 1..close];
+                let missing: Vec<LabelMatcher> = matchers
+                    .iter()
+                    .filter(|matcher| !has_matcher(expr, body, matcher))
+                    .cloned()
+                    .collect();
+                if !missing.is_empty() {
+                    let separator = match body.last() {
+                        None => "",
+                        Some(last) if last.kind == TokenKind::Comma => "",
+                        Some(_) => ", ",
+                    };
+                    inserts.push((
+                        close_token.start,
+                        format!("{separator}{}", format_matchers(&missing)),
+                    ));
+                }
+                i = close + 1;
+                continue;
+            }
+            TokenKind::LeftBracket => {
+                // Ranges and subquery resolutions.
+                i = closing(&tokens, i) + 1;
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    let mut out = expr.to_string();
+    for (pos, text) in inserts.into_iter().rev() {
+        out.insert_str(pos, &text);
+    }
+    out
+}
+
+/// Keywords introducing a parenthesized list of label names.
+const GROUPING_KEYWORDS: &[&str] = &[
+    "by",
+    "without",
+    "on",
+    "ignoring",
+    "group_left",
+    "group_right",
+];
+
+/// Identifiers that never name a metric.
+const KEYWORDS: &[&str] = &[
+    "and",
+    "or",
+    "unless",
+    "atan2",
+    "bool",
+    "offset",
+    "group_left",
+    "group_right",
+    "inf",
+    "nan",
+];
+
+fn is_keyword(keywords: &[&str], word: &str) -> bool {
+    keywords.iter().any(|kw| kw.eq_ignore_ascii_case(word))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Ident,
+    String,
+    Number,
+    LeftBrace,
+    RightBrace,
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    Operator,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn text(self, expr: &str) -> &str {
+        &expr[self.start..self.end]
+    }
+}
+
+fn lex(expr: &str) -> Vec<Token> {
+    let bytes = expr.as_bytes();
+    let is_ident_char = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b':';
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = bytes[i];
+        let kind = match c {
+            _ if c.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'"' | b'\'' | b'`' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != c {
+                    if bytes[i] == b'\\' && c != b'`' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i = (i + 1).min(bytes.len());
+                TokenKind::String
+            }
+            b'{' | b'}' | b'(' | b')' | b'[' | b']' | b',' => {
+                i += 1;
+                match c {
+                    b'{' => TokenKind::LeftBrace,
+                    b'}' => TokenKind::RightBrace,
+                    b'(' => TokenKind::LeftParen,
+                    b')' => TokenKind::RightParen,
+                    b'[' => TokenKind::LeftBracket,
+                    b']' => TokenKind::RightBracket,
+                    _ => TokenKind::Comma,
+                }
+            }
+            _ if c.is_ascii_digit()
+                || (c == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)) =>
+            {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                TokenKind::Number
+            }
+            _ if is_ident_char(c) => {
+                while i < bytes.len() && is_ident_char(bytes[i]) {
+                    i += 1;
+                }
+                TokenKind::Ident
+            }
+            _ => {
+                i += expr[i..].chars().next().map_or(1, char::len_utf8);
+                TokenKind::Operator
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: i,
+        });
+    }
+    tokens
+}
+
+/// Index of the token closing the bracket opened at `open`, or
+/// `tokens.len()` when it is never closed.
+fn closing(tokens: &[Token], open: usize) -> usize {
+    let (left, right) = match tokens[open].kind {
+        TokenKind::LeftBrace => (TokenKind::LeftBrace, TokenKind::RightBrace),
+        TokenKind::LeftBracket => (TokenKind::LeftBracket, TokenKind::RightBracket),
+        _ => (TokenKind::LeftParen, TokenKind::RightParen),
+    };
+    let mut depth = 0usize;
+    for (idx, token) in tokens.iter().enumerate().skip(open) {
+        if token.kind == left {
+            depth += 1;
+        } else if token.kind == right {
+            depth -= 1;
+            if depth == 0 {
+                return idx;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Whether the selector body `body` already contains `matcher`.
+fn has_matcher(expr: &str, body: &[Token], matcher: &LabelMatcher) -> bool {
+    body.split(|token| token.kind == TokenKind::Comma)
+        .any(|item| match item {
+            [name, .., value]
+                if name.kind == TokenKind::Ident && value.kind == TokenKind::String =>
+            {
+                name.text(expr) == matcher.name
+                    && expr[name.end..value.start].trim() == matcher.op.as_str()
+                    && unquote(value.text(expr)) == matcher.value
+            }
+            _ => false,
+        })
+}
+
+/// Strips the quotes from a PromQL string literal and resolves escapes.
+fn unquote(literal: &str) -> String {
+    let inner = literal
+        .get(1..literal.len().saturating_sub(1))
+        .unwrap_or_default();
+    if literal.starts_with('`') {
+        return inner.to_string();
+    }
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cluster() -> Vec<LabelMatcher> {
+        vec![LabelMatcher::new("cluster", MatchOp::Equal, "eu-1")]
+    }
+
+    #[test]
+    fn test_inject_matchers_into_selectors() {
+        let filters = cluster();
+        let cases = [
+            ("up", r#"up{cluster="eu-1"}"#),
+            (r#"up{job="node"}"#, r#"up{job="node", cluster="eu-1"}"#),
+            (r#"{__name__="up"}"#, r#"{__name__="up", cluster="eu-1"}"#),
+            (
+                "sum by (job) (rate(http_requests_total[5m:1m])) / on(job) group_left bool up",
+                r#"sum by (job) (rate(http_requests_total{cluster="eu-1"}[5m:1m])) / on(job) group_left bool up{cluster="eu-1"}"#,
+            ),
+            (
+                r#"label_replace(node:cpu:rate5m offset 1h, "dst", "up", "src", "(.*)")"#,
+                r#"label_replace(node:cpu:rate5m{cluster="eu-1"} offset 1h, "dst", "up", "src", "(.*)")"#,
+            ),
+            (
+                "histogram_quantile(0.9, sum(rate(x_bucket{le!=\"+Inf\",}[5m])) without (instance))",
+                "histogram_quantile(0.9, sum(rate(x_bucket{le!=\"+Inf\",cluster=\"eu-1\"}[5m])) without (instance))",
+            ),
+            ("vector(1) > Inf", "vector(1) > Inf"),
+            (r#"up{cluster="eu-1"}"#, r#"up{cluster="eu-1"}"#),
+        ];
+        for (expr, expected) in cases {
+            assert_eq!(inject_matchers(expr, &filters), expected, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_inject_matchers_escapes_values() {
+        let filters = vec![
+            LabelMatcher::new("path", MatchOp::Regex, r#"/a"b\.*"#),
+            LabelMatcher::new("env", MatchOp::NotEqual, "dev"),
+        ];
+        assert_eq!(
+            inject_matchers("up", &filters),
+            r#"up{path=~"/a\"b\\.*", env!="dev"}"#
+        );
+        assert_eq!(inject_matchers("up µs", &[]), "up µs");
+    }
+
+    #[test]
+    fn test_parse_matchers() {
+        let parsed = parse_matchers(r#"cluster=eu-1, env!~"dev,test", path=~'a\'b',"#).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                LabelMatcher::new("cluster", MatchOp::Equal, "eu-1"),
+                LabelMatcher::new("env", MatchOp::NotRegex, "dev,test"),
+                LabelMatcher::new("path", MatchOp::Regex, "a'b"),
+            ]
+        );
+        assert_eq!(
+            format_matchers(&parsed),
+            r#"cluster="eu-1", env!~"dev,test", path=~"a'b""#
+        );
+        assert!(parse_matchers("").unwrap().is_empty());
+        assert!(parse_matchers("cluster").is_err());
+        assert!(parse_matchers("1x=y").is_err());
+    }
+}
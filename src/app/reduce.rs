@@ -0,0 +1,261 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::ui::GrafanaRegex;
+
+/// A Grafana reducer, turning the samples of a series into one value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Reducer {
+    /// The latest sample, even when it is NaN.
+    Last,
+    LastNotNull,
+    First,
+    FirstNotNull,
+    Min,
+    Max,
+    Mean,
+    Sum,
+    /// Number of samples, including NaN ones.
+    Count,
+    Range,
+    /// Total increase, counting a drop as a counter reset.
+    Delta,
+    /// Last value minus first value.
+    Diff,
+    StdDev,
+    /// Number of times the value changes between samples.
+    ChangeCount,
+    DistinctCount,
+    /// The `pNN` percentile, 1 to 99.
+    Percentile(u8),
+}
+
+/// Grafana `options.reduceOptions` of Stat, Gauge, Bar Gauge and Table panels.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReduceOptions {
+    /// Reducers applied to each series. The first one gives the value of
+    /// single-value panels; tables show a column for each.
+    pub(crate) calcs: Vec<Reducer>,
+    /// Show every sample instead of reducing each series (`values`).
+    pub(crate) values: bool,
+    /// Most values shown when `values` is set.
+    pub(crate) limit: Option<usize>,
+    /// Series selected by `fields`; all series when unset.
+    pub(crate) fields: Option<FieldSelector>,
+}
+
+impl Default for ReduceOptions {
+    fn default() -> Self {
+        Self {
+            calcs: vec![Reducer::LastNotNull],
+            values: false,
+            limit: None,
+            fields: None,
+        }
+    }
+}
+
+/// Series selected by a non-default `reduceOptions.fields`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FieldSelector {
+    /// The series with this name.
+    Name(String),
+    /// Series whose name matches a `/pattern/`.
+    Regex(GrafanaRegex),
+}
+
+impl ReduceOptions {
+    /// Table column headers for the reduced values: one per reducer, or a
+    /// single `Value` column when there is one value per row.
+    pub(crate) fn column_labels(&self) -> Vec<String> {
+        if self.values || self.calcs.len() <= 1 {
+            vec!["Value".to_string()]
+        } else {
+            self.calcs.iter().map(|reducer| reducer.label()).collect()
+        }
+    }
+}
+
+impl FieldSelector {
+    pub(crate) fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Name(selected) => selected == name,
+            Self::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl Reducer {
+    /// Parses a Grafana reducer id such as `lastNotNull` or `p95`.
+    pub(crate) fn from_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "last" => Self::Last,
+            "lastNotNull" => Self::LastNotNull,
+            "first" => Self::First,
+            "firstNotNull" => Self::FirstNotNull,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            "mean" => Self::Mean,
+            "sum" => Self::Sum,
+            "count" => Self::Count,
+            "range" => Self::Range,
+            "delta" => Self::Delta,
+            "diff" => Self::Diff,
+            "stdDev" => Self::StdDev,
+            "changeCount" => Self::ChangeCount,
+            "distinctCount" => Self::DistinctCount,
+            _ => {
+                let percentile = id.strip_prefix('p')?.parse().ok()?;
+                if !(1..=99).contains(&percentile) {
+                    return None;
+                }
+                Self::Percentile(percentile)
+            }
+        })
+    }
+
+    /// Grafana's display name of the reducer, e.g. `Last *` or `95th %`.
+    pub(crate) fn label(self) -> String {
+        match self {
+            Self::Last => "Last".to_string(),
+            Self::LastNotNull => "Last *".to_string(),
+            Self::First => "First".to_string(),
+            Self::FirstNotNull => "First *".to_string(),
+            Self::Min => "Min".to_string(),
+            Self::Max => "Max".to_string(),
+            Self::Mean => "Mean".to_string(),
+            Self::Sum => "Total".to_string(),
+            Self::Count => "Count".to_string(),
+            Self::Range => "Range".to_string(),
+            Self::Delta => "Delta".to_string(),
+            Self::Diff => "Difference".to_string(),
+            Self::StdDev => "StdDev".to_string(),
+            Self::ChangeCount => "Change count".to_string(),
+            Self::DistinctCount => "Distinct count".to_string(),
+            Self::Percentile(p) => format!("{p}th %"),
+        }
+    }
+
+    /// Reduces the samples of a series, oldest first. NaN and infinite
+    /// samples are treated as nulls, except by `Last`, `First` and `Count`.
+    pub(crate) fn reduce(self, samples: &[f64]) -> Option<f64> {
+        let finite: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        match self {
+            Self::Last => samples.last().copied(),
+            Self::LastNotNull => finite.last().copied(),
+            Self::First => samples.first().copied(),
+            Self::FirstNotNull => finite.first().copied(),
+            Self::Min => finite.iter().copied().reduce(f64::min),
+            Self::Max => finite.iter().copied().reduce(f64::max),
+            Self::Mean => mean(&finite),
+            Self::Sum => (!finite.is_empty()).then(|| finite.iter().sum()),
+            Self::Count => Some(samples.len() as f64),
+            Self::Range => {
+                let min = finite.iter().copied().reduce(f64::min)?;
+                let max = finite.iter().copied().reduce(f64::max)?;
+                Some(max - min)
+            }
+            Self::Delta => (!finite.is_empty()).then(|| {
+                finite
+                    .windows(2)
+                    .map(|pair| {
+                        if pair[1] < pair[0] {
+                            pair[1]
+                        } else {
+                            pair[1] - pair[0]
+                        }
+                    })
+                    .sum()
+            }),
+            Self::Diff => Some(finite.last()? - finite.first()?),
+            Self::StdDev => {
+                let mean = mean(&finite)?;
+                let variance =
+                    finite.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / finite.len() as f64;
+                Some(variance.sqrt())
+            }
+            Self::ChangeCount => {
+                Some(finite.windows(2).filter(|pair| pair[0] != pair[1]).count() as f64)
+            }
+            Self::DistinctCount => {
+                let mut sorted = finite;
+                sorted.sort_by(f64::total_cmp);
+                sorted.dedup();
+                Some(sorted.len() as f64)
+            }
+            Self::Percentile(p) => {
+                let mut sorted = finite;
+                if sorted.is_empty() {
+                    return None;
+                }
+                sorted.sort_by(f64::total_cmp);
+                let rank = f64::from(p) / 100.0 * (sorted.len() - 1) as f64;
+                Some(sorted[rank.round() as usize])
+            }
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reducer_ids_round_trip_grafana_names() {
+        assert_eq!(Reducer::from_id("lastNotNull"), Some(Reducer::LastNotNull));
+        assert_eq!(Reducer::from_id("p95"), Some(Reducer::Percentile(95)));
+        assert_eq!(Reducer::from_id("p100"), None);
+        assert_eq!(Reducer::from_id("allValues"), None);
+        assert_eq!(Reducer::Percentile(95).label(), "95th %");
+        assert_eq!(Reducer::Sum.label(), "Total");
+    }
+
+    #[test]
+    fn test_reducers_skip_nan_samples() {
+        let samples = [2.0, 4.0, f64::NAN, 4.0, 10.0, f64::NAN];
+        let reduce = |reducer: Reducer| reducer.reduce(&samples);
+
+        assert!(reduce(Reducer::Last).unwrap().is_nan());
+        assert_eq!(reduce(Reducer::LastNotNull), Some(10.0));
+        assert_eq!(reduce(Reducer::First), Some(2.0));
+        assert_eq!(reduce(Reducer::Min), Some(2.0));
+        assert_eq!(reduce(Reducer::Max), Some(10.0));
+        assert_eq!(reduce(Reducer::Mean), Some(5.0));
+        assert_eq!(reduce(Reducer::Sum), Some(20.0));
+        assert_eq!(reduce(Reducer::Count), Some(6.0));
+        assert_eq!(reduce(Reducer::Range), Some(8.0));
+        assert_eq!(reduce(Reducer::Diff), Some(8.0));
+        assert_eq!(reduce(Reducer::StdDev), Some(3.0));
+        assert_eq!(reduce(Reducer::ChangeCount), Some(2.0));
+        assert_eq!(reduce(Reducer::DistinctCount), Some(3.0));
+        assert_eq!(reduce(Reducer::Percentile(50)), Some(4.0));
+        assert_eq!(reduce(Reducer::Percentile(99)), Some(10.0));
+        // Panels without reduce options show the last finite sample.
+        let default = ReduceOptions::default().calcs[0];
+        assert_eq!(reduce(default), Some(10.0));
+    }
+
+    #[test]
+    fn test_delta_counts_counter_resets() {
+        assert_eq!(Reducer::Delta.reduce(&[10.0, 15.0, 3.0, 8.0]), Some(13.0));
+        assert_eq!(Reducer::Delta.reduce(&[]), None);
+        assert_eq!(Reducer::Mean.reduce(&[f64::NAN]), None);
+    }
+}
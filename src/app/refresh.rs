@@ -0,0 +1,446 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Background data refresh.
+//!
+//! Refreshes run in a tokio task that owns a snapshot of everything it needs
+//! and reports each panel as soon as its queries complete. Starting a new
+//! refresh aborts the previous one, so results from a superseded time window
+//! never reach the UI.
+
+use crate::app::data::{
+    PANEL_RESOLUTION_POINTS, downsample, expand_expr, format_legend, query_step, split_nulls,
+};
+use crate::app::datasources::DatasourceRegistry;
+use crate::app::overrides::SeriesOverrides;
+use crate::app::promql::{LabelMatcher, inject_matchers};
+use crate::app::state::{PanelState, QueryMode, SeriesView};
+use crate::app::variables::{Variable, refresh_query_variables};
+use crate::grafana::TemplateQueryVar;
+use crate::prom;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Maximum number of panels fetched concurrently.
+const MAX_CONCURRENT_PANELS: usize = 4;
+
+/// Progress reported by a background refresh.
+#[derive(Debug)]
+pub(crate) enum RefreshMessage {
+    /// Template variables resolved for this refresh.
+    Variables(HashMap<String, Variable>),
+    /// Fresh data for the panel at `index`.
+    Panel { index: usize, update: PanelUpdate },
+    /// Every panel has been reported; the data ends at `end_ts`.
+    Finished { end_ts: i64 },
+}
+
+/// Query results for a single panel.
+#[derive(Debug, Default)]
+pub(crate) struct PanelUpdate {
+    pub(crate) series: Vec<SeriesView>,
+    /// Last query URL used (for debugging).
+    pub(crate) url: Option<String>,
+    /// Coarsest range query step used by the panel.
+    pub(crate) step: Option<Duration>,
+    pub(crate) error: Option<String>,
+}
+
+/// Everything a background refresh needs, detached from the app state.
+pub(crate) struct RefreshJob {
+    pub(crate) datasources: DatasourceRegistry,
+    pub(crate) query_vars: Vec<TemplateQueryVar>,
+    pub(crate) vars: HashMap<String, Variable>,
+    /// Ad-hoc filters added to every panel query.
+    pub(crate) filters: Vec<LabelMatcher>,
+    /// Panels to fetch with their index in the app state.
+    pub(crate) panels: Vec<(usize, PanelState)>,
+    pub(crate) panel_widths: HashMap<usize, u16>,
+    pub(crate) range: Duration,
+    pub(crate) min_step: Duration,
+    pub(crate) end_ts: i64,
+}
+
+/// Owns the background refresh task and the channel it reports on.
+#[derive(Debug)]
+pub(crate) struct Refresher {
+    tx: mpsc::UnboundedSender<(u64, RefreshMessage)>,
+    rx: mpsc::UnboundedReceiver<(u64, RefreshMessage)>,
+    task: Option<JoinHandle<()>>,
+    generation: u64,
+    in_flight: bool,
+}
+
+impl Refresher {
+    pub(crate) fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx,
+            task: None,
+            generation: 0,
+            in_flight: false,
+        }
+    }
+
+    /// Whether a refresh has been started and has not finished yet.
+    pub(crate) fn is_running(&self) -> bool {
+        self.in_flight
+    }
+
+    /// Starts `job` in the background, aborting any refresh still running.
+    pub(crate) fn start(&mut self, job: RefreshJob) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.generation += 1;
+        self.in_flight = true;
+
+        let generation = self.generation;
+        let tx = self.tx.clone();
+        self.task = Some(tokio::spawn(async move {
+            job.run(|message| {
+                let _ = tx.send((generation, message));
+            })
+            .await;
+        }));
+    }
+
+    /// Returns the next message from the current refresh, if one is ready.
+    ///
+    /// Messages left over from aborted refreshes are discarded.
+    pub(crate) fn try_recv(&mut self) -> Option<RefreshMessage> {
+        // Checked before draining: a finished task has already queued all
+        // of its messages.
+        let task_done = self.task.as_ref().is_none_or(|task| task.is_finished());
+        while let Ok((generation, message)) = self.rx.try_recv() {
+            if generation != self.generation {
+                continue;
+            }
+            if matches!(message, RefreshMessage::Finished { .. }) {
+                self.in_flight = false;
+            }
+            return Some(message);
+        }
+        if task_done {
+            // The task ended without reporting completion (e.g. it panicked).
+            self.in_flight = false;
+        }
+        None
+    }
+}
+
+impl RefreshJob {
+    async fn run(mut self, send: impl Fn(RefreshMessage)) {
+        let range = self.range;
+        let min_step = self.min_step;
+        let end_ts = self.end_ts;
+
+        let _ = refresh_query_variables(
+            &self.datasources,
+            &self.query_vars,
+            range,
+            query_step(range, PANEL_RESOLUTION_POINTS, min_step, 1),
+            end_ts,
+            &mut self.vars,
+        )
+        .await;
+        send(RefreshMessage::Variables(self.vars.clone()));
+
+        let datasources = &self.datasources;
+        let vars = &self.vars;
+        let filters = &self.filters;
+        let panel_widths = &self.panel_widths;
+
+        let panels = &self.panels;
+
+        // Indexed rather than `panels.iter()`: a closure taking a reference
+        // argument makes the spawned future fail the `Send` check.
+        let fetches = (0..panels.len()).map(|i| {
+            let (index, panel) = &panels[i];
+            let index = *index;
+            let width = panel_widths.get(&index).copied();
+            let fetch = fetch_panel(
+                datasources,
+                panel,
+                range,
+                min_step,
+                width,
+                vars,
+                filters,
+                end_ts,
+            );
+            async move { (index, fetch.await) }
+        });
+        let mut updates = futures::stream::iter(fetches).buffer_unordered(MAX_CONCURRENT_PANELS);
+
+        while let Some((index, update)) = updates.next().await {
+            send(RefreshMessage::Panel { index, update });
+        }
+
+        send(RefreshMessage::Finished { end_ts });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn fetch_panel(
+    datasources: &DatasourceRegistry,
+    p: &PanelState,
+    range: Duration,
+    min_step: Duration,
+    width: Option<u16>,
+    vars: &HashMap<String, Variable>,
+    filters: &[LabelMatcher],
+    end_ts: i64,
+) -> PanelUpdate {
+    let mut update = PanelUpdate::default();
+    // Repeat clones see their own value of the repeated variable.
+    let scoped;
+    let vars = if p.scoped_vars.is_empty() {
+        vars
+    } else {
+        scoped = vars
+            .iter()
+            .chain(&p.scoped_vars)
+            .map(|(name, var)| (name.clone(), var.clone()))
+            .collect::<HashMap<_, _>>();
+        &scoped
+    };
+    let max_points = p
+        .resolution
+        .max_data_points
+        .or(width.map(u32::from))
+        .unwrap_or(PANEL_RESOLUTION_POINTS) as usize;
+    let reducers = p.reducers();
+    let graph_options = p.graph_options();
+
+    for (i, expr) in p.exprs.iter().enumerate() {
+        let step = p.query_step(i, range, min_step, width);
+        let expr_expanded = inject_matchers(&expand_expr(expr, range, step, vars), filters);
+        let legend_fmt = p.legends.get(i).and_then(|x| x.as_ref());
+        let query_mode = p.query_mode(i);
+        let prometheus = &datasources.resolve(p.datasource(i), vars).client;
+
+        // Calculate start/end for URL display purposes
+        let start_ts = prom::align_range_start(end_ts - (range.as_secs() as i64), step);
+
+        let url = match query_mode {
+            QueryMode::Range => {
+                prometheus.build_query_range_url(&expr_expanded, start_ts, end_ts, step)
+            }
+            QueryMode::Instant => prometheus.build_query_url(&expr_expanded, end_ts),
+        };
+        update.url = Some(url);
+
+        let query_result = match query_mode {
+            QueryMode::Range => {
+                update.step = Some(update.step.map_or(step, |s: Duration| s.max(step)));
+                prometheus
+                    .query_range(&expr_expanded, start_ts, end_ts, step)
+                    .await
+            }
+            QueryMode::Instant => {
+                prometheus
+                    .query_instant_series(&expr_expanded, end_ts)
+                    .await
+            }
+        };
+
+        match query_result {
+            Ok(res) => {
+                for s in res {
+                    let latest_val = s.values.last().and_then(|(_, v)| v.parse::<f64>().ok());
+                    let legend_base = if let Some(fmt) = legend_fmt {
+                        format_legend(fmt, &s.metric)
+                    } else if s.metric.is_empty() {
+                        expr_expanded.clone()
+                    } else {
+                        let mut labels: Vec<_> = s
+                            .metric
+                            .iter()
+                            .map(|(k, v)| format!("{}=\"{}\"", k, v))
+                            .collect();
+                        labels.sort();
+                        format!("{} {{{}}}", expr_expanded, labels.join(", "))
+                    };
+
+                    let bucket = s.metric.get("le").and_then(|le| le.parse::<f64>().ok());
+
+                    let mut pts = Vec::with_capacity(s.values.len());
+                    let mut samples = Vec::with_capacity(s.values.len());
+                    for (ts, val) in s.values {
+                        if let Ok(y) = val.parse::<f64>() {
+                            samples.push(y);
+                            pts.push((ts, y));
+                        }
+                    }
+                    // Intervals without samples only show up in range queries.
+                    let gap = match query_mode {
+                        QueryMode::Range => graph_options
+                            .insert_nulls
+                            .unwrap_or(step.as_secs_f64() * 1.5),
+                        QueryMode::Instant => f64::INFINITY,
+                    };
+                    let (pts, nulls) =
+                        split_nulls(pts, step.as_secs_f64(), gap, graph_options.null_as_zero);
+                    let calcs = reducers
+                        .iter()
+                        .map(|reducer| (*reducer, reducer.reduce(&samples)))
+                        .collect();
+                    // Histogram buckets are subtracted from one another at
+                    // each timestamp, so they keep every sample; heatmaps
+                    // pool their columns themselves.
+                    let pts = if bucket.is_some() {
+                        pts
+                    } else {
+                        downsample(pts, max_points)
+                    };
+                    update.series.push(SeriesView {
+                        name: legend_base,
+                        value: latest_val,
+                        points: pts,
+                        visible: true,
+                        ref_id: p.ref_ids.get(i).cloned().unwrap_or_default(),
+                        overrides: SeriesOverrides::default(),
+                        calcs,
+                        nulls,
+                        bucket,
+                    });
+                }
+            }
+            Err(e) => {
+                let query_name = match query_mode {
+                    QueryMode::Range => "query_range",
+                    QueryMode::Instant => "query",
+                };
+                update.error = Some(format!(
+                    "{} failed for `{}`: {}",
+                    query_name, expr_expanded, e
+                ));
+            }
+        }
+    }
+    update
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prom::PromClient;
+
+    fn empty_job(end_ts: i64) -> RefreshJob {
+        RefreshJob {
+            datasources: DatasourceRegistry::single(PromClient::new(
+                "http://localhost:9090".to_string(),
+            )),
+            query_vars: vec![],
+            vars: HashMap::from([("job".to_string(), Variable::single("node"))]),
+            filters: vec![],
+            panels: vec![],
+            panel_widths: HashMap::new(),
+            range: Duration::from_secs(300),
+            min_step: Duration::from_secs(5),
+            end_ts,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restarted_refresh_only_reports_latest_generation() {
+        let mut refresher = Refresher::new();
+        refresher.start(empty_job(1));
+        refresher.start(empty_job(2));
+        assert!(refresher.is_running());
+
+        let mut messages = Vec::new();
+        while refresher.is_running() {
+            tokio::task::yield_now().await;
+            while let Some(message) = refresher.try_recv() {
+                messages.push(message);
+            }
+        }
+
+        assert!(
+            matches!(messages[0], RefreshMessage::Variables(ref vars) if vars["job"].first() == Some("node"))
+        );
+        let finished: Vec<_> = messages
+            .iter()
+            .filter_map(|message| match message {
+                RefreshMessage::Finished { end_ts } => Some(*end_ts),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(finished, vec![2]);
+    }
+
+    #[tokio::test]
+    async fn test_histogram_buckets_keep_every_sample_past_max_data_points() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // 121 samples at a 30s step; the peaks of the two buckets fall on
+        // alternate timestamps.
+        let values = |even: u32, odd: u32| {
+            (0..121)
+                .map(|i| format!("[{},\"{}\"]", i * 30, if i % 2 == 0 { even } else { odd }))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let body = format!(
+            r#"{{"status":"success","data":{{"resultType":"matrix","result":[{{"metric":{{"le":"1"}},"values":[{}]}},{{"metric":{{"le":"+Inf"}},"values":[{}]}}]}}}}"#,
+            values(10, 9),
+            values(10, 11)
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            assert!(n > 0);
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let mut panel = crate::app::default_queries(vec!["histogram".to_string()]).remove(0);
+        panel.resolution.max_data_points = Some(120);
+        let datasources = DatasourceRegistry::single(PromClient::new(format!("http://{}", addr)));
+        let update = fetch_panel(
+            &datasources,
+            &panel,
+            Duration::from_secs(3600),
+            Duration::from_secs(1),
+            None,
+            &HashMap::new(),
+            &[],
+            3600,
+        )
+        .await;
+        server.await.unwrap();
+
+        assert_eq!(update.error, None);
+        assert!(update.series.iter().all(|s| s.points.len() == 121));
+        panel.series = update.series;
+        let grid = crate::ui::heatmap_grid(&panel, [0.0, 3600.0], 60, 2).unwrap();
+        // The +Inf bucket holds at most 2 observations at any timestamp.
+        assert!(grid.cells[1].iter().flatten().all(|count| *count <= 2.0));
+    }
+}
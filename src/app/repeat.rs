@@ -0,0 +1,368 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Panel and row repeats.
+//!
+//! Grafana repeats a panel, or a row with every panel it groups, once per
+//! value of a template variable. Each clone binds the variable to its own
+//! value, and clones are placed on the grid the way Grafana places them.
+
+use crate::app::interpolate::interpolate;
+use crate::app::state::{GridUnit, PanelRepeat, PanelState, RepeatDirection};
+use crate::app::variables::{ALL_VALUE, Variable};
+use std::collections::HashMap;
+
+/// Width of the Grafana grid, in columns.
+const GRID_COLUMNS: i32 = 24;
+/// Horizontal clones side by side when `maxPerRow` is not set.
+const DEFAULT_MAX_PER_ROW: u32 = 4;
+
+/// Identifies an expanded panel: the index of the panel it was cloned from
+/// and the values bound by the repeats that produced it.
+pub(crate) type RepeatKey = (usize, Vec<String>);
+
+/// Variable bindings of a clone, in the order the repeats were applied.
+type Binding = Vec<(String, String)>;
+
+/// Expands the repeated rows and panels of `templates` for the current
+/// values of `vars`.
+///
+/// A repeated row is copied with its whole section, one copy below the
+/// other. A repeated panel is cloned in place; horizontal clones share the
+/// grid width, wrapping after `maxPerRow` clones, and vertical clones stack.
+/// Panels below the repeats move down to make room. A repeat whose variable
+/// has no values keeps a single, unbound panel.
+pub(crate) fn expand_repeats(
+    templates: &[PanelState],
+    vars: &HashMap<String, Variable>,
+) -> Vec<(RepeatKey, PanelState)> {
+    let sections = sections(templates);
+    let mut out = Vec::with_capacity(templates.len());
+    // Grid rows added above the current section by earlier repeats.
+    let mut shift = 0;
+
+    for (s, &(start, end)) in sections.iter().enumerate() {
+        let top = grid_top(&templates[start..end]);
+        let height = match sections.get(s + 1) {
+            Some(&(next_start, next_end)) => grid_top(&templates[next_start..next_end]),
+            None => grid_bottom(&templates[start..end]),
+        }
+        .zip(top)
+        .map_or(0, |(next, top)| next - top);
+
+        let header = &templates[start];
+        let bindings: Vec<Binding> = match header.repeat.as_ref().filter(|_| header.is_row()) {
+            Some(repeat) => repeat_values(repeat, &[], vars)
+                .into_iter()
+                .map(|value| vec![(repeat.var.clone(), value)])
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut offset = shift;
+        if bindings.is_empty() {
+            offset += expand_section(templates, start, end, &[], vars, offset, height, &mut out);
+        }
+        for binding in &bindings {
+            offset += expand_section(
+                templates, start, end, binding, vars, offset, height, &mut out,
+            );
+        }
+        shift = offset - height;
+    }
+    out
+}
+
+/// Splits `templates` into the panels before the first row and each row
+/// with the panels it groups.
+fn sections(templates: &[PanelState]) -> Vec<(usize, usize)> {
+    let starts: Vec<usize> = (0..templates.len())
+        .filter(|&i| i == 0 || templates[i].is_row())
+        .collect();
+    let ends = starts.iter().skip(1).copied().chain([templates.len()]);
+    starts.iter().copied().zip(ends).collect()
+}
+
+fn grid_top(panels: &[PanelState]) -> Option<i32> {
+    panels.iter().filter_map(|p| p.grid).map(|g| g.y).min()
+}
+
+fn grid_bottom(panels: &[PanelState]) -> Option<i32> {
+    panels
+        .iter()
+        .filter_map(|p| p.grid)
+        .map(|g| g.y + g.h)
+        .max()
+}
+
+/// Appends one copy of the section `start..end`, moved down by `offset`
+/// grid rows, with `binding` applied to every panel.
+///
+/// Returns the height of the copy: the section `height` plus the room taken
+/// by repeated panels.
+#[allow(clippy::too_many_arguments)]
+fn expand_section(
+    templates: &[PanelState],
+    start: usize,
+    end: usize,
+    binding: &[(String, String)],
+    vars: &HashMap<String, Variable>,
+    offset: i32,
+    height: i32,
+    out: &mut Vec<(RepeatKey, PanelState)>,
+) -> i32 {
+    let values: Vec<Option<(&PanelRepeat, Vec<String>)>> = templates[start..end]
+        .iter()
+        .map(|p| {
+            let repeat = p.repeat.as_ref().filter(|_| !p.is_row())?;
+            let values = repeat_values(repeat, binding, vars);
+            (!values.is_empty()).then_some((repeat, values))
+        })
+        .collect();
+
+    // Bottom edge of each repeated panel and the grid rows its clones add.
+    let growth: Vec<(i32, i32)> = templates[start..end]
+        .iter()
+        .zip(&values)
+        .filter_map(|(p, values)| {
+            let grid = p.grid?;
+            let (repeat, values) = values.as_ref()?;
+            let lines = match repeat.direction {
+                RepeatDirection::Horizontal => values.len().div_ceil(per_row(repeat, values.len())),
+                RepeatDirection::Vertical => values.len(),
+            };
+            Some((grid.y + grid.h, (lines as i32 - 1) * grid.h))
+        })
+        .collect();
+
+    for (i, values) in (start..end).zip(values) {
+        let p = &templates[i];
+        let dy = offset
+            + p.grid.map_or(0, |grid| {
+                growth
+                    .iter()
+                    .filter(|(bottom, _)| grid.y >= *bottom)
+                    .map(|(_, rows)| rows)
+                    .sum()
+            });
+
+        let Some((repeat, values)) = values else {
+            let mut clone = bound_panel(p, binding, vars);
+            if let Some(grid) = clone.grid.as_mut() {
+                grid.y += dy;
+            }
+            out.push((repeat_key(i, binding), clone));
+            continue;
+        };
+
+        let count = values.len();
+        for (k, value) in values.into_iter().enumerate() {
+            let mut binding = binding.to_vec();
+            if !binding.iter().any(|(name, _)| *name == repeat.var) {
+                binding.push((repeat.var.clone(), value));
+            }
+            let mut clone = bound_panel(p, &binding, vars);
+            clone.grid = p.grid.map(|grid| place_clone(grid, repeat, k, count, dy));
+            out.push((repeat_key(i, &binding), clone));
+        }
+    }
+    height + growth.iter().map(|(_, rows)| rows).sum::<i32>()
+}
+
+/// Values a repeat iterates over; a value bound by an enclosing row repeat
+/// takes precedence.
+fn repeat_values(
+    repeat: &PanelRepeat,
+    binding: &[(String, String)],
+    vars: &HashMap<String, Variable>,
+) -> Vec<String> {
+    if let Some((_, value)) = binding.iter().find(|(name, _)| *name == repeat.var) {
+        return vec![value.clone()];
+    }
+    vars.get(&repeat.var)
+        .map(|var| {
+            var.values()
+                .iter()
+                .filter(|value| *value != ALL_VALUE)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn per_row(repeat: &PanelRepeat, count: usize) -> usize {
+    (repeat.max_per_row.unwrap_or(DEFAULT_MAX_PER_ROW).max(1) as usize).min(count)
+}
+
+/// Grid position of clone `k` out of `count`, moved down by `dy` rows.
+fn place_clone(grid: GridUnit, repeat: &PanelRepeat, k: usize, count: usize, dy: i32) -> GridUnit {
+    match repeat.direction {
+        RepeatDirection::Horizontal => {
+            let per_row = per_row(repeat, count);
+            let w = GRID_COLUMNS / per_row as i32;
+            GridUnit {
+                x: (k % per_row) as i32 * w,
+                y: grid.y + dy + (k / per_row) as i32 * grid.h,
+                w,
+                h: grid.h,
+            }
+        }
+        RepeatDirection::Vertical => GridUnit {
+            y: grid.y + dy + k as i32 * grid.h,
+            ..grid
+        },
+    }
+}
+
+/// Copy of `p` with `binding` scoped to it and its title interpolated.
+fn bound_panel(
+    p: &PanelState,
+    binding: &[(String, String)],
+    vars: &HashMap<String, Variable>,
+) -> PanelState {
+    let mut clone = p.clone();
+    if binding.is_empty() {
+        return clone;
+    }
+    clone.scoped_vars = binding
+        .iter()
+        .map(|(name, value)| (name.clone(), Variable::single(value.clone())))
+        .collect();
+    clone.title = interpolate(&p.title, |name| {
+        clone.scoped_vars.get(name).or_else(|| vars.get(name))
+    });
+    clone
+}
+
+fn repeat_key(index: usize, binding: &[(String, String)]) -> RepeatKey {
+    (
+        index,
+        binding.iter().map(|(_, value)| value.clone()).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::state::{PanelOptions, PanelType};
+
+    fn panels(specs: &[(&str, [i32; 4])]) -> Vec<PanelState> {
+        let titles = specs.iter().map(|(title, _)| title.to_string()).collect();
+        let mut panels = crate::app::default_queries(titles);
+        for (panel, (title, [x, y, w, h])) in panels.iter_mut().zip(specs) {
+            panel.grid = Some(GridUnit {
+                x: *x,
+                y: *y,
+                w: *w,
+                h: *h,
+            });
+            if title.starts_with("Row") {
+                panel.panel_type = PanelType::Row;
+                panel.options = PanelOptions::Row { collapsed: false };
+                panel.exprs.clear();
+            }
+        }
+        panels
+    }
+
+    fn repeat(var: &str, direction: RepeatDirection, max_per_row: Option<u32>) -> PanelRepeat {
+        PanelRepeat {
+            var: var.to_string(),
+            direction,
+            max_per_row,
+        }
+    }
+
+    fn grid(panel: &PanelState) -> (i32, i32, i32, i32) {
+        let g = panel.grid.unwrap();
+        (g.x, g.y, g.w, g.h)
+    }
+
+    fn host_vars(hosts: &[&str]) -> HashMap<String, Variable> {
+        HashMap::from([(
+            "host".to_string(),
+            Variable::with_values(hosts.iter().map(|h| h.to_string()).collect()),
+        )])
+    }
+
+    #[test]
+    fn test_horizontal_repeat_wraps_after_max_per_row() {
+        let mut templates = panels(&[("CPU $host", [0, 0, 12, 8]), ("Below", [0, 8, 24, 4])]);
+        templates[0].repeat = Some(repeat("host", RepeatDirection::Horizontal, Some(2)));
+
+        let expanded = expand_repeats(&templates, &host_vars(&["a", "b", "c"]));
+        let titles: Vec<&str> = expanded.iter().map(|(_, p)| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["CPU a", "CPU b", "CPU c", "Below"]);
+        let grids: Vec<_> = expanded.iter().map(|(_, p)| grid(p)).collect();
+        assert_eq!(
+            grids,
+            vec![(0, 0, 12, 8), (12, 0, 12, 8), (0, 8, 12, 8), (0, 16, 24, 4)]
+        );
+        assert_eq!(expanded[1].0, (0, vec!["b".to_string()]));
+        assert_eq!(expanded[1].1.scoped_vars["host"].first(), Some("b"));
+        assert!(expanded[3].1.scoped_vars.is_empty());
+    }
+
+    #[test]
+    fn test_vertical_repeat_stacks_clones() {
+        let mut templates = panels(&[
+            ("Left", [0, 0, 12, 4]),
+            ("Disk $host", [12, 0, 12, 4]),
+            ("Below", [0, 4, 24, 4]),
+        ]);
+        templates[1].repeat = Some(repeat("host", RepeatDirection::Vertical, None));
+
+        let expanded = expand_repeats(&templates, &host_vars(&["a", "b"]));
+        let grids: Vec<_> = expanded.iter().map(|(_, p)| grid(p)).collect();
+        assert_eq!(
+            grids,
+            vec![(0, 0, 12, 4), (12, 0, 12, 4), (12, 4, 12, 4), (0, 8, 24, 4)]
+        );
+    }
+
+    #[test]
+    fn test_row_repeat_copies_its_section_with_the_value_bound() {
+        let mut templates = panels(&[
+            ("Row $host", [0, 0, 24, 1]),
+            ("CPU", [0, 1, 24, 4]),
+            ("Row Other", [0, 5, 24, 1]),
+            ("Load", [0, 6, 24, 4]),
+        ]);
+        templates[0].repeat = Some(repeat("host", RepeatDirection::Horizontal, None));
+
+        let expanded = expand_repeats(&templates, &host_vars(&["a", "b"]));
+        let titles: Vec<&str> = expanded.iter().map(|(_, p)| p.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Row a", "CPU", "Row b", "CPU", "Row Other", "Load"]
+        );
+        let ys: Vec<i32> = expanded.iter().map(|(_, p)| p.grid.unwrap().y).collect();
+        assert_eq!(ys, vec![0, 1, 5, 6, 10, 11]);
+        assert_eq!(expanded[3].1.scoped_vars["host"].first(), Some("b"));
+        assert!(expanded[5].1.scoped_vars.is_empty());
+    }
+
+    #[test]
+    fn test_repeat_without_values_keeps_the_panel() {
+        let mut templates = panels(&[("CPU $host", [0, 0, 12, 8])]);
+        templates[0].repeat = Some(repeat("host", RepeatDirection::Horizontal, None));
+
+        let expanded = expand_repeats(&templates, &HashMap::new());
+        assert_eq!(expanded.len(), 1);
+        assert_eq!(expanded[0].1.title, "CPU $host");
+        assert_eq!(grid(&expanded[0].1), (0, 0, 12, 8));
+    }
+}
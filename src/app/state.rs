@@ -14,15 +14,18 @@
  * limitations under the License.
  */
 
-use crate::app::data::{downsample, expand_expr, format_legend};
-use crate::app::variables::refresh_query_variables;
+use crate::app::data::{PANEL_RESOLUTION_POINTS, query_step};
+use crate::app::datasources::DatasourceRegistry;
+use crate::app::overrides::{FieldOverride, SeriesOverrides, apply_overrides};
+use crate::app::promql::{LabelMatcher, format_matchers, parse_matchers};
+use crate::app::reduce::{ReduceOptions, Reducer};
+use crate::app::refresh::{RefreshJob, RefreshMessage, Refresher};
+use crate::app::repeat::{RepeatKey, expand_repeats};
+use crate::app::variables::{Variable, VariableKind, VariablePicker};
 use crate::export::{ExportOptions, RecordingState};
 use crate::grafana::TemplateQueryVar;
-use crate::prom;
 use crate::theme::Theme;
-use crate::ui::DisplayFormat;
-use anyhow::Result;
-use futures::StreamExt;
+use crate::ui::{DisplayFormat, get_hash_color};
 use ratatui::style::Color;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -38,18 +41,32 @@ pub(crate) struct PanelState {
     pub(crate) legends: Vec<Option<String>>,
     /// Query mode for each expression. Parallel to exprs.
     pub(crate) query_modes: Vec<QueryMode>,
+    /// Grafana datasource reference for each expression. Parallel to exprs.
+    pub(crate) datasources: Vec<Option<String>>,
+    /// Grafana target `refId` of each expression. Parallel to exprs.
+    pub(crate) ref_ids: Vec<String>,
+    /// Query options used to derive the range query step.
+    pub(crate) resolution: QueryResolution,
     /// Current time-series data for this panel.
     pub(crate) series: Vec<SeriesView>,
     /// Last error message, if any.
     pub(crate) last_error: Option<String>,
     /// Last query URL used (for debugging).
     pub(crate) last_url: Option<String>,
+    /// Range query step used for the current data.
+    pub(crate) last_step: Option<Duration>,
+    /// Whether a refresh for this panel is in flight.
+    pub(crate) loading: bool,
+    /// Whether the shown data predates the current time window.
+    pub(crate) stale: bool,
     /// Total number of samples in the current view.
     pub(crate) last_samples: usize,
     /// Grid layout position (if imported from Grafana).
     pub(crate) grid: Option<GridUnit>,
     /// Y-axis scaling mode.
     pub(crate) y_axis_mode: YAxisMode,
+    /// First legend row shown by a table or right-hand legend.
+    pub(crate) legend_scroll: usize,
     /// Visualization type.
     pub(crate) panel_type: PanelType,
     /// Threshold configuration.
@@ -62,8 +79,15 @@ pub(crate) struct PanelState {
     pub(crate) autogrid: Option<bool>,
     /// Display formatting imported from Grafana field configuration.
     pub(crate) display: DisplayFormat,
+    /// Grafana field overrides, applied to the series after each refresh.
+    pub(crate) overrides: Vec<FieldOverride>,
     /// Renderer-specific presentation options.
     pub(crate) options: PanelOptions,
+    /// Template variable this panel, or row, is repeated for.
+    pub(crate) repeat: Option<PanelRepeat>,
+    /// Variable values bound to this repeat clone, overriding the dashboard
+    /// variables in its queries.
+    pub(crate) scoped_vars: HashMap<String, Variable>,
 }
 
 /// Visualization types supported by Grafatui.
@@ -75,22 +99,102 @@ pub(crate) enum PanelType {
     Table,
     Stat,
     Heatmap,
+    /// Grafana row header grouping the panels below it.
+    Row,
+    /// Static Markdown, HTML or code content.
+    Text,
     Unknown,
 }
 
 /// Renderer-specific options carried by a generic panel.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Default)]
 pub(crate) enum PanelOptions {
+    #[default]
     None,
     Graph(GraphOptions),
+    /// Whether a row hides the panels it groups.
+    Row {
+        collapsed: bool,
+    },
+    Text(TextOptions),
+    /// Value calculation of Stat, Gauge, Bar Gauge and Table panels.
+    Reduce(ReduceOptions),
+    Heatmap(HeatmapOptions),
+}
+
+/// Content of a text panel.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TextOptions {
+    pub(crate) mode: TextMode,
+    /// Content as written, before variables are interpolated.
+    pub(crate) content: String,
+}
+
+/// How text panel content is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum TextMode {
+    #[default]
+    Markdown,
+    /// HTML, shown with its markup stripped.
+    Html,
+    /// Preformatted code, shown verbatim.
+    Code,
 }
 
-impl Default for PanelOptions {
+/// Heatmap rendering options imported from Grafana.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HeatmapOptions {
+    pub(crate) color: HeatmapColor,
+    /// Whether the color scale is shown below the heatmap.
+    pub(crate) show_legend: bool,
+    /// Format of the bucket bounds on the y-axis.
+    pub(crate) y_axis: crate::ui::DisplayFormat,
+}
+
+impl Default for HeatmapOptions {
     fn default() -> Self {
-        Self::None
+        Self {
+            color: HeatmapColor::default(),
+            show_legend: true,
+            y_axis: crate::ui::DisplayFormat::default(),
+        }
     }
 }
 
+/// How heatmap cells are colored by value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HeatmapColor {
+    pub(crate) mode: HeatmapColorMode,
+    /// Exponent of an exponential opacity scale, or `None` for a linear one.
+    pub(crate) exponent: Option<f64>,
+    /// Whether the scheme or opacity runs from high to low values.
+    pub(crate) reverse: bool,
+    /// Fixed lower end of the color scale, instead of the lowest cell.
+    pub(crate) min: Option<f64>,
+    /// Fixed upper end of the color scale, instead of the highest cell.
+    pub(crate) max: Option<f64>,
+}
+
+impl Default for HeatmapColor {
+    fn default() -> Self {
+        Self {
+            mode: HeatmapColorMode::Scheme("Oranges".to_string()),
+            exponent: Some(0.5),
+            reverse: false,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum HeatmapColorMode {
+    /// A named color scheme such as `Oranges` or `Viridis`.
+    Scheme(String),
+    /// Shades of a fill color, from transparent to opaque.
+    Opacity(Color),
+}
+
 /// Graph/timeseries rendering options imported from Grafana.
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct GraphOptions {
@@ -98,8 +202,22 @@ pub(crate) struct GraphOptions {
     pub(crate) show_points: GraphPointMode,
     pub(crate) fill_opacity: Option<u8>,
     pub(crate) axis_placement: GraphAxisPlacement,
-    pub(crate) line_interpolation: Option<String>,
+    pub(crate) line_interpolation: GraphLineInterpolation,
     pub(crate) stacking: GraphStackingMode,
+    /// `custom.stacking.group`: series stack only with series of the same group.
+    pub(crate) stacking_group: String,
+    /// `custom.scaleDistribution` of the y-axis.
+    pub(crate) scale: GraphScale,
+    /// `custom.spanNulls`: whether lines connect across nulls.
+    pub(crate) span_nulls: SpanNulls,
+    /// `custom.insertNulls`, in seconds: intervals without samples longer
+    /// than this hold a null. Defaults to one and a half query steps.
+    pub(crate) insert_nulls: Option<f64>,
+    /// Missing samples count as zero instead of null, as with the
+    /// `nullPointMode: "null as zero"` of the old graph panel.
+    pub(crate) null_as_zero: bool,
+    /// Panel `options.legend`.
+    pub(crate) legend: LegendOptions,
 }
 
 impl Default for GraphOptions {
@@ -109,12 +227,45 @@ impl Default for GraphOptions {
             show_points: GraphPointMode::Auto,
             fill_opacity: None,
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            stacking_group: DEFAULT_STACKING_GROUP.to_string(),
+            scale: GraphScale::Linear,
+            span_nulls: SpanNulls::Never,
+            insert_nulls: None,
+            null_as_zero: false,
+            legend: LegendOptions::default(),
         }
     }
 }
 
+/// Grafana graph legend options.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct LegendOptions {
+    pub(crate) display_mode: LegendDisplayMode,
+    pub(crate) placement: LegendPlacement,
+    /// Reducers shown for each series, as columns in table mode.
+    pub(crate) calcs: Vec<Reducer>,
+    /// `Name` or the label of a calc, such as `Max` or `Last *`.
+    pub(crate) sort_by: Option<String>,
+    pub(crate) sort_desc: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum LegendDisplayMode {
+    #[default]
+    List,
+    Table,
+    Hidden,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum LegendPlacement {
+    #[default]
+    Bottom,
+    Right,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum GraphDrawStyle {
     Line,
@@ -129,12 +280,31 @@ pub(crate) enum GraphPointMode {
     Never,
 }
 
+/// `custom.lineInterpolation`: how lines join neighbouring samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum GraphLineInterpolation {
+    #[default]
+    Linear,
+    /// A monotone curve through the samples.
+    Smooth,
+    /// Steps to each value at the sample before it.
+    StepBefore,
+    /// Holds each value until the next sample.
+    StepAfter,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum GraphAxisPlacement {
+    /// `auto` or `left`: the y-axis left of the plot.
     Visible,
     Hidden,
+    /// A y-axis right of the plot, with its own bounds and unit.
+    Right,
 }
 
+/// Stacking group of series without an explicit `custom.stacking.group`.
+pub(crate) const DEFAULT_STACKING_GROUP: &str = "A";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum GraphStackingMode {
     Off,
@@ -142,6 +312,98 @@ pub(crate) enum GraphStackingMode {
     Percent,
 }
 
+/// How graph lines cross nulls: missing and non-finite samples.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum SpanNulls {
+    /// Lines break at every null.
+    #[default]
+    Never,
+    /// Lines connect the samples around nulls.
+    Always,
+    /// Lines connect samples at most this many seconds apart across nulls.
+    Below(f64),
+}
+
+/// Distribution of values along the y-axis.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum GraphScale {
+    #[default]
+    Linear,
+    /// Logarithmic axis; values at or below zero are not drawn.
+    Log { base: f64 },
+    /// Symmetric log axis: close to linear within `linear_threshold` of zero
+    /// and logarithmic beyond, for values of both signs.
+    Symlog { base: f64, linear_threshold: f64 },
+}
+
+impl GraphScale {
+    /// Position of `value` on the axis, or `None` when the scale cannot
+    /// show it. Renderers work in axis positions, which are linear.
+    pub(crate) fn position_of(self, value: f64) -> Option<f64> {
+        if !value.is_finite() {
+            return None;
+        }
+        match self {
+            Self::Linear => Some(value),
+            Self::Log { base } => (value > 0.0).then(|| log(value, base)),
+            Self::Symlog {
+                base,
+                linear_threshold,
+            } => Some(value.signum() * log(1.0 + value.abs() / linear_threshold, base)),
+        }
+    }
+
+    /// Value at axis position `position`, the inverse of [`Self::position_of`].
+    pub(crate) fn value_at(self, position: f64) -> f64 {
+        match self {
+            Self::Linear => position,
+            Self::Log { base } => base.powf(position),
+            Self::Symlog {
+                base,
+                linear_threshold,
+            } => position.signum() * linear_threshold * (base.powf(position.abs()) - 1.0),
+        }
+    }
+
+    /// The scale the runtime scale key switches to: linear, log10, log2,
+    /// symlog and back to linear.
+    pub(crate) fn cycled(self) -> Self {
+        match self {
+            Self::Linear => Self::Log { base: 10.0 },
+            Self::Log { base: 10.0 } => Self::Log { base: 2.0 },
+            Self::Log { .. } => Self::Symlog {
+                base: 10.0,
+                linear_threshold: 1.0,
+            },
+            Self::Symlog { .. } => Self::Linear,
+        }
+    }
+}
+
+fn log(value: f64, base: f64) -> f64 {
+    // Exact for powers of the common bases, so ticks land on whole exponents.
+    if base == 10.0 {
+        value.log10()
+    } else if base == 2.0 {
+        value.log2()
+    } else {
+        value.ln() / base.ln()
+    }
+}
+
+/// Grafana query options that bound the range query step of a panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct QueryResolution {
+    /// Panel `maxDataPoints`; defaults to the rendered panel width.
+    pub(crate) max_data_points: Option<u32>,
+    /// Panel-level minimum interval (`interval`).
+    pub(crate) min_interval: Option<Duration>,
+    /// Minimum step for each expression (`targets[].interval`). Parallel to exprs.
+    pub(crate) target_intervals: Vec<Option<Duration>>,
+    /// Step multiplier for each expression (`targets[].intervalFactor`). Parallel to exprs.
+    pub(crate) interval_factors: Vec<u32>,
+}
+
 /// Prometheus endpoint mode for a target query.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum QueryMode {
@@ -159,7 +421,7 @@ pub(crate) enum YAxisMode {
 }
 
 /// Represents a single time-series line in a chart.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct SeriesView {
     /// Stable name of the series (used for coloring).
     pub(crate) name: String,
@@ -169,6 +431,46 @@ pub(crate) struct SeriesView {
     pub(crate) points: Vec<(f64, f64)>,
     /// Whether the series is visible in the chart.
     pub(crate) visible: bool,
+    /// `refId` of the target that returned the series.
+    pub(crate) ref_id: String,
+    /// Field override properties matching the series.
+    pub(crate) overrides: SeriesOverrides,
+    /// Reducer results over the queried range, computed before the points
+    /// are downsampled.
+    pub(crate) calcs: Vec<(Reducer, Option<f64>)>,
+    /// Nulls of the series, in time order. `points` only holds finite values.
+    pub(crate) nulls: Vec<NullSample>,
+    /// Upper bound of the histogram bucket counted by the series, from its
+    /// `le` label.
+    pub(crate) bucket: Option<f64>,
+}
+
+/// A timestamp where a series has no finite value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct NullSample {
+    pub(crate) ts: f64,
+    /// The NaN or infinite sample value, or `None` for the first step of an
+    /// interval without samples.
+    pub(crate) value: Option<f64>,
+}
+
+impl SeriesView {
+    /// Value of `reducer` for this series. Reducers not computed at query
+    /// time fall back to the latest value or the displayed points.
+    pub(crate) fn calc(&self, reducer: Reducer) -> Option<f64> {
+        match self.calcs.iter().find(|(r, _)| *r == reducer) {
+            Some((_, value)) => *value,
+            None if reducer == Reducer::Last => self.value,
+            // A finite latest value is also the last non-null one.
+            None if reducer == Reducer::LastNotNull && self.value.is_some_and(f64::is_finite) => {
+                self.value
+            }
+            None => {
+                let samples: Vec<f64> = self.points.iter().map(|(_, v)| *v).collect();
+                reducer.reduce(&samples)
+            }
+        }
+    }
 }
 
 /// Grid positioning unit (Grafana style).
@@ -180,33 +482,198 @@ pub(crate) struct GridUnit {
     pub(crate) h: i32,
 }
 
+/// Grafana panel repeat options.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PanelRepeat {
+    /// Name of the variable whose values get a clone each.
+    pub(crate) var: String,
+    pub(crate) direction: RepeatDirection,
+    /// Maximum number of horizontal clones side by side.
+    pub(crate) max_per_row: Option<u32>,
+}
+
+/// Direction in which repeated panels are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum RepeatDirection {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) enum ThresholdMode {
     Absolute,
     Percentage,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct ThresholdStep {
     pub(crate) value: Option<f64>,
     pub(crate) color: Color,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) struct Thresholds {
     pub(crate) mode: ThresholdMode,
     pub(crate) steps: Vec<ThresholdStep>,
     pub(crate) style: Option<String>,
 }
 
+impl Thresholds {
+    /// Whether graphs draw a line at each step: every style but `area`.
+    pub(crate) fn shows_lines(&self) -> bool {
+        self.style.as_deref() != Some("area")
+    }
+
+    /// Whether graphs shade the plot between steps, with the `area`,
+    /// `line+area` and `dashed+area` styles.
+    pub(crate) fn shows_area(&self) -> bool {
+        matches!(
+            self.style.as_deref(),
+            Some("area" | "line+area" | "dashed+area")
+        )
+    }
+
+    /// Whether threshold lines are dashed, with the `dashed` and
+    /// `dashed+area` styles.
+    pub(crate) fn is_dashed(&self) -> bool {
+        self.style
+            .as_deref()
+            .is_some_and(|style| style.starts_with("dashed"))
+    }
+
+    /// Value of a step at `value`, resolving percentages against the panel
+    /// `min` and `max`, which default to 0 and 100.
+    pub(crate) fn absolute_value(&self, value: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+        match self.mode {
+            ThresholdMode::Absolute => value,
+            ThresholdMode::Percentage => {
+                let min = min.unwrap_or(0.0);
+                let max = max.unwrap_or(100.0);
+                min + (value / 100.0) * (max - min)
+            }
+        }
+    }
+}
+
 impl PanelState {
     pub(crate) fn graph_options(&self) -> GraphOptions {
         match &self.options {
             PanelOptions::Graph(options) => options.clone(),
-            PanelOptions::None => GraphOptions::default(),
+            PanelOptions::None
+            | PanelOptions::Row { .. }
+            | PanelOptions::Text(_)
+            | PanelOptions::Reduce(_)
+            | PanelOptions::Heatmap(_) => GraphOptions::default(),
         }
     }
 
+    pub(crate) fn reduce_options(&self) -> ReduceOptions {
+        match &self.options {
+            PanelOptions::Reduce(options) => options.clone(),
+            PanelOptions::None
+            | PanelOptions::Row { .. }
+            | PanelOptions::Text(_)
+            | PanelOptions::Graph(_)
+            | PanelOptions::Heatmap(_) => ReduceOptions::default(),
+        }
+    }
+
+    pub(crate) fn heatmap_options(&self) -> HeatmapOptions {
+        match &self.options {
+            PanelOptions::Heatmap(options) => options.clone(),
+            PanelOptions::None
+            | PanelOptions::Row { .. }
+            | PanelOptions::Text(_)
+            | PanelOptions::Graph(_)
+            | PanelOptions::Reduce(_) => HeatmapOptions::default(),
+        }
+    }
+
+    /// Reducers computed over the queried range, for the panel values and
+    /// the legend calcs.
+    pub(crate) fn reducers(&self) -> Vec<Reducer> {
+        let mut reducers = self.reduce_options().calcs;
+        for reducer in self.graph_options().legend.calcs {
+            if !reducers.contains(&reducer) {
+                reducers.push(reducer);
+            }
+        }
+        reducers
+    }
+
+    /// Values shown by a Stat, Gauge, Bar Gauge or Table panel, one row per
+    /// visible series selected by the reduce options. A row holds the value
+    /// of each reducer, or with `values` set, a row holds a single point and
+    /// the rows are capped by `limit`.
+    pub(crate) fn reduced_values(&self) -> Vec<(&SeriesView, Vec<Option<f64>>)> {
+        let options = self.reduce_options();
+        let selected = self.series.iter().filter(|s| {
+            s.visible
+                && options
+                    .fields
+                    .as_ref()
+                    .is_none_or(|fields| fields.matches(&s.name))
+        });
+        if options.values {
+            selected
+                .flat_map(|s| s.points.iter().map(move |(_, v)| (s, vec![Some(*v)])))
+                .take(options.limit.unwrap_or(usize::MAX))
+                .collect()
+        } else {
+            selected
+                .map(|s| (s, options.calcs.iter().map(|r| s.calc(*r)).collect()))
+                .collect()
+        }
+    }
+
+    /// Whether this panel is a row header.
+    pub(crate) fn is_row(&self) -> bool {
+        self.panel_type == PanelType::Row
+    }
+
+    /// Whether this panel shows query results; rows and text panels do not.
+    pub(crate) fn has_queries(&self) -> bool {
+        !matches!(self.panel_type, PanelType::Row | PanelType::Text)
+    }
+
+    /// Whether this panel is a collapsed row header.
+    pub(crate) fn is_collapsed_row(&self) -> bool {
+        matches!(self.options, PanelOptions::Row { collapsed: true })
+    }
+
+    /// Grafana datasource reference for the query at `index`, if any.
+    pub(crate) fn datasource(&self, index: usize) -> Option<&str> {
+        self.datasources.get(index).and_then(|ds| ds.as_deref())
+    }
+
+    /// Range query step for the query at `index`.
+    ///
+    /// `width` is the rendered panel width in columns, used as the number of
+    /// data points when the panel does not set `maxDataPoints`.
+    pub(crate) fn query_step(
+        &self,
+        index: usize,
+        range: Duration,
+        min_step: Duration,
+        width: Option<u16>,
+    ) -> Duration {
+        let resolution = &self.resolution;
+        let max_data_points = resolution
+            .max_data_points
+            .or(width.map(u32::from))
+            .unwrap_or(PANEL_RESOLUTION_POINTS);
+        let min_step = [
+            resolution.min_interval,
+            resolution.target_intervals.get(index).copied().flatten(),
+        ]
+        .into_iter()
+        .flatten()
+        .fold(min_step, Duration::max);
+        let interval_factor = resolution.interval_factors.get(index).copied().unwrap_or(1);
+        query_step(range, max_data_points, min_step, interval_factor)
+    }
+
     pub(crate) fn query_mode(&self, index: usize) -> QueryMode {
         self.query_modes
             .get(index)
@@ -214,9 +681,7 @@ impl PanelState {
             .unwrap_or(QueryMode::Range)
     }
 
-    pub(crate) fn get_color_for_value(&self, val: f64) -> Option<Color> {
-        let thresholds = self.thresholds.as_ref()?;
-
+    fn threshold_color(&self, thresholds: &Thresholds, val: f64) -> Option<Color> {
         let mut matched_color = None;
 
         match thresholds.mode {
@@ -260,6 +725,37 @@ impl PanelState {
         }
         matched_color
     }
+
+    /// Color for a displayed value of `series`: its value mapping's color,
+    /// otherwise the color of the series' thresholds or the panel's.
+    pub(crate) fn value_color(&self, series: &SeriesView, val: Option<f64>) -> Option<Color> {
+        self.series_display(series).mapped_color(val).or_else(|| {
+            let thresholds = series
+                .overrides
+                .thresholds
+                .as_ref()
+                .or(self.thresholds.as_ref())?;
+            self.threshold_color(thresholds, val?)
+        })
+    }
+
+    /// Display format of a series: its override, otherwise the panel's.
+    pub(crate) fn series_display<'a>(&'a self, series: &'a SeriesView) -> &'a DisplayFormat {
+        series.overrides.display.as_ref().unwrap_or(&self.display)
+    }
+
+    /// Line color of the series at `index`: its override color, otherwise a
+    /// palette color, or a name-based color when the palette runs out.
+    pub(crate) fn series_color(&self, index: usize, theme: &Theme) -> Color {
+        let series = &self.series[index];
+        series.overrides.color.unwrap_or_else(|| {
+            if self.series.len() > theme.palette.len() {
+                get_hash_color(&series.name)
+            } else {
+                theme.palette[index % theme.palette.len()]
+            }
+        })
+    }
 }
 
 /// Application mode.
@@ -270,22 +766,35 @@ pub(crate) enum AppMode {
     Fullscreen,
     Inspect,
     FullscreenInspect,
+    VariablePicker,
+    FilterEditor,
 }
 
 /// Global application state.
 #[derive(Debug)]
 pub(crate) struct AppState {
-    /// Prometheus client for making requests.
-    pub(crate) prometheus: prom::PromClient,
+    /// Prometheus datasources available to panels and variables.
+    pub(crate) datasources: DatasourceRegistry,
+    /// Names of Grafana datasource variables, which follow the default
+    /// datasource when it is switched at runtime.
+    pub(crate) datasource_vars: Vec<String>,
     /// Current time range window.
     pub(crate) range: Duration,
-    /// Query step resolution.
+    /// Minimum query step; panels use coarser steps for longer ranges.
     pub(crate) step: Duration,
     /// How often to refresh data.
     pub(crate) refresh_every: Duration,
     /// List of panels.
     pub(crate) panels: Vec<PanelState>,
-    /// Timestamp of the last successful refresh.
+    /// Dashboard panels before repeats are expanded; empty when nothing
+    /// repeats.
+    panel_templates: Vec<PanelState>,
+    /// Template and bound values each panel was expanded from. Parallel to
+    /// `panels` while `panel_templates` is in use.
+    repeat_keys: Vec<RepeatKey>,
+    /// Last rendered width of each panel, keyed by panel index.
+    pub(crate) panel_widths: HashMap<usize, u16>,
+    /// Timestamp at which the last refresh was started.
     pub(crate) last_refresh: Instant,
     /// Query end timestamp used by the currently rendered data.
     pub(crate) view_end_ts: i64,
@@ -295,8 +804,18 @@ pub(crate) struct AppState {
     pub(crate) title: String,
     /// Whether to show the debug bar.
     pub(crate) debug_bar: bool,
-    /// Template variables (key -> value).
-    pub(crate) vars: HashMap<String, String>,
+    /// Template variables by name.
+    pub(crate) vars: HashMap<String, Variable>,
+    /// Template variable names in dashboard order, for the variable bar.
+    pub(crate) var_order: Vec<String>,
+    /// Open variable picker popup, if any.
+    pub(crate) var_picker: Option<VariablePicker>,
+    /// Ad-hoc label matchers added to every panel query.
+    pub(crate) filters: Vec<LabelMatcher>,
+    /// Text being edited in the filter editor.
+    pub(crate) filter_input: String,
+    /// Why the last filter edit could not be applied.
+    pub(crate) filter_error: Option<String>,
     /// Prometheus-backed template variables imported from Grafana.
     pub(crate) query_vars: Vec<TemplateQueryVar>,
     /// Count of panels skipped during import.
@@ -327,6 +846,8 @@ pub(crate) struct AppState {
     pub(crate) recording: Option<RecordingState>,
     /// Last export or recording status message.
     pub(crate) export_status: Option<String>,
+    /// Background refresh task and its result channel.
+    refresher: Refresher,
 }
 
 impl AppState {
@@ -334,9 +855,9 @@ impl AppState {
     ///
     /// # Arguments
     ///
-    /// * `prometheus` - The Prometheus client.
+    /// * `datasources` - The Prometheus datasources.
     /// * `range` - The initial time range window.
-    /// * `step` - The query resolution step.
+    /// * `step` - The minimum query step.
     /// * `refresh_every` - The data refresh interval.
     /// * `title` - The dashboard title.
     /// * `panels` - The list of panels to display.
@@ -344,7 +865,7 @@ impl AppState {
     /// * `theme` - The UI theme to use.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
-        prometheus: prom::PromClient,
+        datasources: DatasourceRegistry,
         range: Duration,
         step: Duration,
         refresh_every: Duration,
@@ -356,17 +877,26 @@ impl AppState {
         export: ExportOptions,
     ) -> Self {
         Self {
-            prometheus,
+            datasources,
+            datasource_vars: Vec::new(),
             range,
             step,
             refresh_every,
             panels,
+            panel_templates: Vec::new(),
+            repeat_keys: Vec::new(),
+            panel_widths: HashMap::new(),
             last_refresh: Instant::now() - refresh_every,
             view_end_ts: chrono::Utc::now().timestamp(),
             vertical_scroll: 0,
             title,
             debug_bar: false,
             vars: HashMap::new(),
+            var_order: Vec::new(),
+            var_picker: None,
+            filters: Vec::new(),
+            filter_input: String::new(),
+            filter_error: None,
             query_vars: Vec::new(),
             skipped_panels,
             selected_panel: 0,
@@ -382,6 +912,7 @@ impl AppState {
             export,
             recording: None,
             export_status: None,
+            refresher: Refresher::new(),
         }
     }
 
@@ -408,38 +939,154 @@ impl AppState {
 
     /// Automatically scroll to ensure the selected panel is visible.
     pub(crate) fn scroll_to_selected_panel(&mut self) {
-        if let Some(panel) = self.panels.get(self.selected_panel) {
-            if let Some(grid) = panel.grid {
-                let py = grid.y;
-                let ph = grid.h;
-                let scroll_y = self.vertical_scroll as i32;
-                let visible_height = 20;
-
-                if py < scroll_y {
-                    self.vertical_scroll = py as usize;
-                } else if py + ph > scroll_y + visible_height {
-                    self.vertical_scroll = (py + ph - visible_height).max(0) as usize;
-                }
+        if let Some(panel) = self.panels.get(self.selected_panel)
+            && let Some(grid) = panel.grid
+        {
+            let py = grid.y;
+            let ph = grid.h;
+            let scroll_y = self.vertical_scroll as i32;
+            let visible_height = 20;
+
+            if py < scroll_y {
+                self.vertical_scroll = py as usize;
+            } else if py + ph > scroll_y + visible_height {
+                self.vertical_scroll = (py + ph - visible_height).max(0) as usize;
             }
         }
     }
 
     /// Selects the previous panel, keeping the dashboard scrolled to it.
+    ///
+    /// Panels inside collapsed rows are skipped.
     pub(crate) fn select_previous_panel(&mut self) {
-        if self.selected_panel > 0 {
-            self.selected_panel -= 1;
+        if let Some(index) = (0..self.selected_panel)
+            .rev()
+            .find(|&i| !self.is_panel_hidden(i))
+        {
+            self.selected_panel = index;
             self.scroll_to_selected_panel();
         }
     }
 
     /// Selects the next panel, keeping the dashboard scrolled to it.
+    ///
+    /// Panels inside collapsed rows are skipped.
     pub(crate) fn select_next_panel(&mut self) {
-        if self.selected_panel < self.panels.len().saturating_sub(1) {
-            self.selected_panel += 1;
+        if let Some(index) =
+            (self.selected_panel + 1..self.panels.len()).find(|&i| !self.is_panel_hidden(i))
+        {
+            self.selected_panel = index;
             self.scroll_to_selected_panel();
         }
     }
 
+    /// Scrolls the legend of the selected panel by `delta` rows.
+    pub(crate) fn scroll_legend(&mut self, delta: isize) {
+        if let Some(panel) = self.panels.get_mut(self.selected_panel) {
+            let last = panel.series.len().saturating_sub(1);
+            panel.legend_scroll = panel.legend_scroll.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Index of the row grouping the panel at `index`.
+    ///
+    /// A row groups the panels that follow it up to the next row.
+    pub(crate) fn row_of(&self, index: usize) -> Option<usize> {
+        if self.panels.get(index)?.is_row() {
+            return None;
+        }
+        (0..index).rev().find(|&i| self.panels[i].is_row())
+    }
+
+    /// Whether the panel at `index` is hidden by a collapsed row.
+    pub(crate) fn is_panel_hidden(&self, index: usize) -> bool {
+        self.row_of(index)
+            .is_some_and(|row| self.panels[row].is_collapsed_row())
+    }
+
+    /// Collapses or expands the row at `index`, or the row grouping the
+    /// panel at `index`.
+    ///
+    /// Collapsing selects the row itself so the selection stays visible.
+    /// Expanding refreshes, since collapsed panels are not queried.
+    pub(crate) fn toggle_row(&mut self, index: usize) {
+        let Some(row) = self.panels.get(index).and_then(|p| {
+            if p.is_row() {
+                Some(index)
+            } else {
+                self.row_of(index)
+            }
+        }) else {
+            return;
+        };
+        let collapsed = !self.panels[row].is_collapsed_row();
+        self.panels[row].options = PanelOptions::Row { collapsed };
+        if collapsed {
+            if self.is_panel_hidden(self.selected_panel) {
+                self.selected_panel = row;
+            }
+        } else {
+            self.start_refresh();
+        }
+    }
+
+    /// Expands the row hiding the panel at `index`, if any.
+    pub(crate) fn reveal_panel(&mut self, index: usize) {
+        if self.is_panel_hidden(index) {
+            self.toggle_row(index);
+        }
+    }
+
+    /// Keeps the dashboard panels as templates when any of them repeats, and
+    /// expands the repeats for the current variable values.
+    pub(crate) fn init_repeats(&mut self) {
+        if self.panels.iter().any(|p| p.repeat.is_some()) {
+            self.panel_templates = self.panels.clone();
+            self.repeat_keys = (0..self.panels.len()).map(|i| (i, Vec::new())).collect();
+            self.expand_repeats();
+        }
+    }
+
+    /// Expands repeated panels again after variables changed.
+    ///
+    /// Clones that survive keep their data and view state, and so does the
+    /// selection. Returns whether the set of clones changed.
+    fn expand_repeats(&mut self) -> bool {
+        if self.panel_templates.is_empty() {
+            return false;
+        }
+        let expanded = expand_repeats(&self.panel_templates, &self.vars);
+        if expanded.iter().map(|(key, _)| key).eq(&self.repeat_keys) {
+            return false;
+        }
+
+        let selected = self.repeat_keys.get(self.selected_panel).cloned();
+        let mut previous: HashMap<RepeatKey, PanelState> = self
+            .repeat_keys
+            .drain(..)
+            .zip(self.panels.drain(..))
+            .collect();
+        for (key, panel) in expanded {
+            let panel = match previous.remove(&key) {
+                Some(old) => PanelState {
+                    title: panel.title,
+                    grid: panel.grid,
+                    scoped_vars: panel.scoped_vars,
+                    ..old
+                },
+                None => panel,
+            };
+            self.repeat_keys.push(key);
+            self.panels.push(panel);
+        }
+        self.selected_panel = selected
+            .and_then(|key| self.repeat_keys.iter().position(|k| *k == key))
+            .unwrap_or(self.selected_panel)
+            .min(self.panels.len().saturating_sub(1));
+        self.panel_widths.clear();
+        true
+    }
+
     /// Pan right: shift the time window forward (toward "now").
     pub(crate) fn pan_right(&mut self) {
         // Shift by 25% of the current range
@@ -451,6 +1098,148 @@ impl AppState {
         }
     }
 
+    /// Switches the default datasource to the next configured one.
+    ///
+    /// Grafana datasource variables follow the new default so dashboards that
+    /// reference `$datasource` switch along with panels using the default.
+    pub(crate) fn cycle_datasource(&mut self) {
+        if self.datasources.entries().len() < 2 {
+            return;
+        }
+        let name = self.datasources.cycle_default().name.clone();
+        for var in &self.datasource_vars {
+            self.vars
+                .insert(var.clone(), Variable::single(name.clone()));
+        }
+    }
+
+    /// Names of the variables shown in the variable bar.
+    ///
+    /// Dashboard variables come first in dashboard order, followed by any
+    /// variables only set with `--var` or the config file. Constants are
+    /// left out.
+    pub(crate) fn variable_names(&self) -> Vec<&str> {
+        let mut extra: Vec<&str> = self
+            .vars
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !self.var_order.iter().any(|known| known == name))
+            .collect();
+        extra.sort_unstable();
+        self.var_order
+            .iter()
+            .map(String::as_str)
+            .chain(extra)
+            .filter(|name| {
+                self.vars
+                    .get(*name)
+                    .is_none_or(|var| var.kind != VariableKind::Constant)
+            })
+            .collect()
+    }
+
+    /// Opens the picker for the variable at `index` in the variable bar.
+    pub(crate) fn open_variable_picker(&mut self, index: usize) {
+        let Some(name) = self
+            .variable_names()
+            .get(index)
+            .map(|name| name.to_string())
+        else {
+            return;
+        };
+        let var = self.vars.get(&name).cloned().unwrap_or_default();
+        let options: Vec<String> = if self.datasource_vars.contains(&name) {
+            self.datasources
+                .entries()
+                .iter()
+                .map(|ds| ds.name.clone())
+                .collect()
+        } else {
+            var.options.clone()
+        };
+        self.var_picker = Some(VariablePicker::new(&name, &var, &options));
+        self.mode = AppMode::VariablePicker;
+    }
+
+    /// Moves the open picker to the next or previous variable.
+    pub(crate) fn cycle_variable_picker(&mut self, delta: isize) {
+        let names = self.variable_names();
+        let Some(current) = self
+            .var_picker
+            .as_ref()
+            .and_then(|picker| names.iter().position(|name| *name == picker.name))
+        else {
+            return;
+        };
+        let index = (current as isize + delta).rem_euclid(names.len() as isize) as usize;
+        self.open_variable_picker(index);
+    }
+
+    /// Closes the variable picker without changing anything.
+    pub(crate) fn close_variable_picker(&mut self) {
+        self.var_picker = None;
+        self.mode = AppMode::Normal;
+    }
+
+    /// Applies the picker selection and refreshes.
+    ///
+    /// The refresh resolves query variables in dashboard order, so variables
+    /// that depend on the changed one are re-resolved against its new value.
+    pub(crate) fn apply_variable_picker(&mut self) {
+        let Some(picker) = self.var_picker.take() else {
+            return;
+        };
+        self.mode = AppMode::Normal;
+        let Some(values) = picker.confirm() else {
+            return;
+        };
+
+        if self.datasource_vars.contains(&picker.name)
+            && let Some(name) = values.first()
+            && let Some(ds) = self.datasources.select_default(name)
+        {
+            let name = ds.name.clone();
+            for var in &self.datasource_vars {
+                self.vars
+                    .insert(var.clone(), Variable::single(name.clone()));
+            }
+        } else {
+            self.vars.entry(picker.name).or_default().select(values);
+        }
+        self.expand_repeats();
+        self.restart_refresh();
+    }
+
+    /// Opens the filter editor with the current filters.
+    pub(crate) fn open_filter_editor(&mut self) {
+        self.filter_input = format_matchers(&self.filters);
+        self.filter_error = None;
+        self.mode = AppMode::FilterEditor;
+    }
+
+    /// Closes the filter editor without changing anything.
+    pub(crate) fn close_filter_editor(&mut self) {
+        self.filter_input.clear();
+        self.filter_error = None;
+        self.mode = AppMode::Normal;
+    }
+
+    /// Replaces the filters with the edited list and refreshes.
+    ///
+    /// The editor stays open with an error when the list does not parse.
+    pub(crate) fn apply_filter_editor(&mut self) {
+        match parse_matchers(&self.filter_input) {
+            Ok(filters) => {
+                self.close_filter_editor();
+                if filters != self.filters {
+                    self.filters = filters;
+                    self.restart_refresh();
+                }
+            }
+            Err(e) => self.filter_error = Some(e.to_string()),
+        }
+    }
+
     /// Reset to live mode (time_offset = 0).
     pub(crate) fn reset_to_live(&mut self) {
         self.time_offset = Duration::from_secs(0);
@@ -473,12 +1262,55 @@ impl AppState {
         self.cursor_x = Some((start_ts + end_ts) / 2.0);
     }
 
+    /// Records the rendered width of the visible panels.
+    ///
+    /// Panels that are currently off-screen keep their last known width.
+    pub(crate) fn update_panel_widths(&mut self, viewport: ratatui::layout::Rect) {
+        for (rect, idx) in crate::ui::visible_panel_rects(viewport, self) {
+            // Leave out the panel borders.
+            self.panel_widths
+                .insert(idx, rect.width.saturating_sub(2).max(1));
+        }
+    }
+
+    /// Step between the data points currently shown by `panel`.
+    pub(crate) fn panel_step(&self, panel: &PanelState) -> Duration {
+        panel.last_step.unwrap_or(self.step)
+    }
+
+    /// Values of the panel's series at the inspect cursor, keyed by series name.
+    /// Series without a sample within two steps of the cursor are left out.
+    pub(crate) fn cursor_values(&self, panel: &PanelState) -> HashMap<String, f64> {
+        let mut values = HashMap::new();
+        let Some(cursor_x) = self.cursor_x else {
+            return values;
+        };
+
+        for series in &panel.series {
+            let closest = series.points.iter().min_by(|a, b| {
+                let da = (a.0 - cursor_x).abs();
+                let db = (b.0 - cursor_x).abs();
+                da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            if let Some((ts, value)) = closest
+                && (ts - cursor_x).abs() <= self.panel_step(panel).as_secs_f64() * 2.0
+            {
+                values.insert(series.name.clone(), *value);
+            }
+        }
+        values
+    }
+
     /// Move cursor left/right by one step.
     pub(crate) fn move_cursor(&mut self, direction: i32) {
         let (start_ts, end_ts) = self.time_bounds();
 
         if let Some(current_x) = self.cursor_x {
-            let step_secs = self.step.as_secs_f64();
+            let step_secs = self
+                .panels
+                .get(self.selected_panel)
+                .map_or(self.step, |p| self.panel_step(p))
+                .as_secs_f64();
             let new_x = current_x + (direction as f64 * step_secs);
             self.cursor_x = Some(new_x.max(start_ts).min(end_ts));
         } else {
@@ -486,148 +1318,118 @@ impl AppState {
         }
     }
 
-    pub(crate) async fn refresh(&mut self) -> Result<()> {
-        let range = self.range;
-        let step = self.step;
+    /// Starts a periodic refresh unless one is already running.
+    ///
+    /// Returns whether a refresh was started.
+    pub(crate) fn request_refresh(&mut self) -> bool {
+        if self.refresher.is_running() {
+            return false;
+        }
+        self.start_refresh();
+        true
+    }
+
+    /// Refreshes after the view changed, cancelling any refresh in flight.
+    ///
+    /// Panels keep showing their previous data, marked stale, until the new
+    /// results arrive.
+    pub(crate) fn restart_refresh(&mut self) {
+        for p in self.panels.iter_mut().filter(|p| p.has_queries()) {
+            p.stale = true;
+        }
+        self.start_refresh();
+    }
 
+    /// Whether a background refresh is in flight.
+    pub(crate) fn is_refreshing(&self) -> bool {
+        self.refresher.is_running()
+    }
+
+    fn start_refresh(&mut self) {
         // Calculate end timestamp: "now" minus time_offset
         let end_ts = chrono::Utc::now().timestamp() - self.time_offset.as_secs() as i64;
 
-        let _ = refresh_query_variables(
-            &self.prometheus,
-            &self.query_vars,
-            range,
-            step,
+        // Rows and text panels have no queries, and collapsed rows stop
+        // querying their panels.
+        let skipped: Vec<bool> = (0..self.panels.len())
+            .map(|i| !self.panels[i].has_queries() || self.is_panel_hidden(i))
+            .collect();
+        let panels = self
+            .panels
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| !skipped[*i])
+            .map(|(i, p)| {
+                p.loading = true;
+                let snapshot = PanelState {
+                    series: Vec::new(),
+                    ..p.clone()
+                };
+                (i, snapshot)
+            })
+            .collect();
+
+        self.refresher.start(RefreshJob {
+            datasources: self.datasources.clone(),
+            query_vars: self.query_vars.clone(),
+            vars: self.vars.clone(),
+            filters: self.filters.clone(),
+            panels,
+            panel_widths: self.panel_widths.clone(),
+            range: self.range,
+            min_step: self.step,
             end_ts,
-            &mut self.vars,
-        )
-        .await;
-
-        let prometheus = &self.prometheus;
-        let vars = &self.vars;
-
-        // Create a stream of futures for fetching panel data
-        let mut futures = futures::stream::iter(self.panels.iter_mut())
-            .map(|p| Self::fetch_single_panel_data(prometheus, p, range, step, vars, end_ts))
-            .buffer_unordered(4); // Max 4 concurrent panel refreshes
-
-        while let Some((p, results, url, err)) = futures.next().await {
-            p.series = results;
-            p.last_samples = p.series.iter().map(|s| s.points.len()).sum();
-            if let Some(u) = url {
-                p.last_url = Some(u);
-            }
-            p.last_error = err;
-        }
-
-        self.view_end_ts = end_ts;
+        });
         self.last_refresh = Instant::now();
-        Ok(())
     }
 
-    async fn fetch_single_panel_data<'a>(
-        prometheus: &'a prom::PromClient,
-        p: &'a mut PanelState,
-        range: Duration,
-        step: Duration,
-        vars: &'a HashMap<String, String>,
-        end_ts: i64,
-    ) -> (
-        &'a mut PanelState,
-        Vec<SeriesView>,
-        Option<String>,
-        Option<String>,
-    ) {
-        let mut panel_results = Vec::new();
-        let mut last_url = None;
-        let mut error = None;
-
-        for (i, expr) in p.exprs.iter().enumerate() {
-            let expr_expanded = expand_expr(expr, range, step, vars);
-            let legend_fmt = p.legends.get(i).and_then(|x| x.as_ref());
-            let query_mode = p.query_mode(i);
-
-            // Calculate start/end for URL display purposes
-            let start_ts = end_ts - (range.as_secs() as i64);
-
-            let url = match query_mode {
-                QueryMode::Range => {
-                    prometheus.build_query_range_url(&expr_expanded, start_ts, end_ts, step)
-                }
-                QueryMode::Instant => prometheus.build_query_url(&expr_expanded, end_ts),
-            };
-            last_url = Some(url);
-
-            let query_result = match query_mode {
-                QueryMode::Range => {
-                    prometheus
-                        .query_range(&expr_expanded, start_ts, end_ts, step)
-                        .await
-                }
-                QueryMode::Instant => {
-                    prometheus
-                        .query_instant_series(&expr_expanded, end_ts)
-                        .await
+    /// Applies results reported by the background refresh.
+    ///
+    /// Returns whether anything changed.
+    pub(crate) fn apply_refresh_updates(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(message) = self.refresher.try_recv() {
+            changed = true;
+            match message {
+                RefreshMessage::Variables(vars) => {
+                    self.vars = vars;
+                    // New clones need data, and the panel indices of this
+                    // refresh no longer match.
+                    if self.expand_repeats() {
+                        self.start_refresh();
+                    }
                 }
-            };
-
-            match query_result {
-                Ok(res) => {
-                    for s in res {
-                        let latest_val = s.values.last().and_then(|(_, v)| v.parse::<f64>().ok());
-                        let legend_base = if let Some(fmt) = legend_fmt {
-                            format_legend(fmt, &s.metric)
-                        } else if s.metric.is_empty() {
-                            expr_expanded.clone()
-                        } else {
-                            let mut labels: Vec<_> = s
-                                .metric
-                                .iter()
-                                .map(|(k, v)| format!("{}=\"{}\"", k, v))
-                                .collect();
-                            labels.sort();
-                            format!("{} {{{}}}", expr_expanded, labels.join(", "))
-                        };
-
-                        let mut pts = Vec::with_capacity(s.values.len());
-                        for (ts, val) in s.values {
-                            if let Ok(y) = val.parse::<f64>() {
-                                if y.is_finite() {
-                                    pts.push((ts, y));
-                                }
-                            }
+                RefreshMessage::Panel { index, update } => {
+                    if let Some(p) = self.panels.get_mut(index) {
+                        p.series = update.series;
+                        apply_overrides(&p.overrides, &p.display, &mut p.series);
+                        p.last_samples = p.series.iter().map(|s| s.points.len()).sum();
+                        if let Some(u) = update.url {
+                            p.last_url = Some(u);
                         }
-                        panel_results.push(SeriesView {
-                            name: legend_base,
-                            value: latest_val,
-                            points: downsample(pts, 200),
-                            visible: true,
-                        });
+                        p.last_step = update.step;
+                        p.last_error = update.error;
+                        p.loading = false;
+                        p.stale = false;
                     }
                 }
-                Err(e) => {
-                    let query_name = match query_mode {
-                        QueryMode::Range => "query_range",
-                        QueryMode::Instant => "query",
-                    };
-                    error = Some(format!(
-                        "{} failed for `{}`: {}",
-                        query_name, expr_expanded, e
-                    ));
-                }
+                RefreshMessage::Finished { end_ts } => self.view_end_ts = end_ts,
             }
         }
-        (p, panel_results, last_url, error)
+        changed
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::reduce::FieldSelector;
+    use crate::prom;
+    use crate::ui::GrafanaRegex;
 
     fn create_test_app() -> AppState {
         AppState::new(
-            prom::PromClient::new("http://localhost:9090".to_string()),
+            DatasourceRegistry::single(prom::PromClient::new("http://localhost:9090".to_string())),
             Duration::from_secs(3600),
             Duration::from_secs(60),
             Duration::from_millis(1000),
@@ -644,7 +1446,11 @@ mod tests {
     async fn test_empty_panels() {
         let mut app = create_test_app();
 
-        assert!(app.refresh().await.is_ok());
+        assert!(app.request_refresh());
+        while app.is_refreshing() {
+            tokio::task::yield_now().await;
+            app.apply_refresh_updates();
+        }
 
         app.scroll_to_selected_panel();
         assert_eq!(app.selected_panel, 0);
@@ -684,7 +1490,7 @@ mod tests {
     fn test_select_panel_navigation_is_bounded() {
         let prom = prom::PromClient::new("http://localhost:9090".to_string());
         let mut app = AppState::new(
-            prom,
+            DatasourceRegistry::single(prom),
             Duration::from_secs(3600),
             Duration::from_secs(60),
             Duration::from_millis(1000),
@@ -712,6 +1518,98 @@ mod tests {
         assert_eq!(app.selected_panel, 0);
     }
 
+    #[test]
+    fn test_repeats_follow_variable_values() {
+        let mut app = create_test_app();
+        app.panels = crate::app::default_queries(vec!["up{host=\"$host\"}".to_string()]);
+        app.panels[0].title = "Up $host".to_string();
+        app.panels[0].grid = Some(GridUnit {
+            x: 0,
+            y: 0,
+            w: 24,
+            h: 8,
+        });
+        app.panels[0].repeat = Some(PanelRepeat {
+            var: "host".to_string(),
+            direction: RepeatDirection::Horizontal,
+            max_per_row: None,
+        });
+        app.vars.insert(
+            "host".to_string(),
+            Variable::with_values(vec!["a".to_string(), "b".to_string()]),
+        );
+        app.init_repeats();
+        let titles: Vec<&str> = app.panels.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, vec!["Up a", "Up b"]);
+
+        app.panels[1].last_samples = 42;
+        app.selected_panel = 1;
+        app.vars
+            .get_mut("host")
+            .unwrap()
+            .select(vec!["b".to_string()]);
+        assert!(app.expand_repeats());
+        assert_eq!(app.panels.len(), 1);
+        assert_eq!(app.panels[0].title, "Up b");
+        assert_eq!(app.panels[0].last_samples, 42);
+        assert_eq!(app.panels[0].grid.unwrap().w, 24);
+        assert_eq!(app.selected_panel, 0);
+        assert!(!app.expand_repeats());
+    }
+
+    #[tokio::test]
+    async fn test_collapsed_rows_hide_their_panels() {
+        let mut app = create_test_app();
+        app.panels = crate::app::default_queries(
+            ["a", "Row 1", "b", "c", "Row 2", "d"]
+                .map(String::from)
+                .to_vec(),
+        );
+        let grids = [
+            (0, 0, 4),
+            (0, 4, 1),
+            (0, 5, 4),
+            (12, 5, 4),
+            (0, 9, 1),
+            (0, 10, 4),
+        ];
+        for (panel, (x, y, h)) in app.panels.iter_mut().zip(grids) {
+            let w = if h == 1 { 24 } else { 12 };
+            panel.grid = Some(GridUnit { x, y, w, h });
+        }
+        for row in [1, 4] {
+            app.panels[row].panel_type = PanelType::Row;
+            app.panels[row].options = PanelOptions::Row { collapsed: false };
+            app.panels[row].exprs.clear();
+        }
+
+        app.selected_panel = 2;
+        app.toggle_row(2);
+        assert!(app.panels[1].is_collapsed_row());
+        assert_eq!(app.selected_panel, 1);
+        assert!(app.is_panel_hidden(2) && app.is_panel_hidden(3));
+        assert!(!app.is_panel_hidden(5));
+
+        app.select_next_panel();
+        assert_eq!(app.selected_panel, 4);
+
+        let viewport = ratatui::layout::Rect::new(0, 0, 120, 80);
+        let rects = crate::ui::visible_panel_rects(viewport, &app);
+        let shown: Vec<usize> = rects.iter().map(|(_, i)| *i).collect();
+        assert_eq!(shown, vec![0, 1, 4, 5]);
+        // The second row header moves up to the line below the first one.
+        assert_eq!(rects[2].0.y, rects[1].0.y + 1);
+        assert_eq!(rects[1].0.height, 1);
+
+        assert!(app.request_refresh());
+        assert!(app.panels[0].loading && app.panels[5].loading);
+        assert!(!app.panels[1].loading && !app.panels[2].loading);
+
+        app.toggle_row(1);
+        assert!(!app.is_panel_hidden(2));
+        assert!(app.panels[2].loading);
+    }
+
     #[test]
     fn test_panel_query_mode_defaults_to_range_when_missing() {
         let panel = PanelState {
@@ -719,25 +1617,92 @@ mod tests {
             exprs: vec!["up".to_string(), "rate(up[5m])".to_string()],
             legends: vec![None, None],
             query_modes: vec![QueryMode::Instant],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: QueryResolution::default(),
             series: vec![],
             last_error: None,
             last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
             last_samples: 0,
             grid: None,
             y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
             panel_type: PanelType::Graph,
             thresholds: None,
             min: None,
             max: None,
             autogrid: None,
             display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
             options: PanelOptions::None,
+            repeat: None,
+            scoped_vars: HashMap::new(),
         };
 
         assert_eq!(panel.query_mode(0), QueryMode::Instant);
         assert_eq!(panel.query_mode(1), QueryMode::Range);
     }
 
+    #[test]
+    fn test_panel_query_step_uses_width_and_target_options() {
+        let panel = PanelState {
+            title: "Steps".to_string(),
+            exprs: vec!["up".to_string(), "up".to_string()],
+            legends: vec![None, None],
+            query_modes: vec![],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: QueryResolution {
+                max_data_points: None,
+                min_interval: None,
+                target_intervals: vec![None, Some(Duration::from_secs(300))],
+                interval_factors: vec![2],
+            },
+            series: vec![],
+            last_error: None,
+            last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
+            last_samples: 0,
+            grid: None,
+            y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
+            panel_type: PanelType::Graph,
+            thresholds: None,
+            min: None,
+            max: None,
+            autogrid: None,
+            display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
+            options: PanelOptions::None,
+            repeat: None,
+            scoped_vars: HashMap::new(),
+        };
+        let range = Duration::from_secs(3600);
+        let min_step = Duration::from_secs(5);
+
+        // 3600s over 60 columns is 60s, doubled by intervalFactor.
+        assert_eq!(
+            panel.query_step(0, range, min_step, Some(60)),
+            Duration::from_secs(120)
+        );
+        // Without a known width 200 points are used: 18s rounds up to 30s,
+        // doubled by intervalFactor.
+        assert_eq!(
+            panel.query_step(0, range, min_step, None),
+            Duration::from_secs(60)
+        );
+        // The target interval raises the minimum step.
+        assert_eq!(
+            panel.query_step(1, range, min_step, Some(60)),
+            Duration::from_secs(300)
+        );
+    }
+
     #[test]
     fn test_default_graph_options_match_current_line_rendering() {
         let options = GraphOptions::default();
@@ -746,7 +1711,7 @@ mod tests {
         assert_eq!(options.show_points, GraphPointMode::Auto);
         assert_eq!(options.fill_opacity, None);
         assert_eq!(options.axis_placement, GraphAxisPlacement::Visible);
-        assert_eq!(options.line_interpolation, None);
+        assert_eq!(options.line_interpolation, GraphLineInterpolation::Linear);
         assert_eq!(options.stacking, GraphStackingMode::Off);
     }
 
@@ -757,21 +1722,123 @@ mod tests {
             exprs: vec![],
             legends: vec![],
             query_modes: vec![],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: QueryResolution::default(),
             series: vec![],
             last_error: None,
             last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
             last_samples: 0,
             grid: None,
             y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
             panel_type: PanelType::Stat,
             thresholds: None,
             min: None,
             max: None,
             autogrid: None,
             display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
             options: PanelOptions::None,
+            repeat: None,
+            scoped_vars: HashMap::new(),
         };
 
         assert_eq!(panel.graph_options(), GraphOptions::default());
     }
+
+    #[test]
+    fn test_reduced_values_follow_reduce_options() {
+        let series = |name: &str, values: &[f64]| SeriesView {
+            name: name.to_string(),
+            value: values.last().copied(),
+            points: values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i as f64, *v))
+                .collect(),
+            visible: true,
+            ref_id: "A".to_string(),
+            calcs: vec![(Reducer::Max, Some(99.0))],
+            ..Default::default()
+        };
+        let mut panel = PanelState {
+            title: "Reduce".to_string(),
+            exprs: vec!["up".to_string()],
+            legends: vec![None],
+            query_modes: vec![],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: QueryResolution::default(),
+            series: vec![series("api_a", &[1.0, 3.0]), series("db", &[5.0, 7.0])],
+            last_error: None,
+            last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
+            last_samples: 0,
+            grid: None,
+            y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
+            panel_type: PanelType::Table,
+            thresholds: None,
+            min: None,
+            max: None,
+            autogrid: None,
+            display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
+            options: PanelOptions::None,
+            repeat: None,
+            scoped_vars: HashMap::new(),
+        };
+        let values = |panel: &PanelState| {
+            panel
+                .reduced_values()
+                .into_iter()
+                .map(|(s, values)| (s.name.clone(), values))
+                .collect::<Vec<_>>()
+        };
+
+        // Without reduce options, panels keep showing the latest value.
+        assert_eq!(
+            values(&panel),
+            vec![
+                ("api_a".to_string(), vec![Some(3.0)]),
+                ("db".to_string(), vec![Some(7.0)]),
+            ]
+        );
+
+        // Results computed at query time win over the downsampled points.
+        panel.options = PanelOptions::Reduce(ReduceOptions {
+            calcs: vec![Reducer::Max, Reducer::Mean],
+            ..Default::default()
+        });
+        assert_eq!(
+            values(&panel)[1],
+            ("db".to_string(), vec![Some(99.0), Some(6.0)])
+        );
+
+        panel.options = PanelOptions::Reduce(ReduceOptions {
+            values: true,
+            limit: Some(3),
+            ..Default::default()
+        });
+        assert_eq!(
+            values(&panel),
+            vec![
+                ("api_a".to_string(), vec![Some(1.0)]),
+                ("api_a".to_string(), vec![Some(3.0)]),
+                ("db".to_string(), vec![Some(5.0)]),
+            ]
+        );
+
+        panel.options = PanelOptions::Reduce(ReduceOptions {
+            fields: Some(FieldSelector::Regex(GrafanaRegex::new("/^api_/").unwrap())),
+            ..Default::default()
+        });
+        assert_eq!(values(&panel), vec![("api_a".to_string(), vec![Some(3.0)])]);
+    }
 }
@@ -14,14 +14,352 @@
  * limitations under the License.
  */
 
-use super::data::expand_expr;
-use crate::grafana::TemplateQueryVar;
+use super::data::{expand_expr, format_prom_duration, query_step};
+use super::datasources::DatasourceRegistry;
+use crate::grafana::{TemplateQueryVar, TemplateVarSource};
 use crate::prom;
 use anyhow::{Result, anyhow};
 use regex::Regex;
 use std::collections::HashMap;
 use std::time::Duration;
 
+/// Grafana's placeholder value for the "All" option.
+pub(crate) const ALL_VALUE: &str = "$__all";
+
+/// Value selecting the automatic option of an interval variable.
+pub(crate) const AUTO_INTERVAL: &str = "auto";
+
+/// Grafana template variable types.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum VariableKind {
+    /// Options resolved from a Prometheus query.
+    Query,
+    /// Fixed comma-separated options.
+    Custom,
+    /// Fixed value, hidden from the variable bar.
+    Constant,
+    /// Durations, optionally with an `auto` option that follows the range.
+    Interval,
+    /// Free-form text.
+    Textbox,
+    /// Prometheus datasource name.
+    Datasource,
+    /// Set with `--var` or the config file only.
+    #[default]
+    User,
+}
+
+impl VariableKind {
+    /// Parses a Grafana variable `type`.
+    pub(crate) fn parse(var_type: &str) -> Option<Self> {
+        let kind = match var_type {
+            "query" => Self::Query,
+            "custom" => Self::Custom,
+            "constant" => Self::Constant,
+            "interval" => Self::Interval,
+            "textbox" => Self::Textbox,
+            "datasource" => Self::Datasource,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Query => "query",
+            Self::Custom => "custom",
+            Self::Constant => "constant",
+            Self::Interval => "interval",
+            Self::Textbox => "textbox",
+            Self::Datasource => "datasource",
+            Self::User => "user",
+        }
+    }
+}
+
+/// Current state of a template variable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Variable {
+    pub(crate) kind: VariableKind,
+    /// Selected values. Ignored while `all` is set.
+    pub(crate) current: Vec<String>,
+    /// Whether the "All" option is selected.
+    pub(crate) all: bool,
+    /// Whether more than one value may be selected (`multi`).
+    pub(crate) multi: bool,
+    /// Whether the variable offers an "All" option (`includeAll`).
+    pub(crate) include_all: bool,
+    /// Custom value interpolated verbatim for "All" (`allValue`).
+    pub(crate) all_value: Option<String>,
+    /// Values the variable can take, when known.
+    pub(crate) options: Vec<String>,
+    /// Interval the `auto` option currently stands for.
+    pub(crate) auto_value: Option<String>,
+    /// Whether the selection was set from `[vars]` or `--var`; refreshed
+    /// options then leave it alone.
+    pub(crate) pinned: bool,
+}
+
+impl Variable {
+    /// A plain variable holding a single value.
+    pub(crate) fn single(value: impl Into<String>) -> Self {
+        Self {
+            current: vec![value.into()],
+            ..Self::default()
+        }
+    }
+
+    /// A variable set to `values`; several values make it multi-valued.
+    pub(crate) fn with_values(values: Vec<String>) -> Self {
+        if values.iter().any(|value| value == ALL_VALUE) {
+            return Self {
+                all: true,
+                include_all: true,
+                ..Self::default()
+            };
+        }
+        Self {
+            multi: values.len() > 1,
+            current: values,
+            ..Self::default()
+        }
+    }
+
+    /// Values the variable currently stands for, with "All" expanded to
+    /// every known option.
+    pub(crate) fn values(&self) -> &[String] {
+        if self.all {
+            &self.options
+        } else if let Some(auto_value) = &self.auto_value
+            && self.current == [AUTO_INTERVAL]
+        {
+            std::slice::from_ref(auto_value)
+        } else {
+            &self.current
+        }
+    }
+
+    /// First selected value, used where only one value makes sense.
+    pub(crate) fn first(&self) -> Option<&str> {
+        self.values().first().map(String::as_str)
+    }
+
+    /// Interpolates the variable the way Grafana's Prometheus datasource does.
+    ///
+    /// Single-value variables are inserted as is. Variables that allow
+    /// several values are regex-escaped and joined as `(a|b|c)`. "All" uses
+    /// `allValue` verbatim when set and falls back to `.*` while no options
+    /// are known. Returns `None` when there is nothing to insert.
+    pub(crate) fn interpolate(&self) -> Option<String> {
+        if self.all {
+            if let Some(all_value) = &self.all_value {
+                return Some(all_value.clone());
+            }
+            if self.options.is_empty() {
+                return Some(".*".to_string());
+            }
+        }
+
+        let values = self.values();
+        if !self.multi && !self.include_all {
+            return values.first().cloned();
+        }
+        match values {
+            [] => None,
+            [value] => Some(regex_escape(value)),
+            values => Some(format!(
+                "({})",
+                values
+                    .iter()
+                    .map(|value| regex_escape(value))
+                    .collect::<Vec<_>>()
+                    .join("|")
+            )),
+        }
+    }
+
+    /// Selection as shown in the variable bar.
+    pub(crate) fn display(&self) -> String {
+        if self.all {
+            "All".to_string()
+        } else if self.current.is_empty() {
+            "-".to_string()
+        } else {
+            self.current.join(" + ")
+        }
+    }
+
+    /// Selects `values`; [`ALL_VALUE`] among them selects "All".
+    ///
+    /// Selecting several values makes the variable multi-valued.
+    pub(crate) fn select(&mut self, values: Vec<String>) {
+        self.multi |= values.len() > 1;
+        self.all = values.iter().any(|value| value == ALL_VALUE);
+        self.current = if self.all { Vec::new() } else { values };
+    }
+
+    /// Replaces the known options, keeping the selection where possible.
+    ///
+    /// Selected values that are no longer offered are dropped; when nothing
+    /// remains selected the first option is picked, as Grafana does. Pinned
+    /// selections are kept as they are.
+    pub(crate) fn set_options(&mut self, options: Vec<String>) {
+        self.options = options;
+        if self.all || self.pinned {
+            return;
+        }
+        self.current
+            .retain(|value| self.options.iter().any(|option| option == value));
+        if self.current.is_empty()
+            && let Some(first) = self.options.first()
+        {
+            self.current.push(first.clone());
+        }
+    }
+}
+
+/// State of the popup used to pick the value of a template variable.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VariablePicker {
+    /// Name of the variable being edited.
+    pub(crate) name: String,
+    /// Values offered, led by [`ALL_VALUE`] when the variable has "All".
+    pub(crate) options: Vec<String>,
+    /// Whether several values may be checked.
+    pub(crate) multi: bool,
+    /// Fuzzy filter typed by the user.
+    pub(crate) query: String,
+    /// Highlighted row among the filtered options.
+    pub(crate) cursor: usize,
+    /// Values checked so far.
+    pub(crate) selected: Vec<String>,
+}
+
+impl VariablePicker {
+    /// Opens a picker for `var`, offering `options` plus the current values.
+    pub(crate) fn new(name: &str, var: &Variable, options: &[String]) -> Self {
+        let mut all_options = Vec::new();
+        if var.include_all {
+            all_options.push(ALL_VALUE.to_string());
+        }
+        for value in options.iter().chain(&var.current) {
+            if !all_options.contains(value) {
+                all_options.push(value.clone());
+            }
+        }
+        let selected = if var.all {
+            vec![ALL_VALUE.to_string()]
+        } else {
+            var.current.clone()
+        };
+        let cursor = selected
+            .first()
+            .and_then(|value| all_options.iter().position(|option| option == value))
+            .unwrap_or(0);
+
+        Self {
+            name: name.to_string(),
+            options: all_options,
+            multi: var.multi,
+            query: String::new(),
+            cursor,
+            selected,
+        }
+    }
+
+    /// Options matching the filter, in their original order.
+    pub(crate) fn matches(&self) -> Vec<&str> {
+        self.options
+            .iter()
+            .map(String::as_str)
+            .filter(|option| fuzzy_match(&self.query, option_label(option)))
+            .collect()
+    }
+
+    pub(crate) fn highlighted(&self) -> Option<&str> {
+        self.matches().get(self.cursor).copied()
+    }
+
+    pub(crate) fn is_selected(&self, option: &str) -> bool {
+        self.selected.iter().any(|value| value == option)
+    }
+
+    pub(crate) fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.cursor = 0;
+    }
+
+    /// Moves the highlight by `delta` rows, staying within the matches.
+    pub(crate) fn move_cursor(&mut self, delta: isize) {
+        let last = self.matches().len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// Checks or unchecks the highlighted option.
+    ///
+    /// "All" and individual values exclude each other; single-value
+    /// variables keep only the last checked value.
+    pub(crate) fn toggle(&mut self) {
+        let Some(option) = self.highlighted().map(str::to_string) else {
+            return;
+        };
+        if !self.multi || option == ALL_VALUE {
+            self.selected = vec![option];
+        } else if self.is_selected(&option) {
+            self.selected.retain(|value| *value != option);
+        } else {
+            self.selected.retain(|value| value != ALL_VALUE);
+            self.selected.push(option);
+        }
+    }
+
+    /// Values to apply when the picker is confirmed.
+    ///
+    /// Single-value variables take the highlighted option. When nothing
+    /// matches the filter, the filter text itself is used as a free-form
+    /// value.
+    pub(crate) fn confirm(&self) -> Option<Vec<String>> {
+        if self.multi && !self.selected.is_empty() {
+            return Some(self.selected.clone());
+        }
+        if let Some(option) = self.highlighted() {
+            return Some(vec![option.to_string()]);
+        }
+        let query = self.query.trim();
+        (!query.is_empty()).then(|| vec![query.to_string()])
+    }
+}
+
+/// Label shown for an option, spelling out the "All" placeholder.
+pub(crate) fn option_label(option: &str) -> &str {
+    if option == ALL_VALUE { "All" } else { option }
+}
+
+/// Case-insensitive subsequence match, so `nd1` finds `node-1`.
+fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| text.any(|t| t == p))
+}
+
+/// Escapes regex metacharacters inside a PromQL string literal.
+pub(super) fn regex_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '\\' => escaped.push_str(r"\\\\"),
+            '$' | '^' | '*' | '+' | '?' | '.' | '(' | ')' | '|' | '{' | '}' | '[' | ']' | '\'' => {
+                escaped.push_str(r"\\");
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 enum PrometheusVariableQuery {
     LabelValues {
         metric: Option<String>,
@@ -31,21 +369,46 @@ enum PrometheusVariableQuery {
 }
 
 pub(crate) async fn refresh_query_variables(
-    prometheus: &prom::PromClient,
+    datasources: &DatasourceRegistry,
     query_vars: &[TemplateQueryVar],
     range: Duration,
     step: Duration,
     end_ts: i64,
-    vars: &mut HashMap<String, String>,
+    vars: &mut HashMap<String, Variable>,
 ) -> Result<()> {
     for query_var in query_vars {
-        let Some(value) =
-            resolve_query_variable(prometheus, query_var, range, step, end_ts, vars).await?
-        else {
-            continue;
-        };
+        match &query_var.source {
+            TemplateVarSource::Query {
+                query,
+                regex,
+                datasource,
+            } => {
+                let prometheus = &datasources.resolve(datasource.as_deref(), vars).client;
+                let options = resolve_query_variable(
+                    prometheus,
+                    query,
+                    regex.as_deref(),
+                    range,
+                    step,
+                    end_ts,
+                    vars,
+                )
+                .await?;
 
-        vars.insert(query_var.name.clone(), value);
+                vars.entry(query_var.name.clone())
+                    .or_insert_with(|| Variable {
+                        kind: VariableKind::Query,
+                        ..Variable::default()
+                    })
+                    .set_options(options);
+            }
+            TemplateVarSource::AutoInterval { count, min } => {
+                let interval = query_step(range, *count, *min, 1);
+                if let Some(var) = vars.get_mut(&query_var.name) {
+                    var.auto_value = Some(format_prom_duration(interval));
+                }
+            }
+        }
     }
 
     Ok(())
@@ -53,13 +416,14 @@ pub(crate) async fn refresh_query_variables(
 
 async fn resolve_query_variable(
     prometheus: &prom::PromClient,
-    query_var: &TemplateQueryVar,
+    query: &str,
+    regex: Option<&str>,
     range: Duration,
     step: Duration,
     end_ts: i64,
-    vars: &HashMap<String, String>,
-) -> Result<Option<String>> {
-    let expanded_query = expand_expr(&query_var.query, range, step, vars);
+    vars: &HashMap<String, Variable>,
+) -> Result<Vec<String>> {
+    let expanded_query = expand_expr(query, range, step, vars);
     let query = parse_prometheus_variable_query(&expanded_query)?;
     let start_ts = end_ts - range.as_secs() as i64;
     let values = match query {
@@ -79,10 +443,7 @@ async fn resolve_query_variable(
         }
     };
 
-    Ok(first_value(apply_regex(
-        values,
-        query_var.regex.as_deref(),
-    )?))
+    Ok(unique_values(apply_regex(values, regex)?))
 }
 
 fn parse_prometheus_variable_query(query: &str) -> Result<PrometheusVariableQuery> {
@@ -163,14 +524,116 @@ fn apply_regex(values: Vec<String>, regex: Option<&str>) -> Result<Vec<String>>
         .collect())
 }
 
-fn first_value(values: Vec<String>) -> Option<String> {
-    values.into_iter().find(|value| !value.is_empty())
+/// Drops empty and repeated values, keeping the first occurrence.
+fn unique_values(values: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::with_capacity(values.len());
+    for value in values {
+        if !value.is_empty() && !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn multi_var() -> Variable {
+        Variable {
+            current: vec!["node-1".to_string()],
+            multi: true,
+            include_all: true,
+            options: vec![
+                "node-1".to_string(),
+                "node-2".to_string(),
+                "db-1".to_string(),
+            ],
+            ..Variable::default()
+        }
+    }
+
+    #[test]
+    fn test_variable_picker_fuzzy_filter_and_multi_select() {
+        let var = multi_var();
+        let mut picker = VariablePicker::new("instance", &var, &var.options);
+        assert_eq!(picker.options[0], ALL_VALUE);
+        assert_eq!(picker.highlighted(), Some("node-1"));
+
+        picker.set_query("nd".to_string());
+        assert_eq!(picker.matches(), vec!["node-1", "node-2"]);
+        picker.move_cursor(1);
+        picker.toggle();
+        assert_eq!(
+            picker.confirm(),
+            Some(vec!["node-1".to_string(), "node-2".to_string()])
+        );
+
+        picker.set_query("al".to_string());
+        picker.toggle();
+        assert_eq!(picker.confirm(), Some(vec![ALL_VALUE.to_string()]));
+
+        let mut var = var;
+        var.select(picker.confirm().unwrap());
+        assert!(var.all);
+        assert_eq!(var.display(), "All");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_resolves_auto_interval_from_range() {
+        let datasources =
+            DatasourceRegistry::single(prom::PromClient::new("http://127.0.0.1:9".to_string()));
+        let query_vars = vec![TemplateQueryVar {
+            name: "interval".to_string(),
+            source: TemplateVarSource::AutoInterval {
+                count: 30,
+                min: Duration::from_secs(10),
+            },
+            query_path: "templating.list[0].auto_count".to_string(),
+        }];
+        let mut vars = HashMap::from([(
+            "interval".to_string(),
+            Variable {
+                kind: VariableKind::Interval,
+                current: vec![AUTO_INTERVAL.to_string()],
+                ..Variable::default()
+            },
+        )]);
+
+        for (range, expected) in [(3600, "2m"), (60, "10s")] {
+            refresh_query_variables(
+                &datasources,
+                &query_vars,
+                Duration::from_secs(range),
+                Duration::from_secs(15),
+                0,
+                &mut vars,
+            )
+            .await
+            .unwrap();
+            assert_eq!(vars["interval"].interpolate().as_deref(), Some(expected));
+        }
+
+        vars.get_mut("interval")
+            .unwrap()
+            .select(vec!["1h".to_string()]);
+        assert_eq!(vars["interval"].interpolate().as_deref(), Some("1h"));
+    }
+
+    #[test]
+    fn test_variable_picker_single_value_and_free_text() {
+        let var = Variable::single("node");
+        let options = vec!["node".to_string(), "api".to_string()];
+        let mut picker = VariablePicker::new("job", &var, &options);
+
+        picker.move_cursor(5);
+        assert_eq!(picker.confirm(), Some(vec!["api".to_string()]));
+
+        picker.set_query("custom".to_string());
+        assert!(picker.matches().is_empty());
+        assert_eq!(picker.confirm(), Some(vec!["custom".to_string()]));
+    }
+
     #[test]
     fn test_split_label_values_query() {
         let PrometheusVariableQuery::LabelValues { metric, label } =
@@ -204,6 +667,46 @@ mod tests {
 
         let values = apply_regex(values, Some(r#"/instance="([^"]+)"/"#)).unwrap();
 
-        assert_eq!(first_value(values), Some("node-2".to_string()));
+        assert_eq!(unique_values(values), vec!["node-2", "node-1"]);
+    }
+
+    #[test]
+    fn test_interpolate_single_value_is_verbatim() {
+        assert_eq!(
+            Variable::single("10.0.0.1:9100").interpolate().as_deref(),
+            Some("10.0.0.1:9100")
+        );
+    }
+
+    #[test]
+    fn test_interpolate_multi_value_as_regex_alternation() {
+        let var = Variable::with_values(vec!["node-1".to_string(), "10.0.0.1:9100".to_string()]);
+
+        assert_eq!(
+            var.interpolate().as_deref(),
+            Some(r"(node-1|10\\.0\\.0\\.1:9100)")
+        );
+    }
+
+    #[test]
+    fn test_interpolate_all_uses_all_value_or_options() {
+        let mut var = Variable::with_values(vec![ALL_VALUE.to_string()]);
+        assert_eq!(var.interpolate().as_deref(), Some(".*"));
+
+        var.set_options(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(var.interpolate().as_deref(), Some("(a|b)"));
+
+        var.all_value = Some("node-.*".to_string());
+        assert_eq!(var.interpolate().as_deref(), Some("node-.*"));
+    }
+
+    #[test]
+    fn test_set_options_keeps_valid_selection() {
+        let mut var = Variable::with_values(vec!["a".to_string(), "gone".to_string()]);
+        var.set_options(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(var.current, vec!["a"]);
+
+        var.set_options(vec!["c".to_string()]);
+        assert_eq!(var.current, vec!["c"]);
     }
 }
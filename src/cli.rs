@@ -29,11 +29,55 @@ pub(crate) struct Args {
     #[arg(long)]
     pub(crate) prometheus_url: Option<String>,
 
+    /// Default datasource name or UID from the config's [[datasources]]
+    #[arg(long, value_name = "NAME")]
+    pub(crate) datasource: Option<String>,
+
+    /// Username for HTTP basic auth against Prometheus
+    #[arg(long, value_name = "USER")]
+    pub(crate) basic_auth_user: Option<String>,
+
+    /// File containing the HTTP basic auth password
+    #[arg(long, value_name = "FILE")]
+    pub(crate) basic_auth_password_file: Option<PathBuf>,
+
+    /// File containing a bearer token to send to Prometheus
+    #[arg(long, value_name = "FILE", conflicts_with = "bearer_token_env")]
+    pub(crate) bearer_token_file: Option<PathBuf>,
+
+    /// Environment variable holding a bearer token to send to Prometheus
+    #[arg(long, value_name = "VAR")]
+    pub(crate) bearer_token_env: Option<String>,
+
+    /// Extra HTTP header for Prometheus requests (e.g., --header X-Scope-OrgID=tenant-1)
+    #[arg(long, value_parser = parse_key_val::<String, String>, value_name = "KEY=VALUE")]
+    pub(crate) header: Vec<(String, String)>,
+
+    /// PEM bundle of CA certificates to trust for Prometheus TLS
+    #[arg(long, value_name = "FILE")]
+    pub(crate) tls_ca_file: Option<PathBuf>,
+
+    /// PEM client certificate for mutual TLS
+    #[arg(long, value_name = "FILE", requires = "tls_key_file")]
+    pub(crate) tls_cert_file: Option<PathBuf>,
+
+    /// PEM private key for the client certificate
+    #[arg(long, value_name = "FILE", requires = "tls_cert_file")]
+    pub(crate) tls_key_file: Option<PathBuf>,
+
+    /// Name the server certificate is verified against instead of the URL host
+    #[arg(long, value_name = "NAME")]
+    pub(crate) tls_server_name: Option<String>,
+
+    /// Skip TLS certificate verification (insecure)
+    #[arg(long)]
+    pub(crate) tls_insecure_skip_verify: bool,
+
     /// Time range to query (e.g., 5m, 1h, 3d) (default: 5m)
     #[arg(long, value_name = "DURATION")]
     pub(crate) range: Option<String>,
 
-    /// Query step resolution (e.g., 5s, 30s, 1m) (default: 5s)
+    /// Minimum query step; panels pick coarser steps for long ranges (e.g., 5s, 30s, 1m) (default: 5s)
     #[arg(long, value_name = "DURATION")]
     pub(crate) step: Option<String>,
 
@@ -65,10 +109,14 @@ pub(crate) struct Args {
     #[arg(long, value_name = "EXPR")]
     pub(crate) query: Vec<String>,
 
-    /// Template variables to override (e.g., --var instance=server1)
+    /// Template variables to override; repeat a key to select several values (e.g., --var instance=server1)
     #[arg(long, value_parser = parse_key_val::<String, String>, value_name = "KEY=VALUE")]
     pub(crate) var: Vec<(String, String)>,
 
+    /// Ad-hoc filter added to every query; replaces a dashboard filter on the same label (e.g., --filter cluster=eu-1)
+    #[arg(long, value_parser = parse_filter, value_name = "LABEL=VALUE")]
+    pub(crate) filter: Vec<crate::app::LabelMatcher>,
+
     /// Color theme (default, dracula, monokai, solarized-dark, solarized-light, gruvbox, tokyo-night, catppuccin)
     #[arg(long, value_name = "NAME")]
     pub(crate) theme: Option<String>,
@@ -119,6 +167,13 @@ pub(crate) enum ValidateFormat {
     Json,
 }
 
+/// Helper to parse ad-hoc filters such as `cluster=eu-1` or `env!~dev.*`.
+fn parse_filter(
+    s: &str,
+) -> Result<crate::app::LabelMatcher, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    Ok(crate::app::LabelMatcher::parse(s)?)
+}
+
 /// Helper to parse key=value pairs for CLI arguments.
 pub(crate) fn parse_key_val<T, U>(
     s: &str,
@@ -164,4 +219,52 @@ mod tests {
         assert!(args.strict);
         assert_eq!(args.format, crate::cli::ValidateFormat::Json);
     }
+
+    #[test]
+    fn test_parse_tls_options() {
+        let args = Args::parse_from([
+            "grafatui",
+            "--tls-ca-file",
+            "ca.pem",
+            "--tls-cert-file",
+            "client.pem",
+            "--tls-key-file",
+            "client-key.pem",
+            "--tls-server-name",
+            "prometheus.internal",
+        ]);
+
+        assert_eq!(args.tls_ca_file, Some(PathBuf::from("ca.pem")));
+        assert_eq!(args.tls_cert_file, Some(PathBuf::from("client.pem")));
+        assert_eq!(args.tls_key_file, Some(PathBuf::from("client-key.pem")));
+        assert_eq!(
+            args.tls_server_name,
+            Some("prometheus.internal".to_string())
+        );
+        assert!(!args.tls_insecure_skip_verify);
+        assert!(Args::try_parse_from(["grafatui", "--tls-cert-file", "client.pem"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_auth_options() {
+        let args = Args::parse_from([
+            "grafatui",
+            "--basic-auth-user",
+            "grafatui",
+            "--basic-auth-password-file",
+            "/run/secrets/prom",
+            "--header",
+            "X-Scope-OrgID=tenant-1",
+        ]);
+
+        assert_eq!(args.basic_auth_user, Some("grafatui".to_string()));
+        assert_eq!(
+            args.basic_auth_password_file,
+            Some(PathBuf::from("/run/secrets/prom"))
+        );
+        assert_eq!(
+            args.header,
+            vec![("X-Scope-OrgID".to_string(), "tenant-1".to_string())]
+        );
+    }
 }
@@ -14,8 +14,11 @@
  * limitations under the License.
  */
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use directories::ProjectDirs;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -35,7 +38,178 @@ pub(crate) struct Config {
     pub(crate) record_max_frames: Option<usize>,
     pub(crate) autogrid: Option<bool>,
     pub(crate) autogrid_color: Option<String>,
-    pub(crate) vars: Option<HashMap<String, String>>,
+    pub(crate) vars: Option<HashMap<String, VarSetting>>,
+    pub(crate) auth: Option<AuthConfig>,
+    pub(crate) tls: Option<TlsConfig>,
+    pub(crate) default_datasource: Option<String>,
+    pub(crate) datasources: Option<Vec<DatasourceConfig>>,
+}
+
+/// A template variable value from `[vars]`: a string or a list of strings.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub(crate) enum VarSetting {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl VarSetting {
+    pub(crate) fn into_values(self) -> Vec<String> {
+        match self {
+            VarSetting::One(value) => vec![value],
+            VarSetting::Many(values) => values,
+        }
+    }
+}
+
+/// A named Prometheus datasource from `[[datasources]]`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub(crate) struct DatasourceConfig {
+    pub(crate) name: String,
+    /// Grafana datasource UID, used to match dashboard references.
+    pub(crate) uid: Option<String>,
+    pub(crate) url: String,
+    pub(crate) auth: Option<AuthConfig>,
+    pub(crate) tls: Option<TlsConfig>,
+}
+
+/// Authentication settings for a Prometheus datasource.
+///
+/// Secrets can be read from files or environment variables so they do not
+/// have to be stored in the configuration file itself.
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+pub(crate) struct AuthConfig {
+    pub(crate) username: Option<String>,
+    pub(crate) password: Option<String>,
+    pub(crate) password_file: Option<PathBuf>,
+    pub(crate) bearer_token_file: Option<PathBuf>,
+    pub(crate) bearer_token_env: Option<String>,
+    #[serde(default)]
+    pub(crate) headers: HashMap<String, String>,
+}
+
+impl AuthConfig {
+    /// Reads referenced secrets and validates headers.
+    pub(crate) fn resolve(&self) -> Result<crate::prom::PromAuth> {
+        let password = match (&self.password, &self.password_file) {
+            (Some(_), Some(_)) => bail!("auth: set either password or password_file, not both"),
+            (Some(password), None) => Some(password.clone()),
+            (None, Some(path)) => Some(read_secret_file(path).context("auth.password_file")?),
+            (None, None) => None,
+        };
+        let basic = match (&self.username, password) {
+            (Some(user), password) => Some((user.clone(), password.unwrap_or_default())),
+            (None, Some(_)) => bail!("auth: a password requires a username"),
+            (None, None) => None,
+        };
+
+        let bearer_token = match (&self.bearer_token_file, &self.bearer_token_env) {
+            (Some(_), Some(_)) => {
+                bail!("auth: set either bearer_token_file or bearer_token_env, not both")
+            }
+            (Some(path), None) => Some(read_secret_file(path).context("auth.bearer_token_file")?),
+            (None, Some(var)) => Some(
+                std::env::var(var)
+                    .with_context(|| format!("auth.bearer_token_env: ${} is not set", var))?
+                    .trim()
+                    .to_string(),
+            ),
+            (None, None) => None,
+        };
+        if basic.is_some() && bearer_token.is_some() {
+            bail!("auth: basic auth and bearer token cannot be combined");
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("auth.headers: invalid header name `{}`", name))?;
+            let mut value = HeaderValue::from_str(value)
+                .with_context(|| format!("auth.headers: invalid value for `{}`", name))?;
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+
+        Ok(crate::prom::PromAuth {
+            basic,
+            bearer_token,
+            headers,
+        })
+    }
+}
+
+/// TLS settings for a Prometheus datasource.
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+pub(crate) struct TlsConfig {
+    pub(crate) ca_file: Option<PathBuf>,
+    pub(crate) cert_file: Option<PathBuf>,
+    pub(crate) key_file: Option<PathBuf>,
+    pub(crate) server_name: Option<String>,
+    #[serde(default)]
+    pub(crate) insecure_skip_verify: bool,
+}
+
+impl TlsConfig {
+    /// Loads the referenced PEM files.
+    pub(crate) fn resolve(&self) -> Result<crate::prom::PromTls> {
+        let ca_certs = match &self.ca_file {
+            Some(path) => {
+                let pem = read_pem_file(path).context("tls.ca_file")?;
+                let certs = CertificateDer::pem_slice_iter(&pem)
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_context(|| format!("tls.ca_file: parsing {}", path.display()))?;
+                if certs.is_empty() {
+                    bail!("tls.ca_file: no certificates found in {}", path.display());
+                }
+                certs
+            }
+            None => Vec::new(),
+        };
+
+        let identity = match (&self.cert_file, &self.key_file) {
+            (Some(cert), Some(key)) => {
+                let loading = || {
+                    format!(
+                        "tls: loading client certificate {} with key {}",
+                        cert.display(),
+                        key.display()
+                    )
+                };
+                let cert_pem = read_pem_file(cert).context("tls.cert_file")?;
+                let certs = CertificateDer::pem_slice_iter(&cert_pem)
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_context(loading)?;
+                if certs.is_empty() {
+                    bail!("tls.cert_file: no certificates found in {}", cert.display());
+                }
+                let key_pem = read_pem_file(key).context("tls.key_file")?;
+                let key = PrivateKeyDer::from_pem_slice(&key_pem).with_context(loading)?;
+                Some((certs, key))
+            }
+            (Some(_), None) => bail!("tls: cert_file requires key_file"),
+            (None, Some(_)) => bail!("tls: key_file requires cert_file"),
+            (None, None) => None,
+        };
+
+        Ok(crate::prom::PromTls {
+            ca_certs,
+            identity,
+            server_name: self.server_name.clone(),
+            insecure_skip_verify: self.insecure_skip_verify,
+        })
+    }
+}
+
+fn read_pem_file(path: &std::path::Path) -> Result<Vec<u8>> {
+    let path = expand_path(path);
+    fs::read(&path).with_context(|| format!("reading {}", path.display()))
+}
+
+fn read_secret_file(path: &std::path::Path) -> Result<String> {
+    let path = expand_path(path);
+    let secret = fs::read_to_string(&path)
+        .with_context(|| format!("reading secret from {}", path.display()))?;
+    Ok(secret.trim().to_string())
 }
 
 impl Config {
@@ -43,12 +217,12 @@ impl Config {
         let config_path = cli_path
             .map(|p| expand_path(&p))
             .or_else(Self::get_config_path);
-        if let Some(path) = config_path {
-            if path.exists() {
-                let content = fs::read_to_string(path)?;
-                let config: Config = toml::from_str(&content)?;
-                return Ok(config);
-            }
+        if let Some(path) = config_path
+            && path.exists()
+        {
+            let content = fs::read_to_string(path)?;
+            let config: Config = toml::from_str(&content)?;
+            return Ok(config);
         }
         Ok(Config::default())
     }
@@ -77,15 +251,15 @@ impl Config {
 /// Expands a path starting with `~` to the user's home directory.
 pub(crate) fn expand_path(path: &std::path::Path) -> PathBuf {
     let path_str = path.to_string_lossy();
-    if path_str.starts_with("~") {
-        if let Some(dirs) = directories::UserDirs::new() {
-            let home = dirs.home_dir();
-            if path_str == "~" {
-                return home.to_path_buf();
-            }
-            if path_str.starts_with("~/") || path_str.starts_with("~\\") {
-                return home.join(&path_str[2..]);
-            }
+    if path_str.starts_with("~")
+        && let Some(dirs) = directories::UserDirs::new()
+    {
+        let home = dirs.home_dir();
+        if path_str == "~" {
+            return home.to_path_buf();
+        }
+        if path_str.starts_with("~/") || path_str.starts_with("~\\") {
+            return home.join(&path_str[2..]);
         }
     }
     path.to_path_buf()
@@ -117,6 +291,155 @@ mod tests {
         assert_eq!(config.autogrid_color, Some("gray".to_string()));
     }
 
+    #[test]
+    fn test_auth_config_deserialization() {
+        let toml_str = r#"
+            [auth]
+            username = "grafatui"
+            password_file = "~/.config/grafatui/password"
+
+            [auth.headers]
+            X-Scope-OrgID = "tenant-1"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let auth = config.auth.unwrap();
+
+        assert_eq!(auth.username, Some("grafatui".to_string()));
+        assert_eq!(
+            auth.password_file,
+            Some(PathBuf::from("~/.config/grafatui/password"))
+        );
+        assert_eq!(auth.headers.get("X-Scope-OrgID").unwrap(), "tenant-1");
+    }
+
+    #[test]
+    fn test_auth_resolve_reads_bearer_token_file() {
+        let path = std::env::temp_dir().join(format!(
+            "grafatui-token-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::write(&path, "tok3n\n").unwrap();
+        let auth = AuthConfig {
+            bearer_token_file: Some(path.clone()),
+            headers: HashMap::from([("X-Scope-OrgID".to_string(), "tenant-1".to_string())]),
+            ..AuthConfig::default()
+        }
+        .resolve();
+        fs::remove_file(path).unwrap();
+        let auth = auth.unwrap();
+
+        assert_eq!(auth.bearer_token, Some("tok3n".to_string()));
+        assert!(auth.basic.is_none());
+        assert_eq!(auth.headers.get("x-scope-orgid").unwrap(), "tenant-1");
+        assert!(auth.headers.get("x-scope-orgid").unwrap().is_sensitive());
+    }
+
+    #[test]
+    fn test_auth_resolve_rejects_conflicting_credentials() {
+        let auth = AuthConfig {
+            username: Some("grafatui".to_string()),
+            password: Some("secret".to_string()),
+            bearer_token_env: Some("PATH".to_string()),
+            ..AuthConfig::default()
+        };
+
+        let err = auth.resolve().unwrap_err().to_string();
+        assert!(err.contains("cannot be combined"));
+        assert!(!err.contains("secret"));
+    }
+
+    #[test]
+    fn test_auth_resolve_rejects_invalid_header_name() {
+        let auth = AuthConfig {
+            headers: HashMap::from([("bad header".to_string(), "x".to_string())]),
+            ..AuthConfig::default()
+        };
+
+        assert!(auth.resolve().is_err());
+    }
+
+    #[test]
+    fn test_tls_config_deserialization() {
+        let toml_str = r#"
+            [tls]
+            ca_file = "/etc/ssl/internal-ca.pem"
+            cert_file = "client.pem"
+            key_file = "client-key.pem"
+            server_name = "prometheus.internal"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let tls = config.tls.unwrap();
+
+        assert_eq!(tls.ca_file, Some(PathBuf::from("/etc/ssl/internal-ca.pem")));
+        assert_eq!(tls.server_name, Some("prometheus.internal".to_string()));
+        assert!(!tls.insecure_skip_verify);
+    }
+
+    #[test]
+    fn test_tls_resolve_requires_cert_and_key_together() {
+        let tls = TlsConfig {
+            cert_file: Some(PathBuf::from("client.pem")),
+            ..TlsConfig::default()
+        };
+
+        let err = tls.resolve().unwrap_err().to_string();
+        assert!(err.contains("cert_file requires key_file"));
+    }
+
+    #[test]
+    fn test_tls_resolve_rejects_ca_file_without_certificates() {
+        let path = std::env::temp_dir().join(format!(
+            "grafatui-ca-{}.pem",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::write(&path, "not a certificate\n").unwrap();
+        let result = TlsConfig {
+            ca_file: Some(path.clone()),
+            ..TlsConfig::default()
+        }
+        .resolve();
+        fs::remove_file(path).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_datasources_deserialization() {
+        let toml_str = r#"
+            default_datasource = "staging"
+
+            [[datasources]]
+            name = "prod"
+            uid = "P1809F7CD0C75ACF3"
+            url = "https://prometheus.prod:9090"
+
+            [datasources.auth]
+            bearer_token_env = "PROD_TOKEN"
+
+            [[datasources]]
+            name = "staging"
+            url = "http://prometheus.staging:9090"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let datasources = config.datasources.unwrap();
+
+        assert_eq!(config.default_datasource, Some("staging".to_string()));
+        assert_eq!(datasources.len(), 2);
+        assert_eq!(datasources[0].uid.as_deref(), Some("P1809F7CD0C75ACF3"));
+        assert_eq!(
+            datasources[0].auth.as_ref().unwrap().bearer_token_env,
+            Some("PROD_TOKEN".to_string())
+        );
+        assert_eq!(datasources[1].url, "http://prometheus.staging:9090");
+        assert!(datasources[1].auth.is_none());
+    }
+
     #[test]
     fn test_expand_path() {
         if let Some(dirs) = directories::UserDirs::new() {
@@ -14,8 +14,10 @@
  * limitations under the License.
  */
 
-use crate::app::{AppMode, AppState, PanelState, PanelType, SeriesView, ThresholdMode};
-use crate::theme::Theme;
+use crate::app::{
+    AppMode, AppState, GraphLineInterpolation, GraphScale, LegendDisplayMode, LegendPlacement,
+    PanelState, PanelType, SeriesView,
+};
 use crate::ui;
 use anyhow::{Context, Result, anyhow};
 use clap::ValueEnum;
@@ -24,6 +26,7 @@ use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::fmt::Write as _;
 use std::fs;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 
@@ -35,6 +38,7 @@ const PANEL_PADDING: f64 = 12.0;
 const TITLE_HEIGHT: f64 = 28.0;
 const X_LABEL_HEIGHT: f64 = 24.0;
 const LEGEND_HEIGHT: f64 = 28.0;
+const LEGEND_ROW_HEIGHT: f64 = 15.0;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
 #[serde(rename_all = "lowercase")]
@@ -290,6 +294,7 @@ pub(crate) fn render_svg(app: &AppState, viewport: Rect) -> String {
     )
     .unwrap();
     render_header(app, &mut out, width, &text, &border);
+    render_variable_bar(app, &mut out, viewport, &text);
 
     for (rect, index) in ui::visible_panel_rects(viewport, app) {
         let Some(panel) = app.panels.get(index) else {
@@ -322,6 +327,24 @@ fn render_header(app: &AppState, out: &mut String, width: f64, text: &str, borde
     write_text(out, width / 2.0, 31.0, &title, text, "middle", FONT_SIZE);
 }
 
+fn render_variable_bar(app: &AppState, out: &mut String, viewport: Rect, text: &str) {
+    let bar = ui::screen_layout(viewport, app).variables;
+    if bar.height == 0 {
+        return;
+    }
+    let rect = scaled_rect(ui::variable_bar_area(bar));
+    let labels = ui::variable_bar_labels(app).join(ui::VARIABLE_BAR_SEPARATOR);
+    write_text(
+        out,
+        rect.left,
+        rect.top + rect.height * 0.75,
+        &labels,
+        text,
+        "start",
+        SMALL_FONT_SIZE,
+    );
+}
+
 fn render_footer(
     app: &AppState,
     out: &mut String,
@@ -344,6 +367,8 @@ fn render_footer(
         AppMode::Fullscreen => "FULLSCREEN",
         AppMode::Inspect => "INSPECT",
         AppMode::FullscreenInspect => "FULLSCREEN INSPECT",
+        AppMode::VariablePicker => "VARIABLES",
+        AppMode::FilterEditor => "FILTERS",
     };
     let recording = if app.recording.is_some() {
         " | REC"
@@ -377,6 +402,11 @@ fn render_panel(
     let title = color_hex(theme.title, "#00c8ff");
     let bg = color_hex(theme.background, "#111111");
 
+    if panel.panel_type == PanelType::Row {
+        render_row(panel, rect, &title, &border, out);
+        return;
+    }
+
     write!(
         out,
         r#"<rect x="{:.0}" y="{:.0}" width="{:.0}" height="{:.0}" fill="{bg}" stroke="{border}"/>"#,
@@ -420,6 +450,25 @@ fn render_panel(
         PanelType::BarGauge => render_bar_gauge_panel(app, panel, inner, out),
         PanelType::Table => render_table_panel(app, panel, inner, out),
         PanelType::Heatmap => render_heatmap_panel(app, panel, inner, out),
+        PanelType::Text => render_text_panel(app, panel, inner, out),
+        PanelType::Row => {}
+    }
+}
+
+/// Renders a row header as its title followed by a horizontal rule.
+fn render_row(panel: &PanelState, rect: PlotRect, title: &str, border: &str, out: &mut String) {
+    let baseline = rect.top + rect.height * 0.75;
+    let header = ui::row_header(panel);
+    write_text(out, rect.left, baseline, &header, title, "start", FONT_SIZE);
+    let rule_start = rect.left + header.chars().count() as f64 * CELL_WIDTH;
+    let middle = rect.top + rect.height / 2.0;
+    if rule_start < rect.left + rect.width {
+        write!(
+            out,
+            r#"<line x1="{rule_start:.2}" y1="{middle:.2}" x2="{:.2}" y2="{middle:.2}" stroke="{border}"/>"#,
+            rect.left + rect.width
+        )
+        .unwrap();
     }
 }
 
@@ -428,25 +477,50 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         return;
     }
 
-    let legend_height = if panel.series.is_empty() {
-        0.0
-    } else {
-        LEGEND_HEIGHT
-    };
+    let legend = ui::build_legend(panel, app);
+    let (legend_width, legend_height) = legend_size(&legend, rect);
+    let layout = ui::axis_layout(panel);
     let y_label_width = 64.0;
+    let right_label_width = if layout.right { y_label_width } else { 0.0 };
     let plot = PlotRect {
         left: rect.left + y_label_width,
         top: rect.top + 6.0,
-        width: (rect.width - y_label_width - 8.0).max(1.0),
+        width: (rect.width - y_label_width - right_label_width - legend_width - 8.0).max(1.0),
         height: (rect.height - X_LABEL_HEIGHT - legend_height - 10.0).max(1.0),
     };
 
     let (x_min, x_max) = app.time_bounds();
     let y_bounds = ui::calculate_y_bounds(panel);
+    let right_bounds = ui::calculate_right_y_bounds(panel).unwrap_or(y_bounds);
     let text = color_hex(app.theme.text, "#e6e6e6");
     let axis = color_hex(Color::Gray, "#777777");
     let grid = "#6d6d6d";
     let graph_options = panel.graph_options();
+    let axis_display = ui::y_axis_display(panel);
+    let right_display = ui::right_axis_display(panel);
+    let scale = graph_options.scale;
+    let y_ticks = if scale == GraphScale::Linear {
+        value_ticks(y_bounds[0], y_bounds[1])
+    } else {
+        ui::axis_value_ticks(y_bounds, 3, scale)
+    };
+
+    for (from, to, color) in ui::threshold_bands(panel) {
+        let top = map_y(to, y_bounds, plot);
+        let bottom = map_y(from, y_bounds, plot);
+        if bottom - top <= 0.0 {
+            continue;
+        }
+        write!(
+            out,
+            r#"<rect data-role="threshold-area" x="{:.2}" y="{top:.2}" width="{:.2}" height="{:.2}" fill="{}" fill-opacity="0.15" />"#,
+            plot.left,
+            plot.width,
+            bottom - top,
+            color_hex(color, "#ffaa00")
+        )
+        .unwrap();
+    }
 
     write!(
         out,
@@ -466,8 +540,25 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         plot.bottom()
     )
     .unwrap();
+    if layout.right {
+        write!(
+            out,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{axis}"/>"#,
+            plot.right(),
+            plot.top,
+            plot.right(),
+            plot.bottom()
+        )
+        .unwrap();
+    }
 
-    for tick in value_ticks(y_bounds[0], y_bounds[1]) {
+    // Right-axis labels sit level with the left ones, at the values of the
+    // right bounds there.
+    let right_label = |position| {
+        let position = ui::remap_position(position, y_bounds, right_bounds);
+        right_display.format_number(scale.value_at(position))
+    };
+    for tick in y_ticks {
         let y = map_y(tick, y_bounds, plot);
         draw_line(
             out,
@@ -479,15 +570,28 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
                 width: 0.7,
             },
         );
-        write_text(
-            out,
-            plot.left - 8.0,
-            y + 4.0,
-            &panel.display.format_number(tick),
-            grid,
-            "end",
-            SMALL_FONT_SIZE,
-        );
+        if layout.left {
+            write_text(
+                out,
+                plot.left - 8.0,
+                y + 4.0,
+                &axis_display.format_number(scale.value_at(tick)),
+                grid,
+                "end",
+                SMALL_FONT_SIZE,
+            );
+        }
+        if layout.right {
+            write_text(
+                out,
+                plot.right() + 8.0,
+                y + 4.0,
+                &right_label(tick),
+                grid,
+                "start",
+                SMALL_FONT_SIZE,
+            );
+        }
     }
 
     for tick in time_ticks(x_min, x_max) {
@@ -513,24 +617,46 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         );
     }
 
-    write_text(
-        out,
-        plot.left - 8.0,
-        plot.bottom() + 4.0,
-        &panel.display.format_number(y_bounds[0]),
-        &text,
-        "end",
-        SMALL_FONT_SIZE,
-    );
-    write_text(
-        out,
-        plot.left - 8.0,
-        plot.top + 4.0,
-        &panel.display.format_number(y_bounds[1]),
-        &text,
-        "end",
-        SMALL_FONT_SIZE,
-    );
+    if layout.left {
+        write_text(
+            out,
+            plot.left - 8.0,
+            plot.bottom() + 4.0,
+            &axis_display.format_number(scale.value_at(y_bounds[0])),
+            &text,
+            "end",
+            SMALL_FONT_SIZE,
+        );
+        write_text(
+            out,
+            plot.left - 8.0,
+            plot.top + 4.0,
+            &axis_display.format_number(scale.value_at(y_bounds[1])),
+            &text,
+            "end",
+            SMALL_FONT_SIZE,
+        );
+    }
+    if layout.right {
+        write_text(
+            out,
+            plot.right() + 8.0,
+            plot.bottom() + 4.0,
+            &right_label(y_bounds[0]),
+            &text,
+            "start",
+            SMALL_FONT_SIZE,
+        );
+        write_text(
+            out,
+            plot.right() + 8.0,
+            plot.top + 4.0,
+            &right_label(y_bounds[1]),
+            &text,
+            "start",
+            SMALL_FONT_SIZE,
+        );
+    }
     write_text(
         out,
         plot.left,
@@ -551,10 +677,13 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
     );
 
     for (value, color, dashed) in threshold_lines(panel, app) {
-        if value <= y_bounds[0] || value >= y_bounds[1] {
+        let Some(position) = scale.position_of(value) else {
+            continue;
+        };
+        if position <= y_bounds[0] || position >= y_bounds[1] {
             continue;
         }
-        let y = map_y(value, y_bounds, plot);
+        let y = map_y(position, y_bounds, plot);
         let color = color_hex(color, "#ffaa00");
         draw_line(
             out,
@@ -577,42 +706,69 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         );
     }
 
-    if let Some(cursor_x) = app.cursor_x {
-        if cursor_x >= x_min && cursor_x <= x_max {
-            let x = map_x(cursor_x, [x_min, x_max], plot);
-            draw_line(
-                out,
-                (x, plot.top),
-                (x, plot.bottom()),
-                LineStyle {
-                    color: "#ffffff",
-                    dash: Some("4 4"),
-                    width: 1.0,
-                },
-            );
-        }
+    if let Some(cursor_x) = app.cursor_x
+        && cursor_x >= x_min
+        && cursor_x <= x_max
+    {
+        let x = map_x(cursor_x, [x_min, x_max], plot);
+        draw_line(
+            out,
+            (x, plot.top),
+            (x, plot.bottom()),
+            LineStyle {
+                color: "#ffffff",
+                dash: Some("4 4"),
+                width: 1.0,
+            },
+        );
     }
 
+    let stacked = ui::stack_series(panel);
     for (index, series) in panel.series.iter().enumerate() {
         if !series.visible {
             continue;
         }
-        let color = series_color(panel, &app.theme, index);
+        let color = panel.series_color(index, &app.theme);
         let color = color_hex(color, "#00ff88");
         let x_bounds = [x_min, x_max];
+        let stack = stacked.as_ref().and_then(|stacked| stacked[index].as_ref());
+        let values = stack.map_or(series.points.as_slice(), |stack| &stack.points);
+        let base = stack.map(|stack| stack.base.as_slice());
+        let points = ui::scale_points(values, scale);
+        let points = points.as_slice();
+        // Series on a right axis of their own are mapped with its bounds.
+        let y_bounds = if ui::is_right_scaled(panel, layout, index) {
+            right_bounds
+        } else {
+            y_bounds
+        };
 
         match graph_options.draw_style {
             crate::app::GraphDrawStyle::Points => {
-                render_graph_points(series, plot, y_bounds, x_bounds, &color, out);
+                render_graph_points(points, plot, y_bounds, x_bounds, &color, out);
             }
             crate::app::GraphDrawStyle::Bars => {
-                render_graph_bars(series, plot, y_bounds, x_bounds, &color, out);
+                let band = graph_band(values, base, x_bounds, y_bounds, scale);
+                render_graph_bars(&band, plot, y_bounds, x_bounds, &color, out);
             }
             crate::app::GraphDrawStyle::Line => {
+                // Lines and areas break at nulls and follow the line
+                // interpolation, as in the TUI.
+                let interpolation = graph_options.line_interpolation;
                 if let Some(opacity) = graph_area_opacity(&graph_options) {
-                    render_graph_area(series, plot, y_bounds, x_bounds, &color, opacity, out);
+                    for segment in ui::line_segments(panel, index, values) {
+                        let base = base.map(|base| {
+                            ui::interpolate_points(&base[segment.clone()], interpolation)
+                        });
+                        let values = ui::interpolate_points(&values[segment], interpolation);
+                        let band = graph_band(&values, base.as_deref(), x_bounds, y_bounds, scale);
+                        render_graph_area(&band, plot, y_bounds, x_bounds, &color, opacity, out);
+                    }
                 }
-                if let Some(path) = series_path(series, x_bounds, y_bounds, plot) {
+                let segments = ui::line_segments(panel, index, points);
+                if let Some(path) =
+                    series_path(points, &segments, interpolation, x_bounds, y_bounds, plot)
+                {
                     write!(
                         out,
                         r#"<path d="{path}" fill="none" stroke="{color}" stroke-width="1.6" stroke-linejoin="round" stroke-linecap="round"/>"#
@@ -620,40 +776,49 @@ fn render_graph_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
                     .unwrap();
                 }
                 if graph_options.show_points == crate::app::GraphPointMode::Always {
-                    render_graph_points(series, plot, y_bounds, x_bounds, &color, out);
+                    render_graph_points(points, plot, y_bounds, x_bounds, &color, out);
                 }
             }
         }
+        let non_finite: Vec<_> = ui::non_finite_samples(panel, index).collect();
+        render_non_finite_markers(&non_finite, plot, x_bounds, &color, out);
     }
 
-    render_legend(
-        app,
-        panel,
-        plot.left,
-        plot.bottom() + X_LABEL_HEIGHT,
-        plot.width,
-        out,
-    );
+    let legend_rect = if legend.placement == LegendPlacement::Right {
+        PlotRect {
+            left: plot.right() + right_label_width + 12.0,
+            top: plot.top,
+            width: (legend_width - 12.0).max(1.0),
+            height: plot.height + X_LABEL_HEIGHT,
+        }
+    } else {
+        PlotRect {
+            left: plot.left,
+            top: plot.bottom() + X_LABEL_HEIGHT,
+            width: plot.width,
+            height: legend_height,
+        }
+    };
+    render_legend(app, &legend, legend_rect, out);
 }
 
 fn render_stat_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &mut String) {
-    let Some(series) = panel.series.iter().find(|series| series.visible) else {
+    let reduced = panel.reduced_values();
+    let Some((series, values)) = reduced.first() else {
         render_no_data(app, rect, out);
         return;
     };
+    let value = values.first().copied().flatten();
 
     // Mirror the TUI distinction: a visible null series can show Grafana's
     // noValue fallback, but a panel with no visible series still renders No data.
-    let color = series
-        .value
-        .map(|value| value_color(app, panel, value))
-        .unwrap_or_else(|| color_hex(app.theme.text, "#e6e6e6"));
+    let color = value_color(app, panel, series, value);
     let text = color_hex(app.theme.text, "#e6e6e6");
     write_text(
         out,
         rect.left + rect.width / 2.0,
         rect.top + 34.0,
-        &panel.display.format_value(series.value),
+        &panel.series_display(series).format_value(value),
         &color,
         "middle",
         28.0,
@@ -688,7 +853,8 @@ fn render_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         .max
         .unwrap_or(if value > 100.0 { value * 1.2 } else { 100.0 });
     let ratio = value_ratio(value, min, max);
-    let color = value_color(app, panel, value);
+    let color = value_color(app, panel, series, Some(value));
+    let display = panel.series_display(series);
     let text = color_hex(app.theme.text, "#e6e6e6");
     let track = color_hex(Color::DarkGray, "#444444");
     let gauge = PlotRect {
@@ -719,7 +885,7 @@ fn render_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         gauge.top + 18.0,
         &format!(
             "{} ({:.0}%)",
-            panel.display.format_number(value),
+            display.format_value(Some(value)),
             ratio * 100.0
         ),
         "#ffffff",
@@ -730,7 +896,7 @@ fn render_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         out,
         gauge.left,
         gauge.bottom() + 17.0,
-        &panel.display.format_number(min),
+        &display.format_number(min),
         &text,
         "start",
         SMALL_FONT_SIZE,
@@ -739,7 +905,7 @@ fn render_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         out,
         gauge.right(),
         gauge.bottom() + 17.0,
-        &panel.display.format_number(max),
+        &display.format_number(max),
         &text,
         "end",
         SMALL_FONT_SIZE,
@@ -748,10 +914,9 @@ fn render_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
 
 fn render_bar_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &mut String) {
     let mut values = panel
-        .series
-        .iter()
-        .filter(|series| series.visible)
-        .filter_map(|series| series.value.map(|value| (series, value)))
+        .reduced_values()
+        .into_iter()
+        .filter_map(|(series, values)| values.first().copied().flatten().map(|v| (series, v)))
         .collect::<Vec<_>>();
     values.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
 
@@ -775,7 +940,7 @@ fn render_bar_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, ou
     for (row, (series, value)) in values.into_iter().take(max_rows).enumerate() {
         let y = rect.top + row as f64 * row_height + 15.0;
         let ratio = (value / max_value).clamp(0.0, 1.0);
-        let color = value_color(app, panel, value);
+        let color = value_color(app, panel, series, Some(value));
         write_text(
             out,
             rect.left + 4.0,
@@ -806,7 +971,7 @@ fn render_bar_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, ou
             out,
             track_rect.right() + 8.0,
             y,
-            &panel.display.format_number(value),
+            &panel.series_display(series).format_value(Some(value)),
             &color,
             "start",
             SMALL_FONT_SIZE,
@@ -815,11 +980,7 @@ fn render_bar_gauge_panel(app: &AppState, panel: &PanelState, rect: PlotRect, ou
 }
 
 fn render_table_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &mut String) {
-    let values = panel
-        .series
-        .iter()
-        .filter(|series| series.visible)
-        .collect::<Vec<_>>();
+    let values = panel.reduced_values();
     if values.is_empty() {
         render_no_data(app, rect, out);
         return;
@@ -829,7 +990,11 @@ fn render_table_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
     let title = color_hex(app.theme.title, "#00c8ff");
     let border = color_hex(app.theme.border, "#555555");
     let row_height = 20.0;
-    let value_x = rect.left + rect.width * 0.7;
+    let columns = panel.reduce_options().column_labels();
+    let series_width = if columns.len() == 1 { 0.7 } else { 0.4 };
+    let column_width = rect.width * (1.0 - series_width) / columns.len() as f64;
+    let column_x =
+        |column: usize| rect.left + rect.width * series_width + column_width * column as f64;
     let max_rows = ((rect.height - row_height) / row_height).floor().max(1.0) as usize;
 
     write_text(
@@ -841,15 +1006,17 @@ fn render_table_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         "start",
         SMALL_FONT_SIZE,
     );
-    write_text(
-        out,
-        value_x,
-        rect.top + 15.0,
-        "Value",
-        &title,
-        "start",
-        SMALL_FONT_SIZE,
-    );
+    for (column, label) in columns.iter().enumerate() {
+        write_text(
+            out,
+            column_x(column),
+            rect.top + 15.0,
+            label,
+            &title,
+            "start",
+            SMALL_FONT_SIZE,
+        );
+    }
     draw_line(
         out,
         (rect.left, rect.top + row_height),
@@ -861,16 +1028,8 @@ fn render_table_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
         },
     );
 
-    for (row, series) in values.into_iter().take(max_rows).enumerate() {
+    for (row, (series, values)) in values.into_iter().take(max_rows).enumerate() {
         let y = rect.top + row_height * (row as f64 + 2.0) - 5.0;
-        let value = series
-            .value
-            .map(|value| panel.display.format_number(value))
-            .unwrap_or_else(|| panel.display.format_value(None));
-        let value_color = series
-            .value
-            .map(|value| value_color(app, panel, value))
-            .unwrap_or_else(|| text.clone());
         write_text(
             out,
             rect.left + 6.0,
@@ -880,109 +1039,314 @@ fn render_table_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &
             "start",
             SMALL_FONT_SIZE,
         );
-        write_text(
-            out,
-            value_x,
-            y,
-            &value,
-            &value_color,
-            "start",
-            SMALL_FONT_SIZE,
-        );
+        for (column, value) in values.into_iter().enumerate() {
+            let value_color = value_color(app, panel, series, value);
+            write_text(
+                out,
+                column_x(column),
+                y,
+                &panel.series_display(series).format_value(value),
+                &value_color,
+                "start",
+                SMALL_FONT_SIZE,
+            );
+        }
     }
 }
 
 fn render_heatmap_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &mut String) {
-    let visible = panel
-        .series
+    let options = panel.heatmap_options();
+    let longest_label = ui::heatmap_labels(panel)
         .iter()
-        .filter(|series| series.visible)
-        .collect::<Vec<_>>();
-    if visible.is_empty() {
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let label_width = (longest_label as f64 * 7.0 + 8.0).clamp(24.0, (rect.width * 0.3).max(24.0));
+    let legend_height = if options.show_legend { 20.0 } else { 0.0 };
+    let plot = PlotRect {
+        left: rect.left + label_width,
+        top: rect.top + 6.0,
+        width: (rect.width - label_width - 8.0).max(1.0),
+        height: (rect.height - X_LABEL_HEIGHT - legend_height - 10.0).max(1.0),
+    };
+
+    let (x_min, x_max) = app.time_bounds();
+    let columns = (plot.width / 6.0).floor().max(1.0) as usize;
+    // Rows thinner than a pixel merge with their neighbours.
+    let max_rows = plot.height.floor().max(1.0) as usize;
+    let Some(grid) = ui::heatmap_grid(panel, [x_min, x_max], columns, max_rows) else {
         render_no_data(app, rect, out);
         return;
-    }
+    };
+    let text = color_hex(app.theme.text, "#e6e6e6");
+    let cell_color = |value: f64| {
+        color_hex(
+            ui::heatmap_color(&options.color, grid.ratio(value)),
+            "#ffa500",
+        )
+    };
+
+    let cell_width = plot.width / columns as f64;
+    let row_height = plot.height / grid.cells.len() as f64;
+    // Label every row that leaves room for text, counting from the top.
+    let label_every = (SMALL_FONT_SIZE / row_height).ceil().max(1.0) as usize;
+    for (row, values) in grid.cells.iter().enumerate() {
+        let top = plot.bottom() - (row + 1) as f64 * row_height;
+        if (grid.cells.len() - 1 - row).is_multiple_of(label_every) {
+            write_text(
+                out,
+                plot.left - 6.0,
+                top + row_height / 2.0 + 4.0,
+                &grid.labels[row],
+                &text,
+                "end",
+                SMALL_FONT_SIZE,
+            );
+        }
 
-    let (mut min, mut max) = (f64::MAX, f64::MIN);
-    for series in &visible {
-        for (_, value) in &series.points {
-            if value.is_finite() {
-                min = min.min(*value);
-                max = max.max(*value);
+        // Neighbouring cells of the same color share a rectangle.
+        let mut column = 0;
+        while column < columns {
+            let Some(value) = values[column] else {
+                column += 1;
+                continue;
+            };
+            let color = cell_color(value);
+            let start = column;
+            column += 1;
+            while column < columns && values[column].map(cell_color).as_ref() == Some(&color) {
+                column += 1;
             }
+            write_rect(
+                out,
+                PlotRect {
+                    left: plot.left + start as f64 * cell_width,
+                    top,
+                    width: (column - start) as f64 * cell_width,
+                    height: row_height,
+                },
+                &color,
+                "none",
+                0.0,
+            );
         }
     }
-    if !min.is_finite() || !max.is_finite() || min == max {
-        render_no_data(app, rect, out);
-        return;
-    }
 
-    let label_width = (rect.width * 0.24).clamp(60.0, 150.0);
-    let plot_left = rect.left + label_width;
-    let plot_width = (rect.width - label_width - 4.0).max(1.0);
-    let rows = visible
-        .len()
-        .min((rect.height / 18.0).floor().max(1.0) as usize);
-    let row_height = (rect.height / rows as f64).max(1.0);
-    let cols = (plot_width / 10.0).floor().max(1.0) as usize;
-    let cell_width = plot_width / cols as f64;
-    let text = color_hex(app.theme.text, "#e6e6e6");
+    write_text(
+        out,
+        plot.left,
+        plot.bottom() + 17.0,
+        &ui::format_time(x_min),
+        &text,
+        "start",
+        SMALL_FONT_SIZE,
+    );
+    write_text(
+        out,
+        plot.right(),
+        plot.bottom() + 17.0,
+        &ui::format_time(x_max),
+        &text,
+        "end",
+        SMALL_FONT_SIZE,
+    );
 
-    for (row, series) in visible.into_iter().take(rows).enumerate() {
-        let top = rect.top + row as f64 * row_height;
+    if options.show_legend {
+        const STEPS: usize = 16;
+        let top = plot.bottom() + X_LABEL_HEIGHT;
+        let step_width = (plot.width / 2.0).min(160.0) / STEPS as f64;
         write_text(
             out,
-            rect.left + 4.0,
-            top + row_height.min(16.0),
-            &series.name,
+            plot.left - 6.0,
+            top + 9.0,
+            &panel.display.format_number(grid.range[0]),
             &text,
-            "start",
+            "end",
             SMALL_FONT_SIZE,
         );
-        if series.points.is_empty() {
-            continue;
-        }
-        let step = (series.points.len() as f64 / cols as f64).max(1.0);
-        for col in 0..cols {
-            let point = ((col as f64 * step) as usize).min(series.points.len() - 1);
-            let value = series.points[point].1;
-            let color = if value.is_finite() {
-                let normalized = ((value - min) / (max - min)).clamp(0.0, 1.0);
-                color_hex(ui::value_to_heatmap_color(normalized), "#666666")
-            } else {
-                color_hex(Color::DarkGray, "#444444")
-            };
+        for step in 0..STEPS {
+            let ratio = step as f64 / (STEPS - 1) as f64;
             write_rect(
                 out,
                 PlotRect {
-                    left: plot_left + col as f64 * cell_width,
+                    left: plot.left + step as f64 * step_width,
                     top,
-                    width: cell_width.max(1.0),
-                    height: (row_height - 2.0).max(1.0),
+                    width: step_width,
+                    height: 12.0,
                 },
-                &color,
+                &color_hex(ui::heatmap_color(&options.color, ratio), "#ffa500"),
                 "none",
                 0.0,
             );
         }
+        write_text(
+            out,
+            plot.left + STEPS as f64 * step_width + 6.0,
+            top + 9.0,
+            &panel.display.format_number(grid.range[1]),
+            &text,
+            "start",
+            SMALL_FONT_SIZE,
+        );
     }
 }
 
+/// Renders text panel content, word-wrapped to the panel width.
+fn render_text_panel(app: &AppState, panel: &PanelState, rect: PlotRect, out: &mut String) {
+    let theme = &app.theme;
+    let colors = TextColors {
+        text: color_hex(theme.text, "#e6e6e6"),
+        title: color_hex(theme.title, "#00c8ff"),
+        dim: color_hex(theme.legend_dim, "#777777"),
+        code: color_hex(theme.palette[0], "#33cc33"),
+    };
+    let border = color_hex(theme.border, "#555555");
+    let columns = (rect.width / CELL_WIDTH).floor().max(1.0) as usize;
+    let bottom = rect.top + rect.height;
+    let mut y = rect.top;
+
+    for line in ui::panel_text(panel, app) {
+        let quote = line.kind == ui::LineKind::Quote;
+        let indent = line.indent * 2 + if quote { 2 } else { 0 };
+        let x = rect.left + indent as f64 * CELL_WIDTH;
+        if line.kind == ui::LineKind::Rule {
+            y += CELL_HEIGHT;
+            if y > bottom {
+                return;
+            }
+            let middle = y - CELL_HEIGHT / 3.0;
+            draw_line(
+                out,
+                (rect.left, middle),
+                (rect.left + rect.width, middle),
+                LineStyle {
+                    color: &border,
+                    width: 1.0,
+                    dash: None,
+                },
+            );
+            continue;
+        }
+
+        let (size, base) = match line.kind {
+            ui::LineKind::Heading(1) => (FONT_SIZE + 3.0, TextBase::Heading),
+            ui::LineKind::Heading(_) => (FONT_SIZE + 1.0, TextBase::Heading),
+            ui::LineKind::Quote => (FONT_SIZE, TextBase::Quote),
+            _ => (FONT_SIZE, TextBase::Plain),
+        };
+        // Code keeps its layout; everything else wraps at word boundaries.
+        let rows = if line.kind == ui::LineKind::Code {
+            vec![line.spans]
+        } else {
+            ui::wrap_spans(&line.spans, columns.saturating_sub(indent))
+        };
+        for row in rows {
+            y += CELL_HEIGHT;
+            if y > bottom {
+                return;
+            }
+            if quote {
+                draw_line(
+                    out,
+                    (rect.left + 4.0, y - CELL_HEIGHT + 4.0),
+                    (rect.left + 4.0, y + 4.0),
+                    LineStyle {
+                        color: &colors.dim,
+                        width: 2.0,
+                        dash: None,
+                    },
+                );
+            }
+            write_styled_text(out, x, y, &row, base, size, &colors);
+        }
+    }
+}
+
+/// Colors used for text panel content.
+struct TextColors {
+    text: String,
+    title: String,
+    dim: String,
+    code: String,
+}
+
+/// Style shared by every span of a text panel line.
+#[derive(Clone, Copy, PartialEq)]
+enum TextBase {
+    Plain,
+    Heading,
+    Quote,
+}
+
+fn write_styled_text(
+    out: &mut String,
+    x: f64,
+    y: f64,
+    spans: &[crate::ui::TextSpan],
+    base: TextBase,
+    size: f64,
+    colors: &TextColors,
+) {
+    let (fill, extra) = match base {
+        TextBase::Plain => (&colors.text, ""),
+        TextBase::Heading => (&colors.title, r#" font-weight="bold""#),
+        TextBase::Quote => (&colors.text, r#" font-style="italic""#),
+    };
+    write!(
+        out,
+        r#"<text x="{x:.2}" y="{y:.2}" fill="{fill}" font-size="{size:.1}" xml:space="preserve"{extra}>"#
+    )
+    .unwrap();
+    for span in spans {
+        let style = span.style;
+        out.push_str("<tspan");
+        if style.bold {
+            out.push_str(r#" font-weight="bold""#);
+        }
+        if style.italic {
+            out.push_str(r#" font-style="italic""#);
+        }
+        if style.code {
+            write!(out, r#" font-family="monospace" fill="{}""#, colors.code).unwrap();
+        }
+        if style.link {
+            write!(
+                out,
+                r#" fill="{}" text-decoration="underline""#,
+                colors.title
+            )
+            .unwrap();
+        } else if style.strike {
+            out.push_str(r#" text-decoration="line-through""#);
+        }
+        if style.dim {
+            write!(out, r#" fill="{}""#, colors.dim).unwrap();
+        }
+        write!(out, ">{}</tspan>", escape_xml(&span.text)).unwrap();
+    }
+    out.push_str("</text>");
+}
+
 fn first_visible_value(panel: &PanelState) -> Option<(&SeriesView, f64)> {
     panel
-        .series
-        .iter()
-        .filter(|series| series.visible)
-        .find_map(|series| series.value.map(|value| (series, value)))
+        .reduced_values()
+        .into_iter()
+        .find_map(|(series, values)| values.first().copied().flatten().map(|v| (series, v)))
 }
 
-fn value_color(app: &AppState, panel: &PanelState, value: f64) -> String {
-    color_hex(
-        panel
-            .get_color_for_value(value)
-            .unwrap_or(app.theme.palette[0]),
-        "#00ff88",
-    )
+/// Mapped or threshold color of a value. Without one, numbers use the first
+/// palette color and null values the text color.
+fn value_color(
+    app: &AppState,
+    panel: &PanelState,
+    series: &SeriesView,
+    value: Option<f64>,
+) -> String {
+    match (panel.value_color(series, value), value) {
+        (Some(color), _) => color_hex(color, "#00ff88"),
+        (None, Some(_)) => color_hex(app.theme.palette[0], "#00ff88"),
+        (None, None) => color_hex(app.theme.text, "#e6e6e6"),
+    }
 }
 
 fn value_ratio(value: f64, min: f64, max: f64) -> f64 {
@@ -1040,34 +1404,59 @@ fn render_sparkline(series: &SeriesView, rect: PlotRect, color: &str, out: &mut
     .unwrap();
 }
 
-fn render_legend(
-    app: &AppState,
-    panel: &PanelState,
-    left: f64,
-    top: f64,
-    width: f64,
-    out: &mut String,
-) {
-    let mut x = left;
-    let mut y = top + 15.0;
-    let text = color_hex(app.theme.text, "#e6e6e6");
-    let cursor_values = cursor_values(panel, app);
-
-    for (index, series) in panel.series.iter().enumerate().filter(|(_, s)| s.visible) {
-        let color = color_hex(series_color(panel, &app.theme, index), "#00ff88");
-        let value = cursor_values
-            .get(&series.name)
-            .copied()
-            .or(series.value)
-            .map(|value| panel.display.format_number(value));
-        let label = value
-            .map(|value| format!("{} ({value})", series.name))
-            .unwrap_or_else(|| series.name.clone());
-        let estimated_width = (label.len() as f64 * 7.0) + 24.0;
-        if x + estimated_width > left + width && x > left {
-            x = left;
-            y += 15.0;
+/// Width taken by a right-hand legend and height taken by a bottom one.
+fn legend_size(legend: &ui::Legend, rect: PlotRect) -> (f64, f64) {
+    if legend.mode == LegendDisplayMode::Hidden || legend.rows.is_empty() {
+        return (0.0, 0.0);
+    }
+    if legend.placement == LegendPlacement::Right {
+        let chars = legend
+            .rows
+            .iter()
+            .map(|row| match legend.mode {
+                LegendDisplayMode::Table => {
+                    row.name.len()
+                        + (0..legend.columns.len())
+                            .map(|column| legend_column_chars(legend, column) + 1)
+                            .sum::<usize>()
+                }
+                _ => legend.list_label(row).len(),
+            })
+            .max()
+            .unwrap_or(0);
+        let width = chars as f64 * 7.0 + 36.0;
+        return (width.min(rect.width * 0.4), 0.0);
+    }
+    match legend.mode {
+        LegendDisplayMode::Table => {
+            let rows = legend.rows.len() as f64 + 1.0;
+            let height = rows * LEGEND_ROW_HEIGHT + 6.0;
+            (0.0, height.min((rect.height / 3.0).max(LEGEND_HEIGHT)))
         }
+        _ => (0.0, LEGEND_HEIGHT),
+    }
+}
+
+fn legend_column_chars(legend: &ui::Legend, column: usize) -> usize {
+    legend
+        .rows
+        .iter()
+        .filter_map(|row| row.values[column].as_ref().map(String::len))
+        .chain([legend.columns[column].len()])
+        .max()
+        .unwrap_or(0)
+}
+
+fn render_legend(app: &AppState, legend: &ui::Legend, rect: PlotRect, out: &mut String) {
+    if legend.mode == LegendDisplayMode::Hidden {
+        return;
+    }
+    let text = color_hex(app.theme.text, "#e6e6e6");
+    let dim = color_hex(app.theme.legend_dim, "#777777");
+    let title = color_hex(app.theme.title, "#00c8ff");
+    let row_text = |row: &ui::LegendRow| if row.visible { &text } else { &dim };
+    let marker = |out: &mut String, row: &ui::LegendRow, x: f64, y: f64| {
+        let color = color_hex(row.color, "#00ff88");
         write!(
             out,
             r#"<rect x="{:.2}" y="{:.2}" width="8" height="8" fill="{color}"/>"#,
@@ -1075,30 +1464,83 @@ fn render_legend(
             y - 8.0
         )
         .unwrap();
-        write_text(out, x + 13.0, y, &label, &text, "start", SMALL_FONT_SIZE);
-        x += estimated_width;
-    }
-}
-
-fn cursor_values(panel: &PanelState, app: &AppState) -> std::collections::HashMap<String, f64> {
-    let mut values = std::collections::HashMap::new();
-    let Some(cursor_x) = app.cursor_x else {
-        return values;
     };
-
-    for series in &panel.series {
-        let closest = series.points.iter().min_by(|a, b| {
-            let da = (a.0 - cursor_x).abs();
-            let db = (b.0 - cursor_x).abs();
-            da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        if let Some((ts, value)) = closest {
-            if (ts - cursor_x).abs() <= app.step.as_secs_f64() * 2.0 {
-                values.insert(series.name.clone(), *value);
+    let visible_rows = (rect.height / LEGEND_ROW_HEIGHT).floor().max(1.0) as usize;
+
+    if legend.mode == LegendDisplayMode::Table {
+        let column_widths: Vec<f64> = (0..legend.columns.len())
+            .map(|column| legend_column_chars(legend, column) as f64 * 7.0 + 10.0)
+            .collect();
+        let mut column_x = rect.right() - column_widths.iter().sum::<f64>();
+        let mut y = rect.top + LEGEND_ROW_HEIGHT;
+        for (label, width) in legend.columns.iter().zip(&column_widths) {
+            write_text(out, column_x, y, label, &title, "start", SMALL_FONT_SIZE);
+            column_x += width;
+        }
+        for row in legend
+            .scrolled_rows(visible_rows.saturating_sub(1))
+            .iter()
+            .take(visible_rows.saturating_sub(1))
+        {
+            y += LEGEND_ROW_HEIGHT;
+            marker(out, row, rect.left, y);
+            let row_text = row_text(row);
+            write_text(
+                out,
+                rect.left + 13.0,
+                y,
+                &row.name,
+                row_text,
+                "start",
+                SMALL_FONT_SIZE,
+            );
+            let mut column_x = rect.right() - column_widths.iter().sum::<f64>();
+            for (value, width) in row.values.iter().zip(&column_widths) {
+                if let Some(value) = value {
+                    write_text(out, column_x, y, value, row_text, "start", SMALL_FONT_SIZE);
+                }
+                column_x += width;
+            }
+        }
+    } else if legend.is_vertical() {
+        let mut y = rect.top + LEGEND_ROW_HEIGHT;
+        for row in legend.scrolled_rows(visible_rows).iter().take(visible_rows) {
+            marker(out, row, rect.left, y);
+            let label = legend.list_label(row);
+            write_text(
+                out,
+                rect.left + 13.0,
+                y,
+                &label,
+                row_text(row),
+                "start",
+                SMALL_FONT_SIZE,
+            );
+            y += LEGEND_ROW_HEIGHT;
+        }
+    } else {
+        let mut x = rect.left;
+        let mut y = rect.top + LEGEND_ROW_HEIGHT;
+        for row in &legend.rows {
+            let label = legend.list_label(row);
+            let estimated_width = (label.len() as f64 * 7.0) + 24.0;
+            if x + estimated_width > rect.right() && x > rect.left {
+                x = rect.left;
+                y += LEGEND_ROW_HEIGHT;
             }
+            marker(out, row, x, y);
+            write_text(
+                out,
+                x + 13.0,
+                y,
+                &label,
+                row_text(row),
+                "start",
+                SMALL_FONT_SIZE,
+            );
+            x += estimated_width;
         }
     }
-    values
 }
 
 fn graph_area_opacity(options: &crate::app::GraphOptions) -> Option<f64> {
@@ -1108,11 +1550,12 @@ fn graph_area_opacity(options: &crate::app::GraphOptions) -> Option<f64> {
         .map(|value| f64::from(value.min(100)) / 100.0)
 }
 
-fn graph_area_baseline(y_bounds: [f64; 2]) -> f64 {
-    if y_bounds[0] <= 0.0 && y_bounds[1] >= 0.0 {
-        0.0
-    } else {
-        y_bounds[0]
+/// Axis position areas and bars start from: zero when the axis shows it,
+/// otherwise the bottom of the axis.
+fn graph_area_baseline(y_bounds: [f64; 2], scale: GraphScale) -> f64 {
+    match scale.position_of(0.0) {
+        Some(zero) if y_bounds[0] <= zero && y_bounds[1] >= zero => zero,
+        _ => y_bounds[0],
     }
 }
 
@@ -1121,14 +1564,14 @@ fn graph_point_in_range(x: f64, y: f64, x_bounds: [f64; 2]) -> bool {
 }
 
 fn render_graph_points(
-    series: &SeriesView,
+    points: &[(f64, f64)],
     rect: PlotRect,
     y_bounds: [f64; 2],
     x_bounds: [f64; 2],
     color: &str,
     out: &mut String,
 ) {
-    for (x, y) in &series.points {
+    for (x, y) in points {
         if !graph_point_in_range(*x, *y, x_bounds) {
             continue;
         }
@@ -1143,30 +1586,79 @@ fn render_graph_points(
     }
 }
 
+/// Marks NaN and infinite samples with a cross on the top (`+Inf`) or bottom
+/// edge of the plot.
+fn render_non_finite_markers(
+    samples: &[(f64, bool)],
+    rect: PlotRect,
+    x_bounds: [f64; 2],
+    color: &str,
+    out: &mut String,
+) {
+    for &(x, top) in samples {
+        if x < x_bounds[0] || x > x_bounds[1] {
+            continue;
+        }
+        let px = map_x(x, x_bounds, rect);
+        let py = if top { rect.top } else { rect.bottom() };
+        write!(
+            out,
+            r#"<path data-role="non-finite" d="M {:.2} {:.2} L {:.2} {:.2} M {:.2} {:.2} L {:.2} {:.2}" stroke="{}" stroke-width="1.4" />"#,
+            px - 3.0,
+            py - 3.0,
+            px + 3.0,
+            py + 3.0,
+            px - 3.0,
+            py + 3.0,
+            px + 3.0,
+            py - 3.0,
+            color
+        )
+        .unwrap();
+    }
+}
+
+/// Axis positions of each point and of where its bar or area starts: the
+/// stack `base` when the series is stacked, otherwise the axis baseline.
+fn graph_band(
+    points: &[(f64, f64)],
+    base: Option<&[(f64, f64)]>,
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+    scale: GraphScale,
+) -> Vec<(f64, f64, f64)> {
+    let baseline = graph_area_baseline(y_bounds, scale);
+    points
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &(x, y))| {
+            let y = scale.position_of(y)?;
+            let from = base
+                .and_then(|base| scale.position_of(base[index].1))
+                .unwrap_or(baseline);
+            graph_point_in_range(x, y, x_bounds).then_some((x, y, from))
+        })
+        .collect()
+}
+
 fn render_graph_bars(
-    series: &SeriesView,
+    band: &[(f64, f64, f64)],
     rect: PlotRect,
     y_bounds: [f64; 2],
     x_bounds: [f64; 2],
     color: &str,
     out: &mut String,
 ) {
-    let visible_points: Vec<_> = series
-        .points
-        .iter()
-        .filter(|(x, y)| graph_point_in_range(*x, *y, x_bounds))
-        .collect();
-    if visible_points.is_empty() {
+    if band.is_empty() {
         return;
     }
-    let bar_width = (rect.width / visible_points.len() as f64).max(1.0) * 0.7;
-    let baseline = graph_area_baseline(y_bounds);
-    let baseline_y = map_y(baseline, y_bounds, rect);
+    let bar_width = (rect.width / band.len() as f64).max(1.0) * 0.7;
 
-    for (x, y) in visible_points {
-        let px = map_x(*x, x_bounds, rect) - bar_width / 2.0;
-        let py = map_y(*y, y_bounds, rect).min(baseline_y);
-        let height = (baseline_y - map_y(*y, y_bounds, rect)).abs().max(1.0);
+    for &(x, y, from) in band {
+        let px = map_x(x, x_bounds, rect) - bar_width / 2.0;
+        let from_y = map_y(from, y_bounds, rect);
+        let py = map_y(y, y_bounds, rect).min(from_y);
+        let height = (from_y - map_y(y, y_bounds, rect)).abs().max(1.0);
         write!(
             out,
             r#"<rect data-role="graph-bar" x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" />"#,
@@ -1176,8 +1668,10 @@ fn render_graph_bars(
     }
 }
 
+/// Fills the area under a series, or between a stacked series and the
+/// series below it.
 fn render_graph_area(
-    series: &SeriesView,
+    band: &[(f64, f64, f64)],
     rect: PlotRect,
     y_bounds: [f64; 2],
     x_bounds: [f64; 2],
@@ -1185,29 +1679,31 @@ fn render_graph_area(
     opacity: f64,
     out: &mut String,
 ) {
-    let points: Vec<_> = series
-        .points
-        .iter()
-        .filter(|(x, y)| graph_point_in_range(*x, *y, x_bounds))
-        .collect();
-    if points.len() < 2 {
+    if band.len() < 2 {
         return;
     }
 
-    let baseline = graph_area_baseline(y_bounds);
-    let baseline_y = map_y(baseline, y_bounds, rect);
-    let first_x = map_x(points[0].0, x_bounds, rect);
-    let last_x = map_x(points[points.len() - 1].0, x_bounds, rect);
-
-    let mut path = format!("M {:.2} {:.2}", first_x, baseline_y);
-    for (x, y) in points {
-        path.push_str(&format!(
-            " L {:.2} {:.2}",
+    let mut path = String::new();
+    for (x, y, _) in band {
+        let command = if path.is_empty() { "M" } else { " L" };
+        write!(
+            path,
+            "{command} {:.2} {:.2}",
             map_x(*x, x_bounds, rect),
             map_y(*y, y_bounds, rect)
-        ));
+        )
+        .unwrap();
     }
-    path.push_str(&format!(" L {:.2} {:.2} Z", last_x, baseline_y));
+    for (x, _, from) in band.iter().rev() {
+        write!(
+            path,
+            " L {:.2} {:.2}",
+            map_x(*x, x_bounds, rect),
+            map_y(*from, y_bounds, rect)
+        )
+        .unwrap();
+    }
+    path.push_str(" Z");
 
     write!(
         out,
@@ -1217,68 +1713,80 @@ fn render_graph_area(
     .unwrap();
 }
 
+/// Path through `points`, with a subpath for each of the `segments` a line
+/// connects. Steps are drawn with `H` and `V` commands, and smooth lines
+/// with Béziers along the monotone curve the terminal approximates.
 fn series_path(
-    series: &SeriesView,
+    points: &[(f64, f64)],
+    segments: &[Range<usize>],
+    interpolation: GraphLineInterpolation,
     x_bounds: [f64; 2],
     y_bounds: [f64; 2],
     plot: PlotRect,
 ) -> Option<String> {
     let mut path = String::new();
-    let mut started = false;
 
-    for &(x_value, y_value) in &series.points {
-        if !x_value.is_finite()
-            || !y_value.is_finite()
-            || x_value < x_bounds[0]
-            || x_value > x_bounds[1]
-        {
+    for segment in segments {
+        let mapped: Vec<(f64, f64)> = points[segment.clone()]
+            .iter()
+            .filter(|(x, y)| {
+                x.is_finite() && y.is_finite() && *x >= x_bounds[0] && *x <= x_bounds[1]
+            })
+            .map(|&(x, y)| (map_x(x, x_bounds, plot), map_y(y, y_bounds, plot)))
+            .collect();
+        let Some(&(x, y)) = mapped.first() else {
             continue;
+        };
+        if !path.is_empty() {
+            path.push(' ');
         }
-        let x = map_x(x_value, x_bounds, plot);
-        let y = map_y(y_value, y_bounds, plot);
-        if started {
-            write!(path, " L {x:.2} {y:.2}").unwrap();
+        write!(path, "M {x:.2} {y:.2}").unwrap();
+
+        let tangents = if interpolation == GraphLineInterpolation::Smooth {
+            ui::monotone_tangents(&mapped)
         } else {
-            write!(path, "M {x:.2} {y:.2}").unwrap();
-            started = true;
+            Vec::new()
+        };
+        for (index, pair) in mapped.windows(2).enumerate() {
+            let [(x0, y0), (x1, y1)] = [pair[0], pair[1]];
+            match interpolation {
+                GraphLineInterpolation::Linear => write!(path, " L {x1:.2} {y1:.2}"),
+                GraphLineInterpolation::StepAfter => write!(path, " H {x1:.2} V {y1:.2}"),
+                GraphLineInterpolation::StepBefore => write!(path, " V {y1:.2} H {x1:.2}"),
+                GraphLineInterpolation::Smooth => {
+                    let third = (x1 - x0) / 3.0;
+                    write!(
+                        path,
+                        " C {:.2} {:.2} {:.2} {:.2} {x1:.2} {y1:.2}",
+                        x0 + third,
+                        y0 + tangents[index] * third,
+                        x1 - third,
+                        y1 - tangents[index + 1] * third
+                    )
+                }
+            }
+            .unwrap();
         }
     }
 
-    started.then_some(path)
+    (!path.is_empty()).then_some(path)
 }
 
 fn threshold_lines(panel: &PanelState, app: &AppState) -> Vec<(f64, Color, bool)> {
-    let Some(thresholds) = &panel.thresholds else {
+    let Some(thresholds) = panel.thresholds.as_ref().filter(|t| t.shows_lines()) else {
         return Vec::new();
     };
     thresholds
         .steps
         .iter()
         .filter_map(|step| {
-            let value = step.value?;
-            let value = match thresholds.mode {
-                ThresholdMode::Absolute => value,
-                ThresholdMode::Percentage => {
-                    let min = panel.min.unwrap_or(0.0);
-                    let max = panel.max.unwrap_or(100.0);
-                    min + (value / 100.0) * (max - min)
-                }
-            };
-            let dashed = app.threshold_marker.starts_with("dashed")
-                || thresholds.style.as_deref() == Some("dashed");
+            let value = thresholds.absolute_value(step.value?, panel.min, panel.max);
+            let dashed = app.threshold_marker.starts_with("dashed") || thresholds.is_dashed();
             Some((value, step.color, dashed))
         })
         .collect()
 }
 
-fn series_color(panel: &PanelState, theme: &Theme, index: usize) -> Color {
-    if panel.series.len() > theme.palette.len() {
-        ui::get_hash_color(&panel.series[index].name)
-    } else {
-        theme.palette[index % theme.palette.len()]
-    }
-}
-
 fn write_outputs(svg: &str, dir: &Path, stem: &str, format: ExportFormat) -> Result<Vec<PathBuf>> {
     fs::create_dir_all(dir)
         .with_context(|| format!("failed to create export directory {}", dir.display()))?;
@@ -1435,35 +1943,10 @@ fn time_ticks(start: f64, end: f64) -> Vec<f64> {
 }
 
 fn color_hex(color: Color, reset: &str) -> String {
-    match color {
-        Color::Reset => reset.to_string(),
-        Color::Black => "#000000".to_string(),
-        Color::Red => "#cc3333".to_string(),
-        Color::Green => "#33cc66".to_string(),
-        Color::Yellow => "#d6c343".to_string(),
-        Color::Blue => "#4f83ff".to_string(),
-        Color::Magenta => "#cc66cc".to_string(),
-        Color::Cyan => "#33c8cc".to_string(),
-        Color::Gray => "#a0a0a0".to_string(),
-        Color::DarkGray => "#666666".to_string(),
-        Color::LightRed => "#ff6666".to_string(),
-        Color::LightGreen => "#66ff99".to_string(),
-        Color::LightYellow => "#fff06a".to_string(),
-        Color::LightBlue => "#7aa2ff".to_string(),
-        Color::LightMagenta => "#ff8cff".to_string(),
-        Color::LightCyan => "#66ffff".to_string(),
-        Color::White => "#f5f5f5".to_string(),
-        Color::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
-        Color::Indexed(value) => indexed_color_hex(value).to_string(),
-    }
-}
-
-fn indexed_color_hex(value: u8) -> &'static str {
-    const ANSI: [&str; 16] = [
-        "#000000", "#cc3333", "#33cc66", "#d6c343", "#4f83ff", "#cc66cc", "#33c8cc", "#d0d0d0",
-        "#666666", "#ff6666", "#66ff99", "#fff06a", "#7aa2ff", "#ff8cff", "#66ffff", "#f5f5f5",
-    ];
-    ANSI.get(value as usize).copied().unwrap_or("#a0a0a0")
+    match ui::color_rgb(color) {
+        Some((r, g, b)) => format!("#{r:02x}{g:02x}{b:02x}"),
+        None => reset.to_string(),
+    }
 }
 
 fn escape_xml(input: &str) -> String {
@@ -1495,8 +1978,9 @@ fn display_paths(paths: &[PathBuf]) -> String {
 mod tests {
     use super::*;
     use crate::app::{
-        GraphAxisPlacement, GraphDrawStyle, GraphOptions, GraphPointMode, GraphStackingMode,
-        PanelOptions, PanelState, SeriesView, YAxisMode,
+        GraphAxisPlacement, GraphDrawStyle, GraphLineInterpolation, GraphOptions, GraphPointMode,
+        GraphStackingMode, NullSample, PanelOptions, PanelState, Reducer, SeriesView, SpanNulls,
+        ThresholdMode, ThresholdStep, Thresholds, YAxisMode,
     };
 
     fn test_panel(start: f64) -> PanelState {
@@ -1505,24 +1989,35 @@ mod tests {
             exprs: vec![],
             legends: vec![],
             query_modes: vec![],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: crate::app::QueryResolution::default(),
             series: vec![SeriesView {
                 name: "usage & total".to_string(),
                 value: Some(10.0),
                 points: vec![(start, 0.0), (start + 50.0, 50.0), (start + 100.0, 100.0)],
                 visible: true,
+                ..Default::default()
             }],
             last_error: None,
             last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
             last_samples: 3,
             grid: None,
             y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
             panel_type: PanelType::Graph,
             thresholds: None,
             min: None,
             max: None,
             autogrid: None,
             display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
             options: PanelOptions::Graph(GraphOptions::default()),
+            repeat: None,
+            scoped_vars: std::collections::HashMap::new(),
         }
     }
 
@@ -1539,14 +2034,14 @@ mod tests {
         let now = chrono::Utc::now().timestamp() as f64;
         let range = std::time::Duration::from_secs(100);
         AppState::new(
-            prom,
+            crate::app::DatasourceRegistry::single(prom),
             range,
             std::time::Duration::from_secs(10),
             std::time::Duration::from_secs(1),
             "Dash & Main".to_string(),
             vec![test_panel(now - range.as_secs_f64())],
             0,
-            Theme::default(),
+            crate::theme::Theme::default(),
             "dashed-line".to_string(),
             export,
         )
@@ -1641,8 +2136,9 @@ mod tests {
             show_points: GraphPointMode::Auto,
             fill_opacity: None,
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let points_svg = render_svg(&points_app, Rect::new(0, 0, 120, 50));
         assert!(points_svg.contains(r#"data-role="graph-point""#));
@@ -1653,8 +2149,9 @@ mod tests {
             show_points: GraphPointMode::Never,
             fill_opacity: Some(30),
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let area_svg = render_svg(&area_app, Rect::new(0, 0, 120, 50));
         assert!(area_svg.contains(r#"data-role="graph-area""#));
@@ -1666,13 +2163,198 @@ mod tests {
             show_points: GraphPointMode::Auto,
             fill_opacity: None,
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let bars_svg = render_svg(&bars_app, Rect::new(0, 0, 120, 50));
         assert!(bars_svg.contains(r#"data-role="graph-bar""#));
     }
 
+    #[test]
+    fn test_graph_export_fills_stacked_series_between_neighbours() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let panel = &mut app.panels[0];
+        let mut upper = panel.series[0].clone();
+        upper.name = "upper".to_string();
+        panel.series.push(upper);
+        panel.options = PanelOptions::Graph(GraphOptions {
+            fill_opacity: Some(40),
+            stacking: GraphStackingMode::Percent,
+            ..GraphOptions::default()
+        });
+
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        assert_eq!(svg.matches(r#"data-role="graph-area""#).count(), 2);
+        // Percent stacks are labelled as ratios of the group total.
+        assert!(svg.contains(">100.00%<"));
+
+        let stacked = ui::stack_series(&app.panels[0]).unwrap();
+        let upper = stacked[1].as_ref().unwrap();
+        assert_eq!(upper.base[1], (upper.base[1].0, 0.5));
+        assert_eq!(upper.points[1].1, 1.0);
+    }
+
+    #[test]
+    fn test_graph_export_labels_log_scale_decades() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let panel = &mut app.panels[0];
+        let start = panel.series[0].points[0].0;
+        panel.series[0].points = vec![(start, 0.0), (start + 50.0, 0.5), (start + 100.0, 5000.0)];
+        panel.options = PanelOptions::Graph(GraphOptions {
+            fill_opacity: Some(20),
+            scale: GraphScale::Log { base: 10.0 },
+            ..GraphOptions::default()
+        });
+
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        // Every other decade is labelled at this size.
+        for label in [">1.00<", ">100.00<"] {
+            assert!(svg.contains(label), "missing {label}");
+        }
+        assert!(!svg.contains("NaN") && !svg.contains("inf"));
+    }
+
+    #[test]
+    fn test_graph_export_draws_right_axis_with_its_own_unit() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let panel = &mut app.panels[0];
+        let start = panel.series[0].points[0].0;
+        panel.series[0].points = vec![(start, 0.0), (start + 100.0, 10.0)];
+        let mut latency = panel.series[0].clone();
+        latency.name = "latency".to_string();
+        latency.points = vec![(start, 0.5), (start + 100.0, 2.0)];
+        latency.overrides.axis_placement = Some(GraphAxisPlacement::Right);
+        latency.overrides.display = Some(ui::DisplayFormat {
+            unit: Some("s".to_string()),
+            ..Default::default()
+        });
+        panel.series.push(latency);
+
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        assert!(svg.contains(r#"text-anchor="start""#));
+        assert!(svg.contains(">10.50<"), "left bound missing");
+        assert!(svg.contains(">2.08s<"), "right bound missing");
+        assert!(svg.contains(">1.25s<"), "right tick missing");
+    }
+
+    #[test]
+    fn test_graph_export_shades_threshold_areas() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let panel = &mut app.panels[0];
+        panel.min = Some(0.0);
+        panel.max = Some(200.0);
+        panel.thresholds = Some(Thresholds {
+            mode: ThresholdMode::Percentage,
+            steps: vec![
+                ThresholdStep {
+                    value: None,
+                    color: Color::Rgb(0x73, 0xbf, 0x69),
+                },
+                ThresholdStep {
+                    value: Some(50.0),
+                    color: Color::Rgb(0xf2, 0x49, 0x5c),
+                },
+            ],
+            style: Some("line+area".to_string()),
+        });
+
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        let heights: Vec<&str> = svg
+            .split(r#"data-role="threshold-area""#)
+            .skip(1)
+            .map(|rect| rect.split(r#"height=""#).nth(1).unwrap())
+            .map(|rest| &rest[..rest.find('"').unwrap()])
+            .collect();
+        // 50% of 0-200 splits the plot at 100, halfway up.
+        assert_eq!(heights.len(), 2);
+        assert_eq!(heights[0], heights[1]);
+        assert!(svg.contains(r##"fill="#73bf69" fill-opacity="0.15""##));
+        assert!(svg.contains(r##"fill="#f2495c" fill-opacity="0.15""##));
+        assert!(svg.contains(r##"stroke="#f2495c" stroke-width="1.20""##));
+
+        app.panels[0].thresholds.as_mut().unwrap().style = Some("area".to_string());
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        assert_eq!(svg.matches(r#"data-role="threshold-area""#).count(), 2);
+        assert!(!svg.contains(r##"stroke="#f2495c" stroke-width="1.20""##));
+    }
+
+    #[test]
+    fn test_graph_export_breaks_lines_at_nulls_and_marks_non_finite_samples() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let panel = &mut app.panels[0];
+        let start = panel.series[0].points[0].0;
+        panel.series[0].points = vec![
+            (start, 1.0),
+            (start + 10.0, 2.0),
+            (start + 60.0, 3.0),
+            (start + 70.0, 4.0),
+        ];
+        panel.series[0].nulls = vec![
+            NullSample {
+                ts: start + 20.0,
+                value: None,
+            },
+            NullSample {
+                ts: start + 80.0,
+                value: Some(f64::NAN),
+            },
+        ];
+
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        let path = svg
+            .split("<path d=\"")
+            .nth(1)
+            .and_then(|path| path.split('"').next())
+            .unwrap();
+        assert_eq!(path.matches('M').count(), 2);
+        assert_eq!(svg.matches(r#"data-role="non-finite""#).count(), 1);
+
+        app.panels[0].options = PanelOptions::Graph(GraphOptions {
+            span_nulls: SpanNulls::Always,
+            ..GraphOptions::default()
+        });
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        let path = svg
+            .split("<path d=\"")
+            .nth(1)
+            .and_then(|path| path.split('"').next())
+            .unwrap();
+        assert_eq!(path.matches('M').count(), 1);
+    }
+
+    #[test]
+    fn test_graph_export_draws_step_and_smooth_interpolation() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let start = app.panels[0].series[0].points[0].0;
+        app.panels[0].series[0].points =
+            vec![(start, 1.0), (start + 50.0, 3.0), (start + 100.0, 2.0)];
+        let line_path = |app: &AppState| {
+            let svg = render_svg(app, Rect::new(0, 0, 120, 50));
+            svg.split("<path d=\"")
+                .nth(1)
+                .and_then(|path| path.split('"').next())
+                .unwrap()
+                .to_string()
+        };
+
+        app.panels[0].options = PanelOptions::Graph(GraphOptions {
+            line_interpolation: GraphLineInterpolation::StepAfter,
+            ..GraphOptions::default()
+        });
+        let path = line_path(&app);
+        assert_eq!(path.matches(" H ").count(), 2);
+        assert_eq!(path.matches(" V ").count(), 2);
+        assert!(!path.contains(" L "));
+
+        app.panels[0].options = PanelOptions::Graph(GraphOptions {
+            line_interpolation: GraphLineInterpolation::Smooth,
+            ..GraphOptions::default()
+        });
+        let path = line_path(&app);
+        assert_eq!(path.matches(" C ").count(), 2);
+    }
+
     #[test]
     fn test_graph_export_skips_non_finite_style_points() {
         let mut points_app = test_app_with_panel_type(PanelType::Graph);
@@ -1681,8 +2363,9 @@ mod tests {
             show_points: GraphPointMode::Auto,
             fill_opacity: None,
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let points_start = points_app.panels[0].series[0].points[0].0;
         points_app.panels[0].series[0].points = vec![
@@ -1700,8 +2383,9 @@ mod tests {
             show_points: GraphPointMode::Never,
             fill_opacity: Some(30),
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let area_start = area_app.panels[0].series[0].points[0].0;
         area_app.panels[0].series[0].points = vec![
@@ -1719,8 +2403,9 @@ mod tests {
             show_points: GraphPointMode::Auto,
             fill_opacity: None,
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let bars_start = bars_app.panels[0].series[0].points[0].0;
         bars_app.panels[0].series[0].points = vec![
@@ -1740,7 +2425,7 @@ mod tests {
             (PanelType::Gauge, "10%", "100.00"),
             (PanelType::BarGauge, "usage &amp; total", "10.00"),
             (PanelType::Table, "Series", "Value"),
-            (PanelType::Heatmap, "#cc3333", "#33c8cc"),
+            (PanelType::Heatmap, "#7f2704", "#fff5eb"),
         ];
 
         for (panel_type, first, second) in cases {
@@ -1754,6 +2439,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_heatmap_export_labels_buckets_and_draws_color_scale() {
+        let mut app = test_app_with_panel_type(PanelType::Heatmap);
+        let panel = &mut app.panels[0];
+        let start = panel.series[0].points[0].0;
+        let template = panel.series[0].clone();
+        panel.series = [(f64::INFINITY, 9.0), (0.25, 2.0), (1.0, 5.0)]
+            .into_iter()
+            .map(|(bound, count)| SeriesView {
+                bucket: Some(bound),
+                points: (0..=10)
+                    .map(|i| (start + f64::from(i) * 10.0, count))
+                    .collect(),
+                ..template.clone()
+            })
+            .collect();
+
+        let svg = render_svg(&app, Rect::new(0, 0, 100, 40));
+        let labels: Vec<_> = [">0.25<", ">1<", ">+Inf<"]
+            .into_iter()
+            .map(|label| svg.find(label).unwrap_or_else(|| panic!("missing {label}")))
+            .collect();
+        assert!(labels.windows(2).all(|pair| pair[0] < pair[1]));
+        // The color scale runs from the smallest to the largest bucket count.
+        assert!(svg.contains(">2.00<") && svg.contains(">4.00<"));
+        assert!(svg.contains(r##"fill="#7f2704""##));
+        assert!(svg.contains(r##"fill="#fff5eb""##));
+    }
+
+    #[test]
+    fn test_text_panel_export_renders_styled_markdown() {
+        let mut app = test_app_with_panel_type(PanelType::Text);
+        app.vars.insert(
+            "team".to_string(),
+            crate::app::Variable::single("db & storage"),
+        );
+        app.panels[0].options = PanelOptions::Text(crate::app::TextOptions {
+            mode: crate::app::TextMode::Markdown,
+            content: "# On call\n\nPage **$team** via [runbook](https://wiki/rb)".to_string(),
+        });
+        let svg = render_svg(&app, Rect::new(0, 0, 100, 40));
+
+        assert!(svg.contains(r#"font-weight="bold"><tspan>On call</tspan>"#));
+        assert!(svg.contains(r#"<tspan font-weight="bold">db &amp; storage</tspan>"#));
+        assert!(svg.contains(r#"text-decoration="underline">runbook</tspan>"#));
+        assert!(svg.contains("(https://wiki/rb)"));
+        assert!(!svg.contains("No data"));
+    }
+
     #[test]
     fn test_export_uses_panel_display_format_for_stat_and_table_values() {
         let mut stat_app = test_app_with_panel_type(PanelType::Stat);
@@ -1761,6 +2495,7 @@ mod tests {
             unit: Some("bytes".to_string()),
             decimals: Some(1),
             no_value: None,
+            mappings: Vec::new(),
         };
         stat_app.panels[0].series[0].value = Some(1536.0);
 
@@ -1769,6 +2504,7 @@ mod tests {
 
         stat_app.panels[0].display.no_value = Some("n/a".to_string());
         stat_app.panels[0].series[0].value = None;
+        stat_app.panels[0].series[0].points.clear();
         let stat_no_value_svg = render_svg(&stat_app, Rect::new(0, 0, 100, 40));
         assert!(stat_no_value_svg.contains("n/a"));
 
@@ -1777,13 +2513,13 @@ mod tests {
             unit: Some("percentunit".to_string()),
             decimals: Some(0),
             no_value: Some("n/a".to_string()),
+            mappings: Vec::new(),
         };
         table_app.panels[0].series[0].value = Some(0.42);
         table_app.panels[0].series.push(SeriesView {
             name: "missing".to_string(),
-            value: None,
-            points: vec![],
             visible: true,
+            ..Default::default()
         });
 
         let table_svg = render_svg(&table_app, Rect::new(0, 0, 100, 40));
@@ -1791,6 +2527,109 @@ mod tests {
         assert!(table_svg.contains("n/a"));
     }
 
+    #[test]
+    fn test_export_applies_value_mappings_to_text_and_color() {
+        let status = ui::DisplayFormat {
+            mappings: vec![ui::ValueMapping::Value {
+                value: "2".to_string(),
+                result: ui::MappingResult {
+                    text: Some("DEGRADED".to_string()),
+                    color: Some(Color::Yellow),
+                },
+            }],
+            ..Default::default()
+        };
+
+        for panel_type in [PanelType::Stat, PanelType::BarGauge, PanelType::Table] {
+            let mut app = test_app_with_panel_type(panel_type);
+            app.panels[0].display = status.clone();
+            app.panels[0].series[0].value = Some(2.0);
+
+            let svg = render_svg(&app, Rect::new(0, 0, 100, 40));
+            assert!(svg.contains("DEGRADED"), "{panel_type:?}");
+            assert!(svg.contains(r##"fill="#d6c343""##), "{panel_type:?}");
+        }
+    }
+
+    #[test]
+    fn test_export_legend_follows_legend_options() {
+        let mut app = test_app_with_panel_type(PanelType::Graph);
+        let mut hidden = app.panels[0].series[0].clone();
+        hidden.name = "idle".to_string();
+        hidden.visible = false;
+        app.panels[0].series.push(hidden);
+        app.panels[0].options = PanelOptions::Graph(GraphOptions {
+            legend: crate::app::LegendOptions {
+                display_mode: LegendDisplayMode::Table,
+                calcs: vec![Reducer::Min, Reducer::Max],
+                sort_by: Some("Name".to_string()),
+                ..Default::default()
+            },
+            ..GraphOptions::default()
+        });
+
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        assert!(svg.contains(">Min</text>") && svg.contains(">Max</text>"));
+        assert!(svg.contains(">100.00</text>"));
+        let dim = color_hex(app.theme.legend_dim, "#777777");
+        assert!(svg.contains(&format!(
+            r#"fill="{dim}" font-size="11.0" text-anchor="start">idle</text>"#
+        )));
+        assert!(svg.find(">idle<").unwrap() < svg.find(">usage &amp; total<").unwrap());
+
+        app.panels[0].options = PanelOptions::Graph(GraphOptions {
+            legend: crate::app::LegendOptions {
+                display_mode: LegendDisplayMode::Hidden,
+                ..Default::default()
+            },
+            ..GraphOptions::default()
+        });
+        let svg = render_svg(&app, Rect::new(0, 0, 120, 50));
+        assert!(!svg.contains(">idle<"));
+    }
+
+    #[test]
+    fn test_export_uses_series_override_units_and_colors() {
+        let mut table_app = test_app_with_panel_type(PanelType::Table);
+        let panel = &mut table_app.panels[0];
+        panel.display.unit = Some("bytes".to_string());
+        panel.series[0].value = Some(1536.0);
+        let mut cpu = panel.series[0].clone();
+        cpu.name = "CPU".to_string();
+        cpu.value = Some(0.5);
+        cpu.overrides.display = Some(ui::DisplayFormat {
+            unit: Some("percentunit".to_string()),
+            decimals: Some(0),
+            ..Default::default()
+        });
+        panel.series.push(cpu);
+
+        let table_svg = render_svg(&table_app, Rect::new(0, 0, 100, 40));
+        assert!(table_svg.contains("1.54KB"));
+        assert!(table_svg.contains("50%"));
+
+        let mut graph_app = test_app_with_panel_type(PanelType::Graph);
+        graph_app.panels[0].series[0].overrides.color = Some(Color::Rgb(1, 2, 3));
+        let graph_svg = render_svg(&graph_app, Rect::new(0, 0, 100, 40));
+        assert!(graph_svg.contains("#010203"));
+    }
+
+    #[test]
+    fn test_export_tables_show_a_column_per_reducer() {
+        let mut app = test_app_with_panel_type(PanelType::Table);
+        app.panels[0].options = PanelOptions::Reduce(crate::app::ReduceOptions {
+            calcs: vec![Reducer::Min, Reducer::Mean, Reducer::Percentile(95)],
+            ..Default::default()
+        });
+
+        let svg = render_svg(&app, Rect::new(0, 0, 100, 40));
+        for label in ["Min", "Mean", "95th %"] {
+            assert!(svg.contains(&format!(">{label}</text>")), "missing {label}");
+        }
+        assert!(svg.contains(">50.00</text>"));
+        assert!(!svg.contains(">Value</text>"));
+    }
+
     #[test]
     fn test_png_rasterization_writes_non_empty_file() {
         let app = test_app(ExportOptions::default());
@@ -26,9 +26,15 @@ pub(crate) struct DashboardImport {
     /// List of panels extracted.
     pub(crate) queries: Vec<QueryPanel>,
     /// Variables extracted from `templating.list`.
-    pub(crate) vars: HashMap<String, String>,
+    pub(crate) vars: HashMap<String, crate::app::Variable>,
+    /// Names of all `templating.list` variables, in dashboard order.
+    pub(crate) var_names: Vec<String>,
     /// Dynamic query variables extracted from `templating.list`.
     pub(crate) query_vars: Vec<TemplateQueryVar>,
+    /// Names of `datasource` variables extracted from `templating.list`.
+    pub(crate) datasource_vars: Vec<String>,
+    /// Filters of `adhoc` variables extracted from `templating.list`.
+    pub(crate) filters: Vec<crate::app::LabelMatcher>,
     /// Number of panels that were skipped (unsupported types).
     pub(crate) skipped_panels: usize,
     /// Dashboard-level refresh interval in milliseconds, if provided.
@@ -58,19 +64,37 @@ impl ImportDiagnostic {
     }
 }
 
-/// A Prometheus-backed Grafana template variable.
+/// A Grafana template variable resolved again on every refresh.
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) struct TemplateQueryVar {
     /// Variable name used in PromQL expressions.
     pub(crate) name: String,
-    /// Prometheus variable query expression.
-    pub(crate) query: String,
-    /// Optional Grafana regex extractor.
-    pub(crate) regex: Option<String>,
+    /// How the variable is resolved.
+    pub(crate) source: TemplateVarSource,
     /// JSON-ish source path for the variable query.
     pub(crate) query_path: String,
 }
 
+/// What a refreshed template variable is resolved from.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TemplateVarSource {
+    /// Options returned by a Prometheus variable query.
+    Query {
+        /// Prometheus variable query expression.
+        query: String,
+        /// Optional Grafana regex extractor.
+        regex: Option<String>,
+        /// Grafana datasource reference (UID, name or variable) for the query.
+        datasource: Option<String>,
+    },
+    /// The `auto` option of an interval variable: the range split into
+    /// `count` intervals, but no shorter than `min`.
+    AutoInterval {
+        count: u32,
+        min: std::time::Duration,
+    },
+}
+
 /// A single panel extracted from Grafana.
 #[derive(Debug, Clone)]
 pub(crate) struct QueryPanel {
@@ -79,6 +103,9 @@ pub(crate) struct QueryPanel {
     pub(crate) expr_paths: Vec<String>,      // Parallel to exprs
     pub(crate) legends: Vec<Option<String>>, // Parallel to exprs
     pub(crate) query_modes: Vec<crate::app::QueryMode>, // Parallel to exprs
+    pub(crate) datasources: Vec<Option<String>>, // Parallel to exprs
+    pub(crate) ref_ids: Vec<String>,         // Parallel to exprs
+    pub(crate) resolution: crate::app::QueryResolution,
     pub(crate) grid: Option<GridPos>,
     pub(crate) panel_type: crate::app::PanelType,
     pub(crate) thresholds: Option<crate::app::Thresholds>,
@@ -86,7 +113,9 @@ pub(crate) struct QueryPanel {
     pub(crate) max: Option<f64>,
     pub(crate) autogrid: Option<bool>,
     pub(crate) display: crate::ui::DisplayFormat,
+    pub(crate) overrides: Vec<crate::app::FieldOverride>,
     pub(crate) options: crate::app::PanelOptions,
+    pub(crate) repeat: Option<crate::app::PanelRepeat>,
 }
 
 /// Grid position extracted from Grafana.
@@ -123,6 +152,29 @@ struct RawVar {
     /// The value to use when "All" is selected. Used to replace $__all in queries.
     #[serde(rename = "allValue")]
     all_value: Option<String>,
+    datasource: Option<serde_json::Value>,
+    multi: Option<bool>,
+    #[serde(rename = "includeAll")]
+    include_all: Option<bool>,
+    options: Option<Vec<RawVarOption>>,
+    /// Whether an interval variable offers the `auto` option.
+    auto: Option<bool>,
+    auto_count: Option<u32>,
+    auto_min: Option<String>,
+    /// Filters of an `adhoc` variable.
+    filters: Option<Vec<RawAdhocFilter>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawAdhocFilter {
+    key: Option<String>,
+    operator: Option<String>,
+    value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawVarOption {
+    value: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,20 +199,154 @@ struct RawPanel {
     #[serde(rename = "gridPos")]
     grid_pos: Option<RawGridPos>,
     panels: Option<Vec<RawPanel>>, // nested rows
+    /// Whether a row hides its panels.
+    collapsed: Option<bool>,
+    datasource: Option<serde_json::Value>,
     #[serde(rename = "fieldConfig")]
     field_config: Option<RawFieldConfig>,
     options: Option<RawPanelOptions>,
+    #[serde(rename = "maxDataPoints")]
+    max_data_points: Option<u32>,
+    interval: Option<String>,
+    repeat: Option<String>,
+    #[serde(rename = "repeatDirection")]
+    repeat_direction: Option<String>,
+    #[serde(rename = "maxPerRow")]
+    max_per_row: Option<u32>,
+    /// Null handling of the graph panel before Grafana 7.
+    #[serde(rename = "nullPointMode")]
+    null_point_mode: Option<String>,
+    /// Text panel content and mode, kept here by Grafana before 7.0.
+    content: Option<serde_json::Value>,
+    mode: Option<serde_json::Value>,
+    /// Set on repeat clones saved by older Grafana versions.
+    #[serde(rename = "repeatPanelId")]
+    repeat_panel_id: Option<serde_json::Value>,
+    /// Heatmap settings, kept here by Grafana before 9.0.
+    color: Option<RawHeatmapColor>,
+    legend: Option<RawLegacyLegend>,
+    #[serde(rename = "yAxis")]
+    y_axis: Option<RawHeatmapYAxis>,
+    #[serde(rename = "dataFormat")]
+    data_format: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct RawPanelOptions {
     #[serde(rename = "reduceOptions")]
-    reduce_options: Option<serde_json::Value>,
+    reduce_options: Option<RawReduceOptions>,
+    legend: Option<RawLegendOptions>,
+    /// Text panel content.
+    content: Option<serde_json::Value>,
+    /// Text panel mode.
+    mode: Option<serde_json::Value>,
+    /// Heatmap cell colors.
+    color: Option<RawHeatmapColor>,
+    /// Heatmap bucket axis.
+    #[serde(rename = "yAxis")]
+    y_axis: Option<RawHeatmapYAxis>,
+    /// Whether a heatmap computes buckets from series values.
+    calculate: Option<bool>,
+}
+
+/// Heatmap colors, in `options.color` or, before Grafana 9.0, in the
+/// panel's `color` with the legacy field names.
+#[derive(Debug, Deserialize)]
+struct RawHeatmapColor {
+    mode: Option<String>,
+    scheme: Option<String>,
+    #[serde(rename = "colorScheme")]
+    color_scheme: Option<String>,
+    fill: Option<String>,
+    #[serde(rename = "cardColor")]
+    card_color: Option<String>,
+    scale: Option<String>,
+    #[serde(rename = "colorScale")]
+    color_scale: Option<String>,
+    exponent: Option<f64>,
+    reverse: Option<bool>,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawHeatmapYAxis {
+    unit: Option<String>,
+    /// Unit of heatmaps saved before Grafana 9.0.
+    format: Option<String>,
+    decimals: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLegacyLegend {
+    show: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLegendOptions {
+    #[serde(rename = "displayMode")]
+    display_mode: Option<String>,
+    placement: Option<String>,
+    calcs: Option<Vec<String>>,
+    #[serde(rename = "showLegend")]
+    show_legend: Option<bool>,
+    #[serde(rename = "sortBy")]
+    sort_by: Option<String>,
+    #[serde(rename = "sortDesc")]
+    sort_desc: Option<bool>,
+    /// Whether a heatmap shows its color scale.
+    show: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawReduceOptions {
+    calcs: Option<Vec<String>>,
+    values: Option<bool>,
+    limit: Option<usize>,
+    fields: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct RawFieldConfig {
     defaults: Option<RawFieldConfigDefaults>,
+    overrides: Option<Vec<RawFieldOverride>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFieldOverride {
+    matcher: RawFieldMatcher,
+    properties: Option<Vec<RawOverrideProperty>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFieldMatcher {
+    id: String,
+    options: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawNamesMatcher {
+    mode: Option<String>,
+    names: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawOverrideProperty {
+    id: String,
+    value: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFieldColor {
+    mode: Option<String>,
+    #[serde(rename = "fixedColor")]
+    fixed_color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawHideFrom {
+    legend: Option<bool>,
+    viz: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -173,7 +359,46 @@ struct RawFieldConfigDefaults {
     max: Option<f64>,
     thresholds: Option<RawThresholds>,
     custom: Option<RawCustom>,
-    mappings: Option<serde_json::Value>,
+    mappings: Option<Vec<RawValueMapping>>,
+}
+
+/// A value mapping, either `{type, options}` or the pre-8.0 form with
+/// `type` 1 (value) or 2 (range) and top-level `value`, `from`, `to` and `text`.
+#[derive(Debug, Deserialize)]
+struct RawValueMapping {
+    #[serde(rename = "type")]
+    kind: Option<serde_json::Value>,
+    options: Option<serde_json::Value>,
+    value: Option<serde_json::Value>,
+    from: Option<serde_json::Value>,
+    to: Option<serde_json::Value>,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRangeMapping {
+    from: Option<f64>,
+    to: Option<f64>,
+    result: RawMappingResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRegexMapping {
+    pattern: String,
+    result: RawMappingResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSpecialMapping {
+    #[serde(rename = "match")]
+    matcher: String,
+    result: RawMappingResult,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawMappingResult {
+    text: Option<String>,
+    color: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -193,6 +418,23 @@ struct RawCustom {
     axis_grid_show: Option<bool>,
     #[serde(rename = "thresholdsStyle")]
     thresholds_style: Option<RawThresholdsStyle>,
+    #[serde(rename = "scaleDistribution")]
+    scale_distribution: Option<RawScaleDistribution>,
+    /// `true`, `false` or a threshold in milliseconds.
+    #[serde(rename = "spanNulls")]
+    span_nulls: Option<serde_json::Value>,
+    /// `false` or a threshold in milliseconds.
+    #[serde(rename = "insertNulls")]
+    insert_nulls: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawScaleDistribution {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    log: Option<f64>,
+    #[serde(rename = "linearThreshold")]
+    linear_threshold: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -203,6 +445,7 @@ struct RawThresholdsStyle {
 #[derive(Debug, Deserialize)]
 struct RawStacking {
     mode: Option<String>,
+    group: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -224,6 +467,12 @@ struct RawTarget {
     legend_format: Option<String>,
     instant: Option<bool>,
     hide: Option<bool>,
+    datasource: Option<serde_json::Value>,
+    interval: Option<String>,
+    #[serde(rename = "intervalFactor")]
+    interval_factor: Option<u32>,
+    #[serde(rename = "refId")]
+    ref_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -241,58 +490,82 @@ pub(crate) fn load_grafana_dashboard(path: &std::path::Path) -> Result<Dashboard
         serde_json::from_str(&data).with_context(|| "parsing grafana dashboard JSON")?;
 
     let mut vars = HashMap::new();
+    let mut var_names = Vec::new();
     let mut query_vars = Vec::new();
-    if let Some(templating) = raw.templating {
-        if let Some(list) = templating.list {
-            for (var_idx, v) in list.into_iter().enumerate() {
-                // Heuristic: prefer 'value' over 'text', handle arrays by taking first or joining?
-                // Grafana 'current' value can be "All" or ["val1", "val2"].
-                // For simple PromQL substitution, we usually want the raw value.
-                // If it's "All", it might be $__all, which is tricky.
-                // Let's try to get a string representation.
-                let val = v
-                    .current
-                    .as_ref()
-                    .and_then(|c| c.value.as_ref())
-                    .or(v.current.as_ref().and_then(|c| c.text.as_ref()));
-
-                if let Some(val) = val {
-                    let mut s = match val {
-                        serde_json::Value::String(s) => s.clone(),
-                        serde_json::Value::Array(arr) => {
-                            // If array, maybe join with pipe for regex? or just take first?
-                            // For now, let's take the first string we find.
-                            arr.iter()
-                                .find_map(|x| x.as_str())
-                                .unwrap_or("")
-                                .to_string()
-                        }
-                        serde_json::Value::Number(n) => n.to_string(),
-                        _ => String::new(),
-                    };
+    let mut datasource_vars = Vec::new();
+    let mut filters = Vec::new();
+    let mut diagnostics = Vec::new();
+    if let Some(templating) = raw.templating
+        && let Some(list) = templating.list
+    {
+        for (var_idx, v) in list.into_iter().enumerate() {
+            let path = format!("templating.list[{var_idx}]");
+            if v.var_type.as_deref() == Some("adhoc") {
+                filters.extend(adhoc_filters(
+                    v.filters.unwrap_or_default(),
+                    &path,
+                    &mut diagnostics,
+                ));
+                continue;
+            }
+            var_names.push(v.name.clone());
+            let kind = match v.var_type.as_deref() {
+                None => Some(crate::app::VariableKind::User),
+                Some(var_type) => crate::app::VariableKind::parse(var_type),
+            };
+            let Some(kind) = kind else {
+                diagnostics.push(ImportDiagnostic::new(
+                    "unsupported_variable_type",
+                    format!("{path}.type"),
+                    format!(
+                        "unsupported variable type `{}`; its current value is used as is",
+                        v.var_type.as_deref().unwrap_or_default()
+                    ),
+                ));
+                let var = v.variable(crate::app::VariableKind::User);
+                if var.all || !var.current.is_empty() {
+                    vars.insert(v.name.clone(), var);
+                }
+                continue;
+            };
 
-                    // Handle $__all
-                    if s == "$__all" {
-                        // Use allValue if present, otherwise permissive regex
-                        s = v.all_value.clone().unwrap_or_else(|| ".*".to_string());
-                    }
+            let var = v.variable(kind);
+            if var.all || !var.current.is_empty() {
+                vars.insert(v.name.clone(), var);
+            }
 
-                    if !s.is_empty() {
-                        vars.insert(v.name.clone(), s);
+            match kind {
+                crate::app::VariableKind::Datasource => datasource_vars.push(v.name.clone()),
+                crate::app::VariableKind::Query => {
+                    if let Some(query) = v.query_string() {
+                        let datasource = datasource_reference(
+                            v.datasource.as_ref(),
+                            &format!("{path}.datasource"),
+                            &mut diagnostics,
+                        );
+                        query_vars.push(TemplateQueryVar {
+                            name: v.name,
+                            source: TemplateVarSource::Query {
+                                query,
+                                regex: v.regex.filter(|regex| !regex.trim().is_empty()),
+                                datasource,
+                            },
+                            query_path: format!("{path}.query"),
+                        });
                     }
                 }
-
-                if v.var_type.as_deref() == Some("query")
-                    && !v.current_is_all()
-                    && let Some(query) = v.query_string()
-                {
+                crate::app::VariableKind::Interval if v.auto == Some(true) => {
+                    let min = auto_min(v.auto_min.as_deref(), &path, &mut diagnostics);
                     query_vars.push(TemplateQueryVar {
                         name: v.name,
-                        query,
-                        regex: v.regex.filter(|regex| !regex.trim().is_empty()),
-                        query_path: format!("templating.list[{var_idx}].query"),
+                        source: TemplateVarSource::AutoInterval {
+                            count: v.auto_count.filter(|count| *count > 0).unwrap_or(30),
+                            min,
+                        },
+                        query_path: format!("{path}.auto_count"),
                     });
                 }
+                _ => {}
             }
         }
     }
@@ -302,9 +575,12 @@ pub(crate) fn load_grafana_dashboard(path: &std::path::Path) -> Result<Dashboard
         refresh_rate_ms: raw.refresh.as_ref().and_then(parse_refresh_rate_ms),
         queries: vec![],
         vars,
+        var_names,
         query_vars,
+        datasource_vars,
+        filters,
         skipped_panels: 0,
-        diagnostics: vec![],
+        diagnostics,
     };
 
     if let Some(panels) = raw.panels {
@@ -313,6 +589,38 @@ pub(crate) fn load_grafana_dashboard(path: &std::path::Path) -> Result<Dashboard
     Ok(out)
 }
 
+/// Converts the filters of an `adhoc` variable into label matchers.
+///
+/// Filters with an operator PromQL has no matcher for are skipped.
+fn adhoc_filters(
+    raw: Vec<RawAdhocFilter>,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> Vec<crate::app::LabelMatcher> {
+    let mut filters = Vec::new();
+    for (idx, filter) in raw.into_iter().enumerate() {
+        let Some(key) = filter.key.filter(|key| !key.is_empty()) else {
+            continue;
+        };
+        let operator = filter.operator.unwrap_or_else(|| "=".to_string());
+        match crate::app::MatchOp::parse(&operator) {
+            Some(op) => filters.push(crate::app::LabelMatcher::new(
+                key,
+                op,
+                filter.value.unwrap_or_default(),
+            )),
+            None => diagnostics.push(ImportDiagnostic::new(
+                "ignored_field",
+                format!("{path}.filters[{idx}].operator"),
+                format!(
+                    "unsupported ad-hoc filter operator `{operator}`; the `{key}` filter is skipped"
+                ),
+            )),
+        }
+    }
+    filters
+}
+
 impl RawVar {
     fn query_string(&self) -> Option<String> {
         let query = self
@@ -328,6 +636,71 @@ impl RawVar {
         (!query.is_empty()).then(|| query.to_string())
     }
 
+    /// Builds the variable state from `current`, `multi`, `includeAll`,
+    /// `allValue` and `options`, plus the type-specific fields of `kind`.
+    fn variable(&self, kind: crate::app::VariableKind) -> crate::app::Variable {
+        use crate::app::{AUTO_INTERVAL, VariableKind};
+
+        let current = self
+            .current
+            .as_ref()
+            .and_then(|c| c.value.as_ref().or(c.text.as_ref()));
+        let mut current: Vec<String> = current.map(json_strings).unwrap_or_default();
+        if kind == VariableKind::Interval {
+            for value in &mut current {
+                // Grafana stores the auto option as `$__auto_interval_<name>`.
+                if value.starts_with("$__auto_interval") {
+                    *value = AUTO_INTERVAL.to_string();
+                }
+            }
+        }
+        let mut var = crate::app::Variable::with_values(current);
+        if self.current_is_all() {
+            var.all = true;
+            var.current.clear();
+        }
+        var.kind = kind;
+        var.multi |= self.multi == Some(true);
+        var.include_all |= self.include_all == Some(true);
+        var.all_value = self.all_value.clone().filter(|value| !value.is_empty());
+        var.options = self
+            .options
+            .iter()
+            .flatten()
+            .filter_map(|option| option.value.as_ref())
+            .flat_map(json_strings)
+            .filter(|value| value != crate::app::ALL_VALUE && !value.starts_with("$__auto"))
+            .collect();
+
+        let query = self.query_string();
+        // The query is authoritative; `options` may be missing or outdated.
+        let options = query
+            .as_deref()
+            .map(custom_options)
+            .unwrap_or_else(|| var.options.clone());
+        match kind {
+            VariableKind::Custom => var.set_options(options),
+            VariableKind::Interval => {
+                let auto = (self.auto == Some(true)).then(|| AUTO_INTERVAL.to_string());
+                var.set_options(auto.into_iter().chain(options).collect());
+            }
+            VariableKind::Constant => {
+                if let Some(query) = query {
+                    var.current = vec![query];
+                }
+            }
+            VariableKind::Textbox => {
+                if var.current.is_empty()
+                    && let Some(query) = query
+                {
+                    var.current = vec![query];
+                }
+            }
+            _ => {}
+        }
+        var
+    }
+
     fn current_is_all(&self) -> bool {
         self.current.as_ref().is_some_and(|current| {
             value_is_all(current.value.as_ref()) || value_is_all(current.text.as_ref())
@@ -335,6 +708,64 @@ impl RawVar {
     }
 }
 
+/// Parses the options of a `custom` or `interval` variable.
+///
+/// Options are comma-separated; `\,` escapes a comma and `text : value`
+/// pairs keep only the value.
+fn custom_options(query: &str) -> Vec<String> {
+    let mut options = Vec::new();
+    let mut option = String::new();
+    let mut chars = query.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&',') => option.push(chars.next().unwrap_or(',')),
+            ',' => options.push(std::mem::take(&mut option)),
+            _ => option.push(ch),
+        }
+    }
+    options.push(option);
+
+    let mut values: Vec<String> = Vec::new();
+    for option in options {
+        let option = option.trim();
+        let value = option.split_once(" : ").map_or(option, |(_, value)| value);
+        if !value.is_empty() && !values.iter().any(|known| known == value) {
+            values.push(value.to_string());
+        }
+    }
+    values
+}
+
+/// Parses `auto_min` of an interval variable, defaulting to Grafana's 10s.
+fn auto_min(
+    value: Option<&str>,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> std::time::Duration {
+    let default = std::time::Duration::from_secs(10);
+    let Some(value) = value.map(str::trim).filter(|value| !value.is_empty()) else {
+        return default;
+    };
+    humantime::parse_duration(value).unwrap_or_else(|_| {
+        diagnostics.push(ImportDiagnostic::new(
+            "ignored_field",
+            format!("{path}.auto_min"),
+            format!("auto_min `{value}` could not be parsed; 10s is used instead"),
+        ));
+        default
+    })
+}
+
+/// Reads a Grafana variable value, which may be a string, number or array.
+fn json_strings(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(value) => vec![value.clone()],
+        serde_json::Value::Number(value) => vec![value.to_string()],
+        serde_json::Value::Array(values) => values.iter().flat_map(json_strings).collect(),
+        _ => Vec::new(),
+    }
+}
+
 fn value_is_all(value: Option<&serde_json::Value>) -> bool {
     match value {
         Some(serde_json::Value::String(value)) => {
@@ -347,6 +778,68 @@ fn value_is_all(value: Option<&serde_json::Value>) -> bool {
     }
 }
 
+/// Extracts a datasource reference from a Grafana `datasource` field.
+///
+/// Grafana stores either a name/variable string or a `{ type, uid }` object.
+/// References to non-Prometheus datasources produce an `unsupported_datasource`
+/// diagnostic and fall back to the default datasource.
+fn datasource_reference(
+    value: Option<&serde_json::Value>,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> Option<String> {
+    let reference = match value? {
+        serde_json::Value::String(name) => name.clone(),
+        serde_json::Value::Object(object) => {
+            let ds_type = object.get("type").and_then(|t| t.as_str());
+            if let Some(ds_type) = ds_type
+                && !matches!(ds_type, "prometheus" | "datasource")
+                && !ds_type.starts_with('$')
+            {
+                diagnostics.push(ImportDiagnostic::new(
+                    "unsupported_datasource",
+                    path,
+                    format!(
+                        "datasource type `{ds_type}` is not supported; the default Prometheus datasource will be used"
+                    ),
+                ));
+                return None;
+            }
+            object.get("uid").and_then(|uid| uid.as_str())?.to_string()
+        }
+        _ => return None,
+    };
+
+    let reference = reference.trim();
+    (!reference.is_empty()).then(|| reference.to_string())
+}
+
+/// Parses a Grafana minimum interval such as `30s` or `>1m`.
+///
+/// Intervals that cannot be parsed, including template variables, produce an
+/// `ignored_field` diagnostic and leave the step unconstrained.
+fn min_interval(
+    value: Option<&str>,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> Option<std::time::Duration> {
+    let value = value?.trim().trim_start_matches('>').trim();
+    if value.is_empty() {
+        return None;
+    }
+    match humantime::parse_duration(value) {
+        Ok(duration) => Some(duration),
+        Err(_) => {
+            diagnostics.push(ImportDiagnostic::new(
+                "ignored_field",
+                path,
+                format!("interval `{value}` could not be parsed; the automatic step will be used"),
+            ));
+            None
+        }
+    }
+}
+
 fn query_mode_for_target(
     instant: Option<bool>,
     panel_type: crate::app::PanelType,
@@ -377,17 +870,35 @@ fn graph_options_from_custom(custom: Option<&RawCustom>) -> crate::app::GraphOpt
         show_points: parse_graph_point_mode(custom.show_points.as_deref()),
         fill_opacity: custom.fill_opacity.map(|value| value.min(100) as u8),
         axis_placement: parse_graph_axis_placement(custom.axis_placement.as_deref()),
-        line_interpolation: custom
-            .line_interpolation
-            .as_ref()
-            .filter(|value| !value.trim().is_empty())
-            .cloned(),
+        line_interpolation: parse_graph_line_interpolation(custom.line_interpolation.as_deref()),
         stacking: parse_graph_stacking_mode(
             custom
                 .stacking
                 .as_ref()
                 .and_then(|stacking| stacking.mode.as_deref()),
         ),
+        stacking_group: stacking_group(custom.stacking.as_ref()),
+        scale: parse_graph_scale(custom.scale_distribution.as_ref()),
+        span_nulls: parse_span_nulls(custom.span_nulls.as_ref()),
+        insert_nulls: custom
+            .insert_nulls
+            .as_ref()
+            .and_then(serde_json::Value::as_f64)
+            .filter(|threshold| *threshold > 0.0)
+            .map(|threshold| threshold / 1000.0),
+        null_as_zero: false,
+        legend: crate::app::LegendOptions::default(),
+    }
+}
+
+fn parse_span_nulls(value: Option<&serde_json::Value>) -> crate::app::SpanNulls {
+    match value {
+        Some(serde_json::Value::Bool(true)) => crate::app::SpanNulls::Always,
+        Some(serde_json::Value::Number(threshold)) => match threshold.as_f64() {
+            Some(threshold) if threshold > 0.0 => crate::app::SpanNulls::Below(threshold / 1000.0),
+            _ => crate::app::SpanNulls::Never,
+        },
+        _ => crate::app::SpanNulls::Never,
     }
 }
 
@@ -407,9 +918,19 @@ fn parse_graph_point_mode(value: Option<&str>) -> crate::app::GraphPointMode {
     }
 }
 
+fn parse_graph_line_interpolation(value: Option<&str>) -> crate::app::GraphLineInterpolation {
+    match value {
+        Some("smooth") => crate::app::GraphLineInterpolation::Smooth,
+        Some("stepBefore") => crate::app::GraphLineInterpolation::StepBefore,
+        Some("stepAfter") => crate::app::GraphLineInterpolation::StepAfter,
+        _ => crate::app::GraphLineInterpolation::Linear,
+    }
+}
+
 fn parse_graph_axis_placement(value: Option<&str>) -> crate::app::GraphAxisPlacement {
     match value {
         Some("hidden") => crate::app::GraphAxisPlacement::Hidden,
+        Some("right") => crate::app::GraphAxisPlacement::Right,
         _ => crate::app::GraphAxisPlacement::Visible,
     }
 }
@@ -422,9 +943,52 @@ fn parse_graph_stacking_mode(value: Option<&str>) -> crate::app::GraphStackingMo
     }
 }
 
+fn parse_graph_scale(scale: Option<&RawScaleDistribution>) -> crate::app::GraphScale {
+    let Some(scale) = scale else {
+        return crate::app::GraphScale::Linear;
+    };
+    let base = scale.log.filter(|base| *base > 1.0).unwrap_or(2.0);
+    match scale.kind.as_deref() {
+        Some("log") => crate::app::GraphScale::Log { base },
+        Some("symlog") => crate::app::GraphScale::Symlog {
+            base,
+            linear_threshold: scale
+                .linear_threshold
+                .filter(|threshold| *threshold > 0.0)
+                .unwrap_or(1.0),
+        },
+        _ => crate::app::GraphScale::Linear,
+    }
+}
+
+fn stacking_group(stacking: Option<&RawStacking>) -> String {
+    stacking
+        .and_then(|stacking| stacking.group.as_deref())
+        .filter(|group| !group.trim().is_empty())
+        .unwrap_or(crate::app::DEFAULT_STACKING_GROUP)
+        .to_string()
+}
+
 fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str) -> Result<()> {
-    for (panel_idx, p) in panels.into_iter().enumerate() {
+    // Grid rows the panels must move down to make room for collapsed rows.
+    let mut y_shift = 0;
+    for (panel_idx, mut p) in panels.into_iter().enumerate() {
         let panel_path = format!("{path}[{panel_idx}]");
+        if p.repeat_panel_id.is_some() {
+            // Repeats are expanded again from the original panel.
+            continue;
+        }
+        if let Some(grid) = p.grid_pos.as_mut() {
+            grid.y += y_shift;
+        }
+        if p.panel_type == "row" {
+            y_shift += collect_row(out, p, &panel_path)?;
+            continue;
+        }
+        if p.panel_type == "text" {
+            collect_text(out, p, &panel_path);
+            continue;
+        }
         if let Some(children) = p.panels {
             collect_panels(out, children, &format!("{panel_path}.panels"))?;
         }
@@ -446,6 +1010,22 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
             let mut expr_paths = Vec::new();
             let mut legends = Vec::new();
             let mut query_modes = Vec::new();
+            let mut datasources = Vec::new();
+            let mut ref_ids = Vec::new();
+            let mut resolution = crate::app::QueryResolution {
+                max_data_points: p.max_data_points.filter(|points| *points > 0),
+                min_interval: min_interval(
+                    p.interval.as_deref(),
+                    &format!("{panel_path}.interval"),
+                    &mut out.diagnostics,
+                ),
+                ..Default::default()
+            };
+            let panel_datasource = datasource_reference(
+                p.datasource.as_ref(),
+                &format!("{panel_path}.datasource"),
+                &mut out.diagnostics,
+            );
 
             for (target_idx, t) in p.targets.unwrap_or_default().into_iter().enumerate() {
                 let target_path = format!("{panel_path}.targets[{target_idx}]");
@@ -453,10 +1033,29 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
                     continue;
                 }
                 if let Some(e) = t.expr {
+                    // Targets without their own datasource inherit the panel's.
+                    let target_datasource = match t.datasource.as_ref() {
+                        Some(value) if !value.is_null() => datasource_reference(
+                            Some(value),
+                            &format!("{target_path}.datasource"),
+                            &mut out.diagnostics,
+                        ),
+                        _ => panel_datasource.clone(),
+                    };
                     exprs.push(e);
                     expr_paths.push(format!("{target_path}.expr"));
                     legends.push(t.legend_format);
                     query_modes.push(query_mode_for_target(t.instant, panel_type));
+                    datasources.push(target_datasource);
+                    ref_ids.push(t.ref_id.unwrap_or_else(|| default_ref_id(target_idx)));
+                    resolution.target_intervals.push(min_interval(
+                        t.interval.as_deref(),
+                        &format!("{target_path}.interval"),
+                        &mut out.diagnostics,
+                    ));
+                    resolution
+                        .interval_factors
+                        .push(t.interval_factor.unwrap_or(1).max(1));
                 }
             }
 
@@ -465,33 +1064,55 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
             let mut max = None;
             let mut autogrid = None;
             let mut display = crate::ui::DisplayFormat::default();
+            let mut overrides = Vec::new();
             let mut graph_options = crate::app::GraphOptions::default();
 
-            if let Some(options) = p.options {
-                if options.reduce_options.is_some() {
-                    out.diagnostics.push(ImportDiagnostic::new(
-                        "ignored_field",
-                        format!("{panel_path}.options.reduceOptions"),
-                        "`options.reduceOptions` is not supported yet; Grafatui will use default value selection",
-                    ));
+            let mut raw_heatmap = RawHeatmapPanel {
+                color: p.color,
+                show_legend: p.legend.and_then(|legend| legend.show),
+                y_axis: p.y_axis,
+                calculate: p.data_format.as_deref() == Some("timeseries"),
+                path: panel_path.clone(),
+            };
+            let (raw_reduce, raw_legend) = match p.options {
+                Some(options) => {
+                    if options.color.is_some() || options.y_axis.is_some() {
+                        raw_heatmap = RawHeatmapPanel {
+                            color: options.color,
+                            show_legend: None,
+                            y_axis: options.y_axis,
+                            calculate: options.calculate == Some(true),
+                            path: format!("{panel_path}.options"),
+                        };
+                    }
+                    raw_heatmap.show_legend = options
+                        .legend
+                        .as_ref()
+                        .and_then(|legend| legend.show)
+                        .or(raw_heatmap.show_legend);
+                    (options.reduce_options, options.legend)
                 }
-            }
+                None => (None, None),
+            };
+            let reduce = reduce_options(
+                raw_reduce,
+                &format!("{panel_path}.options.reduceOptions"),
+                &mut out.diagnostics,
+            );
 
             if let Some(fc) = p.field_config {
+                let mut thresholds_style = "line".to_string();
                 if let Some(defaults) = fc.defaults {
-                    if defaults.mappings.as_ref().is_some_and(non_empty_json_value) {
-                        out.diagnostics.push(ImportDiagnostic::new(
-                            "ignored_field",
-                            format!("{panel_path}.fieldConfig.defaults.mappings"),
-                            "`fieldConfig.defaults.mappings` is not supported yet; value mappings will be ignored",
-                        ));
-                    }
-
                     graph_options = graph_options_from_custom(defaults.custom.as_ref());
                     display = crate::ui::DisplayFormat {
                         unit: defaults.unit,
                         decimals: defaults.decimals,
                         no_value: defaults.no_value,
+                        mappings: value_mappings(
+                            defaults.mappings.unwrap_or_default(),
+                            &format!("{panel_path}.fieldConfig.defaults.mappings"),
+                            &mut out.diagnostics,
+                        ),
                     };
                     min = defaults.min;
                     max = defaults.max;
@@ -499,51 +1120,46 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
                         .custom
                         .as_ref()
                         .and_then(|custom| custom.axis_grid_show);
-
-                    if let Some(th) = defaults.thresholds {
-                        let mode = match th.mode.as_deref() {
-                            Some("percentage") => crate::app::ThresholdMode::Percentage,
-                            _ => crate::app::ThresholdMode::Absolute,
-                        };
-
-                        let mut steps = Vec::new();
-                        if let Some(raw_steps) = th.steps {
-                            for s in raw_steps {
-                                let color = s.color.unwrap_or_else(|| "green".to_string());
-                                let parsed_color = crate::theme::parse_grafana_color(&color);
-                                steps.push(crate::app::ThresholdStep {
-                                    value: s.value,
-                                    color: parsed_color,
-                                });
-                            }
-                            steps.sort_by(|a, b| {
-                                let a_val = a.value.unwrap_or(f64::NEG_INFINITY);
-                                let b_val = b.value.unwrap_or(f64::NEG_INFINITY);
-                                a_val
-                                    .partial_cmp(&b_val)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            });
-                        }
-
-                        if !steps.is_empty() {
-                            let style = defaults
-                                .custom
-                                .as_ref()
-                                .and_then(|c| c.thresholds_style.as_ref())
-                                .and_then(|t| t.mode.clone())
-                                .unwrap_or_else(|| "line".to_string());
-
-                            thresholds = Some(crate::app::Thresholds {
-                                mode,
-                                steps,
-                                style: Some(style),
-                            });
-                        }
+                    if let Some(style) = defaults
+                        .custom
+                        .as_ref()
+                        .and_then(|c| c.thresholds_style.as_ref())
+                        .and_then(|t| t.mode.clone())
+                    {
+                        thresholds_style = style;
                     }
+                    thresholds = defaults
+                        .thresholds
+                        .and_then(|th| parse_thresholds(th, &thresholds_style));
+                }
+                overrides = field_overrides(
+                    fc.overrides.unwrap_or_default(),
+                    &format!("{panel_path}.fieldConfig.overrides"),
+                    &thresholds_style,
+                    &mut out.diagnostics,
+                );
+            }
+            if let Some(legend) = raw_legend {
+                graph_options.legend = legend_options(
+                    legend,
+                    &format!("{panel_path}.options.legend"),
+                    &mut out.diagnostics,
+                );
+            }
+            match p.null_point_mode.as_deref() {
+                Some("null as zero") => graph_options.null_as_zero = true,
+                Some("connected") if graph_options.span_nulls == crate::app::SpanNulls::Never => {
+                    graph_options.span_nulls = crate::app::SpanNulls::Always;
                 }
+                _ => {}
             }
 
             if !exprs.is_empty() {
+                let repeat = panel_repeat(
+                    p.repeat.as_deref(),
+                    p.repeat_direction.as_deref(),
+                    p.max_per_row,
+                );
                 let gp = p.grid_pos.map(|g| GridPos {
                     x: g.x,
                     y: g.y,
@@ -552,6 +1168,13 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
                 });
                 let options = match panel_type {
                     crate::app::PanelType::Graph => crate::app::PanelOptions::Graph(graph_options),
+                    crate::app::PanelType::Stat
+                    | crate::app::PanelType::Gauge
+                    | crate::app::PanelType::BarGauge
+                    | crate::app::PanelType::Table => crate::app::PanelOptions::Reduce(reduce),
+                    crate::app::PanelType::Heatmap => crate::app::PanelOptions::Heatmap(
+                        heatmap_options(raw_heatmap, &mut out.diagnostics),
+                    ),
                     _ => crate::app::PanelOptions::None,
                 };
                 out.queries.push(QueryPanel {
@@ -560,6 +1183,9 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
                     expr_paths,
                     legends,
                     query_modes,
+                    datasources,
+                    ref_ids,
+                    resolution,
                     grid: gp,
                     panel_type,
                     thresholds,
@@ -567,11 +1193,12 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
                     max,
                     autogrid,
                     display,
+                    overrides,
                     options,
+                    repeat,
                 });
             }
-        } else if !kind.is_empty() && kind != "row" {
-            // Count skipped panels (ignore rows)
+        } else if !kind.is_empty() {
             out.skipped_panels += 1;
             out.diagnostics.push(ImportDiagnostic::new(
                 "skipped_panel",
@@ -583,131 +1210,708 @@ fn collect_panels(out: &mut DashboardImport, panels: Vec<RawPanel>, path: &str)
     Ok(())
 }
 
-fn non_empty_json_value(value: &serde_json::Value) -> bool {
-    match value {
-        serde_json::Value::Array(values) => !values.is_empty(),
-        serde_json::Value::Object(values) => !values.is_empty(),
-        serde_json::Value::String(value) => !value.trim().is_empty(),
-        serde_json::Value::Null => false,
-        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => true,
+/// Imports a row header followed by the panels nested in it.
+///
+/// Grafana keeps the panels of a collapsed row inside the row, positioned as
+/// when the row was last expanded. They are placed right below the row, and
+/// the returned number of grid rows tells how far the panels after the row
+/// must move down to make room for them.
+fn collect_row(out: &mut DashboardImport, p: RawPanel, path: &str) -> Result<i32> {
+    let collapsed = p.collapsed.unwrap_or(false);
+    let repeat = panel_repeat(
+        p.repeat.as_deref(),
+        p.repeat_direction.as_deref(),
+        p.max_per_row,
+    );
+    let grid = p.grid_pos.map(|g| GridPos {
+        x: g.x,
+        y: g.y,
+        w: g.w,
+        h: g.h,
+    });
+    out.queries.push(QueryPanel {
+        title: p.title.unwrap_or_default(),
+        exprs: vec![],
+        expr_paths: vec![],
+        legends: vec![],
+        query_modes: vec![],
+        datasources: vec![],
+        ref_ids: vec![],
+        resolution: crate::app::QueryResolution::default(),
+        grid,
+        panel_type: crate::app::PanelType::Row,
+        thresholds: None,
+        min: None,
+        max: None,
+        autogrid: None,
+        display: crate::ui::DisplayFormat::default(),
+        overrides: vec![],
+        options: crate::app::PanelOptions::Row { collapsed },
+        repeat,
+    });
+
+    let mut children = p.panels.unwrap_or_default();
+    let mut pushed_down = 0;
+    if collapsed
+        && let Some(row) = grid
+        && let Some(first_y) = children
+            .iter()
+            .find_map(|child| child.grid_pos.as_ref().map(|g| g.y))
+    {
+        let row_bottom = row.y + row.h;
+        let offset = first_y - row_bottom;
+        let mut bottom = row_bottom;
+        for grid in children
+            .iter_mut()
+            .filter_map(|child| child.grid_pos.as_mut())
+        {
+            grid.y -= offset;
+            bottom = bottom.max(grid.y + grid.h);
+        }
+        pushed_down = bottom - row_bottom;
     }
+    collect_panels(out, children, &format!("{path}.panels"))?;
+    Ok(pushed_down)
 }
 
-pub(crate) fn variable_diagnostics(
-    dashboard: &DashboardImport,
-    vars: &HashMap<String, String>,
-) -> Vec<ImportDiagnostic> {
-    let mut known_vars: HashSet<String> = vars.keys().cloned().collect();
-    known_vars.extend(dashboard.query_vars.iter().map(|var| var.name.clone()));
+/// Imports a text panel; it has no queries.
+fn collect_text(out: &mut DashboardImport, p: RawPanel, path: &str) {
+    let (options_content, options_mode) = p
+        .options
+        .map(|options| (options.content, options.mode))
+        .unwrap_or_default();
+    let content = options_content
+        .or(p.content)
+        .and_then(|content| content.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mode = match options_mode
+        .or(p.mode)
+        .as_ref()
+        .and_then(|mode| mode.as_str())
+    {
+        None | Some("markdown") => crate::app::TextMode::Markdown,
+        Some("html") => crate::app::TextMode::Html,
+        Some("code") => crate::app::TextMode::Code,
+        Some(other) => {
+            out.diagnostics.push(ImportDiagnostic::new(
+                "ignored_field",
+                format!("{path}.options.mode"),
+                format!("text mode `{other}` is not supported; content is shown as Markdown"),
+            ));
+            crate::app::TextMode::Markdown
+        }
+    };
+    out.queries.push(QueryPanel {
+        title: p.title.unwrap_or_default(),
+        exprs: vec![],
+        expr_paths: vec![],
+        legends: vec![],
+        query_modes: vec![],
+        datasources: vec![],
+        ref_ids: vec![],
+        resolution: crate::app::QueryResolution::default(),
+        grid: p.grid_pos.map(|g| GridPos {
+            x: g.x,
+            y: g.y,
+            w: g.w,
+            h: g.h,
+        }),
+        panel_type: crate::app::PanelType::Text,
+        thresholds: None,
+        min: None,
+        max: None,
+        autogrid: None,
+        display: crate::ui::DisplayFormat::default(),
+        overrides: vec![],
+        options: crate::app::PanelOptions::Text(crate::app::TextOptions { mode, content }),
+        repeat: panel_repeat(
+            p.repeat.as_deref(),
+            p.repeat_direction.as_deref(),
+            p.max_per_row,
+        ),
+    });
+}
 
-    let mut diagnostics = Vec::new();
-    let mut seen = HashSet::new();
-    for panel in &dashboard.queries {
-        for (expr, path) in panel.exprs.iter().zip(panel.expr_paths.iter()) {
-            collect_variable_diagnostics(expr, path, &known_vars, &mut diagnostics, &mut seen);
+/// Repeat options of a panel or row, if it repeats.
+fn panel_repeat(
+    var: Option<&str>,
+    direction: Option<&str>,
+    max_per_row: Option<u32>,
+) -> Option<crate::app::PanelRepeat> {
+    let var = var.map(str::trim).filter(|var| !var.is_empty())?;
+    Some(crate::app::PanelRepeat {
+        var: var.to_string(),
+        direction: match direction {
+            Some("v") => crate::app::RepeatDirection::Vertical,
+            _ => crate::app::RepeatDirection::Horizontal,
+        },
+        max_per_row: max_per_row.filter(|max| *max > 0),
+    })
+}
+
+fn parse_thresholds(th: RawThresholds, style: &str) -> Option<crate::app::Thresholds> {
+    let mode = match th.mode.as_deref() {
+        Some("percentage") => crate::app::ThresholdMode::Percentage,
+        _ => crate::app::ThresholdMode::Absolute,
+    };
+
+    let mut steps = Vec::new();
+    if let Some(raw_steps) = th.steps {
+        for s in raw_steps {
+            let color = s.color.unwrap_or_else(|| "green".to_string());
+            let parsed_color = crate::theme::parse_grafana_color(&color);
+            steps.push(crate::app::ThresholdStep {
+                value: s.value,
+                color: parsed_color,
+            });
         }
-    }
-    for query_var in &dashboard.query_vars {
-        collect_variable_diagnostics(
-            &query_var.query,
-            &query_var.query_path,
-            &known_vars,
-            &mut diagnostics,
-            &mut seen,
-        );
+        steps.sort_by(|a, b| {
+            let a_val = a.value.unwrap_or(f64::NEG_INFINITY);
+            let b_val = b.value.unwrap_or(f64::NEG_INFINITY);
+            a_val
+                .partial_cmp(&b_val)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
     }
 
-    diagnostics
+    (!steps.is_empty()).then(|| crate::app::Thresholds {
+        mode,
+        steps,
+        style: Some(style.to_string()),
+    })
 }
 
-fn collect_variable_diagnostics(
-    expr: &str,
+/// Grafana's `refId` for a target without one: `A`, `B`, ... `Z`, `AA`, ...
+fn default_ref_id(index: usize) -> String {
+    let mut id = String::new();
+    let mut n = index + 1;
+    while n > 0 {
+        n -= 1;
+        id.insert(0, (b'A' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    id
+}
+
+/// Parses Grafana reducer ids, reporting the unsupported ones.
+fn reducers(
+    ids: Option<Vec<String>>,
     path: &str,
-    known_vars: &HashSet<String>,
     diagnostics: &mut Vec<ImportDiagnostic>,
-    seen: &mut HashSet<(String, String, String)>,
-) {
-    let chars: Vec<(usize, char)> = expr.char_indices().collect();
-    let mut idx = 0;
-    while idx < chars.len() {
-        if chars[idx].1 != '$' {
-            idx += 1;
-            continue;
+) -> Vec<crate::app::Reducer> {
+    let mut reducers = Vec::new();
+    for (index, id) in ids.unwrap_or_default().into_iter().enumerate() {
+        match crate::app::Reducer::from_id(&id) {
+            Some(reducer) => reducers.push(reducer),
+            None => diagnostics.push(ImportDiagnostic::new(
+                "ignored_field",
+                format!("{path}[{index}]"),
+                format!("unsupported reducer `{id}` ignored"),
+            )),
         }
+    }
+    reducers
+}
 
-        if idx + 1 >= chars.len() {
-            idx += 1;
-            continue;
-        }
+fn legend_options(
+    raw: RawLegendOptions,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> crate::app::LegendOptions {
+    let display_mode = match raw.display_mode.as_deref() {
+        _ if raw.show_legend == Some(false) => crate::app::LegendDisplayMode::Hidden,
+        Some("table") => crate::app::LegendDisplayMode::Table,
+        Some("hidden") => crate::app::LegendDisplayMode::Hidden,
+        _ => crate::app::LegendDisplayMode::List,
+    };
+    let placement = match raw.placement.as_deref() {
+        Some("right") => crate::app::LegendPlacement::Right,
+        _ => crate::app::LegendPlacement::Bottom,
+    };
+    let calcs = reducers(raw.calcs, &format!("{path}.calcs"), diagnostics);
+
+    crate::app::LegendOptions {
+        display_mode,
+        placement,
+        calcs,
+        sort_by: raw.sort_by.filter(|sort_by| !sort_by.is_empty()),
+        sort_desc: raw.sort_desc.unwrap_or(false),
+    }
+}
 
-        if chars[idx + 1].1 == '{' {
-            let start = chars[idx].0;
-            let inner_start = chars[idx + 1].0 + 1;
-            let mut end_idx = idx + 2;
-            while end_idx < chars.len() && chars[end_idx].1 != '}' {
-                end_idx += 1;
-            }
-            if end_idx >= chars.len() {
-                idx += 1;
-                continue;
-            }
+/// Heatmap settings of a panel, from `options` or from the panel itself
+/// for heatmaps saved before Grafana 9.0.
+struct RawHeatmapPanel {
+    color: Option<RawHeatmapColor>,
+    show_legend: Option<bool>,
+    y_axis: Option<RawHeatmapYAxis>,
+    calculate: bool,
+    /// Path of the object holding the settings.
+    path: String,
+}
 
-            let end = chars[end_idx].0;
-            let token_end = end + 1;
-            let inner = &expr[inner_start..end];
-            let token = &expr[start..token_end];
-            let (name, modifier) = inner.split_once(':').unwrap_or((inner, ""));
-            if !modifier.is_empty() {
-                push_variable_diagnostic(
-                    diagnostics,
-                    seen,
-                    ImportDiagnostic::new(
-                        "unsupported_variable_modifier",
-                        path,
-                        format!(
-                            "unsupported Grafana variable modifier `{token}`; Grafatui expands only unmodified variables"
-                        ),
-                    ),
-                );
+/// Imports heatmap colors, legend and bucket axis. Unknown color schemes
+/// fall back to Grafana's default `Oranges`.
+fn heatmap_options(
+    raw: RawHeatmapPanel,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> crate::app::HeatmapOptions {
+    let path = raw.path;
+    if raw.calculate {
+        diagnostics.push(ImportDiagnostic::new(
+            "ignored_field",
+            format!("{path}.calculate"),
+            "heatmap bucket calculation is not supported; series are shown as rows",
+        ));
+    }
+
+    let mut color = crate::app::HeatmapColor::default();
+    if let Some(raw_color) = raw.color {
+        if raw_color.mode.as_deref() == Some("opacity") {
+            let fill = raw_color.fill.or(raw_color.card_color);
+            color.mode = crate::app::HeatmapColorMode::Opacity(
+                fill.map_or(ratatui::style::Color::Rgb(255, 165, 0), |fill| {
+                    crate::theme::parse_grafana_color(&fill)
+                }),
+            );
+        } else if let Some(scheme) = raw_color.scheme.or(raw_color.color_scheme) {
+            // Heatmaps before Grafana 9.0 name the d3 interpolator.
+            let scheme = scheme.trim_start_matches("interpolate");
+            if crate::ui::is_heatmap_scheme(scheme) {
+                color.mode = crate::app::HeatmapColorMode::Scheme(scheme.to_string());
+            } else {
+                diagnostics.push(ImportDiagnostic::new(
+                    "ignored_field",
+                    format!("{path}.color.scheme"),
+                    format!("unsupported heatmap color scheme `{scheme}` ignored, using Oranges"),
+                ));
             }
-            if is_valid_variable_name(name)
-                && !is_builtin_variable(name)
-                && !known_vars.contains(name)
-            {
-                push_variable_diagnostic(
-                    diagnostics,
-                    seen,
-                    ImportDiagnostic::new(
-                        "unresolved_variable",
-                        path,
-                        format!(
-                            "unresolved variable `{token}`; provide it with --var or dashboard templating"
-                        ),
-                    ),
-                );
+        }
+        color.exponent = match raw_color.scale.or(raw_color.color_scale).as_deref() {
+            Some("linear") => None,
+            _ => Some(raw_color.exponent.unwrap_or(0.5)),
+        };
+        color.reverse = raw_color.reverse.unwrap_or(false);
+        color.min = raw_color.min;
+        color.max = raw_color.max;
+    }
+
+    let y_axis = raw
+        .y_axis
+        .map_or_else(crate::ui::DisplayFormat::default, |y_axis| {
+            crate::ui::DisplayFormat {
+                // `short` is the default, which leaves bounds as written.
+                unit: y_axis.unit.or(y_axis.format).filter(|unit| unit != "short"),
+                decimals: y_axis.decimals,
+                ..Default::default()
             }
-            idx = end_idx + 1;
-            continue;
+        });
+
+    crate::app::HeatmapOptions {
+        color,
+        show_legend: raw.show_legend.unwrap_or(true),
+        y_axis,
+    }
+}
+
+/// Imports `options.reduceOptions`. Without calcs, panels use Grafana's
+/// default `lastNotNull`.
+fn reduce_options(
+    raw: Option<RawReduceOptions>,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> crate::app::ReduceOptions {
+    let raw = raw.unwrap_or_default();
+    let mut calcs = reducers(raw.calcs, &format!("{path}.calcs"), diagnostics);
+    if calcs.is_empty() {
+        calcs.push(crate::app::Reducer::LastNotNull);
+    }
+
+    let fields = match raw.fields.as_deref() {
+        None | Some("" | "Value") => None,
+        Some(fields) if fields.starts_with('/') => match crate::ui::GrafanaRegex::new(fields) {
+            Ok(regex) => Some(crate::app::FieldSelector::Regex(regex)),
+            Err(err) => {
+                diagnostics.push(ImportDiagnostic::new(
+                    "ignored_field",
+                    format!("{path}.fields"),
+                    format!("invalid fields regex ignored, using all series: {err}"),
+                ));
+                None
+            }
+        },
+        Some(name) => Some(crate::app::FieldSelector::Name(name.to_string())),
+    };
+
+    crate::app::ReduceOptions {
+        calcs,
+        values: raw.values.unwrap_or(false),
+        limit: raw.limit,
+        fields,
+    }
+}
+
+fn field_overrides(
+    raw: Vec<RawFieldOverride>,
+    path: &str,
+    thresholds_style: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> Vec<crate::app::FieldOverride> {
+    let mut overrides = Vec::new();
+    for (index, raw) in raw.into_iter().enumerate() {
+        let override_path = format!("{path}[{index}]");
+        let matcher = match field_matcher(raw.matcher) {
+            Ok(matcher) => matcher,
+            Err(reason) => {
+                diagnostics.push(ImportDiagnostic::new(
+                    "ignored_field",
+                    format!("{override_path}.matcher"),
+                    format!("field override ignored: {reason}"),
+                ));
+                continue;
+            }
+        };
+        let mut properties = Vec::new();
+        for (property_idx, property) in raw.properties.unwrap_or_default().into_iter().enumerate() {
+            let property_path = format!("{override_path}.properties[{property_idx}]");
+            match override_property(property, &property_path, thresholds_style, diagnostics) {
+                Ok(property) => properties.push(property),
+                Err(reason) => diagnostics.push(ImportDiagnostic::new(
+                    "ignored_field",
+                    property_path,
+                    format!("override property ignored: {reason}"),
+                )),
+            }
+        }
+        if !properties.is_empty() {
+            overrides.push(crate::app::FieldOverride {
+                matcher,
+                properties,
+            });
         }
+    }
+    overrides
+}
 
-        let name_start = chars[idx + 1].0;
-        let mut end_idx = idx + 1;
-        while end_idx < chars.len() && is_variable_name_char(chars[end_idx].1) {
-            end_idx += 1;
+fn field_matcher(raw: RawFieldMatcher) -> std::result::Result<crate::app::FieldMatcher, String> {
+    use crate::app::FieldMatcher;
+
+    let text = || {
+        raw.options
+            .as_ref()
+            .and_then(|options| options.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| format!("`{}` matcher needs a text option", raw.id))
+    };
+    match raw.id.as_str() {
+        "byName" => Ok(FieldMatcher::Name(text()?)),
+        "byNames" => {
+            let names: RawNamesMatcher =
+                serde_json::from_value(raw.options.clone().unwrap_or_default())
+                    .map_err(|err| format!("invalid `byNames` options: {err}"))?;
+            Ok(FieldMatcher::Names {
+                names: names.names,
+                exclude: names.mode.as_deref() == Some("exclude"),
+            })
         }
-        if end_idx == idx + 1 {
-            idx += 1;
-            continue;
+        "byRegexp" => {
+            let pattern = text()?;
+            crate::ui::GrafanaRegex::new(&pattern)
+                .map(FieldMatcher::Regex)
+                .map_err(|err| format!("invalid pattern `{pattern}`: {err}"))
+        }
+        "byFrameRefID" => Ok(FieldMatcher::RefId(text()?)),
+        "byType" => Ok(FieldMatcher::Type(text()?)),
+        other => Err(format!("matcher `{other}` is not supported")),
+    }
+}
+
+fn override_property(
+    raw: RawOverrideProperty,
+    path: &str,
+    thresholds_style: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> std::result::Result<crate::app::OverrideProperty, String> {
+    use crate::app::OverrideProperty;
+
+    fn parse<T: serde::de::DeserializeOwned>(
+        value: serde_json::Value,
+        id: &str,
+    ) -> std::result::Result<T, String> {
+        serde_json::from_value(value).map_err(|err| format!("invalid `{id}` value: {err}"))
+    }
+
+    let id = raw.id.as_str();
+    let value = raw.value.unwrap_or_default();
+    match id {
+        "displayName" => parse(value, id).map(OverrideProperty::DisplayName),
+        "unit" => parse(value, id).map(OverrideProperty::Unit),
+        "decimals" => parse(value, id).map(OverrideProperty::Decimals),
+        "noValue" => parse(value, id).map(OverrideProperty::NoValue),
+        "mappings" => Ok(OverrideProperty::Mappings(value_mappings(
+            parse(value, id)?,
+            &format!("{path}.value"),
+            diagnostics,
+        ))),
+        "color" => {
+            let color: RawFieldColor = parse(value, id)?;
+            match (color.mode.as_deref(), color.fixed_color) {
+                (Some("fixed"), Some(fixed)) => Ok(OverrideProperty::Color(
+                    crate::theme::parse_grafana_color(&fixed),
+                )),
+                (mode, _) => Err(format!(
+                    "color mode `{}` is not supported",
+                    mode.unwrap_or_default()
+                )),
+            }
+        }
+        "custom.axisPlacement" => {
+            let placement: String = parse(value, id)?;
+            Ok(OverrideProperty::AxisPlacement(parse_graph_axis_placement(
+                Some(&placement),
+            )))
+        }
+        "custom.stacking" => {
+            let stacking: RawStacking = parse(value, id)?;
+            Ok(OverrideProperty::Stacking {
+                mode: parse_graph_stacking_mode(stacking.mode.as_deref()),
+                group: stacking_group(Some(&stacking)),
+            })
+        }
+        "custom.hideFrom" => {
+            let hide: RawHideFrom = parse(value, id)?;
+            Ok(OverrideProperty::HideFrom {
+                legend: hide.legend.unwrap_or(false),
+                viz: hide.viz.unwrap_or(false),
+            })
+        }
+        "thresholds" => parse_thresholds(parse(value, id)?, thresholds_style)
+            .map(OverrideProperty::Thresholds)
+            .ok_or_else(|| "thresholds without steps".to_string()),
+        other => Err(format!("`{other}` is not supported")),
+    }
+}
+
+fn value_mappings(
+    raw: Vec<RawValueMapping>,
+    path: &str,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+) -> Vec<crate::ui::ValueMapping> {
+    let mut mappings = Vec::new();
+    for (index, mapping) in raw.into_iter().enumerate() {
+        if let Err(reason) = push_value_mapping(&mut mappings, mapping) {
+            diagnostics.push(ImportDiagnostic::new(
+                "ignored_field",
+                format!("{path}[{index}]"),
+                format!("value mapping ignored: {reason}"),
+            ));
         }
+    }
+    mappings
+}
+
+fn push_value_mapping(
+    out: &mut Vec<crate::ui::ValueMapping>,
+    raw: RawValueMapping,
+) -> std::result::Result<(), String> {
+    use crate::ui::{GrafanaRegex, SpecialMatch, ValueMapping};
+
+    fn options<T: serde::de::DeserializeOwned>(
+        options: Option<serde_json::Value>,
+    ) -> std::result::Result<T, String> {
+        serde_json::from_value(options.unwrap_or_default())
+            .map_err(|err| format!("invalid options: {err}"))
+    }
+
+    let kind = match &raw.kind {
+        Some(serde_json::Value::String(kind)) => kind.clone(),
+        Some(serde_json::Value::Number(kind)) => kind.to_string(),
+        _ => String::new(),
+    };
+    match kind.as_str() {
+        "value" => {
+            let values: std::collections::BTreeMap<String, RawMappingResult> =
+                options(raw.options)?;
+            out.extend(
+                values
+                    .into_iter()
+                    .map(|(value, result)| ValueMapping::Value {
+                        value,
+                        result: mapping_result(result),
+                    }),
+            );
+        }
+        "range" => {
+            let range: RawRangeMapping = options(raw.options)?;
+            out.push(ValueMapping::Range {
+                from: range.from,
+                to: range.to,
+                result: mapping_result(range.result),
+            });
+        }
+        "regex" => {
+            let regex: RawRegexMapping = options(raw.options)?;
+            let pattern = GrafanaRegex::new(&regex.pattern)
+                .map_err(|err| format!("invalid pattern `{}`: {err}", regex.pattern))?;
+            out.push(ValueMapping::Regex {
+                pattern,
+                result: mapping_result(regex.result),
+            });
+        }
+        "special" => {
+            let special: RawSpecialMapping = options(raw.options)?;
+            let matcher = match special.matcher.as_str() {
+                "null" => SpecialMatch::Null,
+                "nan" => SpecialMatch::NaN,
+                "null+nan" => SpecialMatch::NullAndNaN,
+                "true" => SpecialMatch::True,
+                "false" => SpecialMatch::False,
+                other => return Err(format!("special value `{other}` is not supported")),
+            };
+            out.push(ValueMapping::Special {
+                matcher,
+                result: mapping_result(special.result),
+            });
+        }
+        "1" => {
+            let result = mapping_result(RawMappingResult {
+                text: raw.text,
+                color: None,
+            });
+            let value = raw.value.as_ref().map(json_scalar).unwrap_or_default();
+            out.push(if value == "null" {
+                ValueMapping::Special {
+                    matcher: SpecialMatch::Null,
+                    result,
+                }
+            } else {
+                ValueMapping::Value { value, result }
+            });
+        }
+        "2" => {
+            let bound = |value: Option<&serde_json::Value>| {
+                value
+                    .map(json_scalar)
+                    .and_then(|value| value.trim().parse().ok())
+            };
+            out.push(ValueMapping::Range {
+                from: bound(raw.from.as_ref()),
+                to: bound(raw.to.as_ref()),
+                result: mapping_result(RawMappingResult {
+                    text: raw.text,
+                    color: None,
+                }),
+            });
+        }
+        other => return Err(format!("mapping type `{other}` is not supported")),
+    }
+    Ok(())
+}
+
+fn mapping_result(raw: RawMappingResult) -> crate::ui::MappingResult {
+    crate::ui::MappingResult {
+        text: raw.text.filter(|text| !text.is_empty()),
+        color: raw
+            .color
+            .map(|color| crate::theme::parse_grafana_color(&color))
+            .filter(|color| *color != ratatui::style::Color::Reset),
+    }
+}
+
+/// Text of a JSON string or number, as legacy mappings store either.
+fn json_scalar(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub(crate) fn variable_diagnostics(
+    dashboard: &DashboardImport,
+    vars: &HashMap<String, crate::app::Variable>,
+) -> Vec<ImportDiagnostic> {
+    let mut known_vars: HashSet<String> = vars.keys().cloned().collect();
+    known_vars.extend(dashboard.query_vars.iter().map(|var| var.name.clone()));
+    let multi_vars: HashSet<String> = vars
+        .iter()
+        .filter(|(_, var)| var.multi || var.include_all)
+        .map(|(name, _)| name.clone())
+        .collect();
+    let vars = VariableNames {
+        known: &known_vars,
+        multi: &multi_vars,
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut seen = HashSet::new();
+    for panel in &dashboard.queries {
+        for (expr, path) in panel.exprs.iter().zip(panel.expr_paths.iter()) {
+            collect_variable_diagnostics(expr, path, &vars, &mut diagnostics, &mut seen);
+        }
+    }
+    for query_var in &dashboard.query_vars {
+        if let TemplateVarSource::Query { query, .. } = &query_var.source {
+            collect_variable_diagnostics(
+                query,
+                &query_var.query_path,
+                &vars,
+                &mut diagnostics,
+                &mut seen,
+            );
+        }
+    }
+
+    diagnostics
+}
+
+/// Variable names known to the import, used by variable diagnostics.
+struct VariableNames<'a> {
+    known: &'a HashSet<String>,
+    /// Variables that can hold several values (`multi` or `includeAll`).
+    multi: &'a HashSet<String>,
+}
+
+fn collect_variable_diagnostics(
+    expr: &str,
+    path: &str,
+    vars: &VariableNames<'_>,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+    seen: &mut HashSet<(String, String, String)>,
+) {
+    for token in crate::app::tokenize(expr) {
+        let crate::app::Token::Variable(reference) = token else {
+            continue;
+        };
+        let token = reference.raw;
+        let name = reference.name;
 
-        let name_end = chars
-            .get(end_idx)
-            .map(|(byte_idx, _)| *byte_idx)
-            .unwrap_or(expr.len());
-        let name = &expr[name_start..name_end];
-        if is_valid_variable_name(name) && !is_builtin_variable(name) && !known_vars.contains(name)
+        if let Some(format) = reference.format
+            && crate::app::VariableFormat::parse(format).is_none()
+        {
+            push_variable_diagnostic(
+                diagnostics,
+                seen,
+                ImportDiagnostic::new(
+                    "unsupported_variable_modifier",
+                    path,
+                    format!(
+                        "unsupported Grafana variable format `{token}`; the variable is expanded with the default formatting"
+                    ),
+                ),
+            );
+        }
+        check_multi_value_matcher(
+            expr,
+            reference.start,
+            token,
+            name,
+            path,
+            vars,
+            diagnostics,
+            seen,
+        );
+        if is_valid_variable_name(name) && !is_builtin_variable(name) && !vars.known.contains(name)
         {
-            let token = &expr[chars[idx].0..name_end];
             push_variable_diagnostic(
                 diagnostics,
                 seen,
@@ -720,7 +1924,40 @@ fn collect_variable_diagnostics(
                 ),
             );
         }
-        idx = end_idx;
+    }
+}
+
+/// Warns when a multi-value variable is compared with `=` or `!=`.
+///
+/// Several values interpolate as a regex alternation, which only matches with
+/// `=~` or `!~`.
+#[allow(clippy::too_many_arguments)]
+fn check_multi_value_matcher(
+    expr: &str,
+    start: usize,
+    token: &str,
+    name: &str,
+    path: &str,
+    vars: &VariableNames<'_>,
+    diagnostics: &mut Vec<ImportDiagnostic>,
+    seen: &mut HashSet<(String, String, String)>,
+) {
+    let is_equality = expr[..start]
+        .strip_suffix('"')
+        .map(str::trim_end)
+        .is_some_and(|before| before.ends_with('='));
+    if is_equality && vars.multi.contains(name) {
+        push_variable_diagnostic(
+            diagnostics,
+            seen,
+            ImportDiagnostic::new(
+                "multi_value_equality_matcher",
+                path,
+                format!(
+                    "multi-value variable `{token}` is used with an equality matcher; use `=~` so every selected value matches"
+                ),
+            ),
+        );
     }
 }
 
@@ -752,16 +1989,9 @@ fn is_builtin_variable(name: &str) -> bool {
     )
 }
 
+/// Whether `name` can be a variable rather than e.g. a `$1` capture group.
 fn is_valid_variable_name(name: &str) -> bool {
-    let mut chars = name.chars();
-    chars
-        .next()
-        .is_some_and(|ch| ch == '_' || ch.is_ascii_alphabetic())
-        && chars.all(is_variable_name_char)
-}
-
-fn is_variable_name_char(ch: char) -> bool {
-    ch == '_' || ch.is_ascii_alphanumeric()
+    name.starts_with(|ch: char| ch == '_' || ch.is_ascii_alphabetic())
 }
 
 fn parse_refresh_rate_ms(value: &serde_json::Value) -> Option<u64> {
@@ -817,6 +2047,174 @@ mod tests {
         assert_eq!(val.unwrap().as_str(), Some("node-exporter"));
     }
 
+    #[test]
+    fn test_import_custom_constant_interval_and_textbox_variables() {
+        let json = r#"{
+            "title": "Variable types",
+            "templating": {
+                "list": [
+                    {
+                        "name": "env",
+                        "type": "custom",
+                        "query": "Production : prod, staging, a\\,b",
+                        "current": { "text": "staging", "value": "staging" }
+                    },
+                    { "name": "region", "type": "custom", "query": "eu,us" },
+                    { "name": "prefix", "type": "constant", "query": "node_" },
+                    {
+                        "name": "interval",
+                        "type": "interval",
+                        "query": "1m,10m,1h",
+                        "auto": true,
+                        "auto_count": 10,
+                        "auto_min": "2m",
+                        "current": { "text": "auto", "value": "$__auto_interval_interval" }
+                    },
+                    { "name": "filter", "type": "textbox", "query": "cpu" },
+                    { "name": "group", "type": "groupby" }
+                ]
+            }
+        }"#;
+        let path = std::env::temp_dir().join("grafatui-variable-types-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let env = &dashboard.vars["env"];
+        assert_eq!(env.kind, crate::app::VariableKind::Custom);
+        assert_eq!(env.options, vec!["prod", "staging", "a,b"]);
+        assert_eq!(env.current, vec!["staging"]);
+        assert_eq!(dashboard.vars["region"].current, vec!["eu"]);
+        assert_eq!(dashboard.vars["prefix"].current, vec!["node_"]);
+        assert_eq!(dashboard.vars["filter"].current, vec!["cpu"]);
+
+        let interval = &dashboard.vars["interval"];
+        assert_eq!(interval.options, vec!["auto", "1m", "10m", "1h"]);
+        assert_eq!(interval.current, vec!["auto"]);
+        assert_eq!(
+            dashboard.query_vars,
+            vec![TemplateQueryVar {
+                name: "interval".to_string(),
+                source: TemplateVarSource::AutoInterval {
+                    count: 10,
+                    min: std::time::Duration::from_secs(120),
+                },
+                query_path: "templating.list[3].auto_count".to_string(),
+            }]
+        );
+
+        assert_eq!(dashboard.diagnostics.len(), 1);
+        assert_eq!(dashboard.diagnostics[0].code, "unsupported_variable_type");
+        assert_eq!(dashboard.diagnostics[0].path, "templating.list[5].type");
+    }
+
+    #[test]
+    fn test_import_adhoc_filters() {
+        let json = r#"
+        {
+            "title": "Adhoc",
+            "templating": {
+                "list": [
+                    {
+                        "name": "filters",
+                        "type": "adhoc",
+                        "filters": [
+                            { "key": "cluster", "operator": "=", "value": "eu-1" },
+                            { "key": "env", "operator": "!~", "value": "dev.*" },
+                            { "key": "latency", "operator": ">", "value": "5" }
+                        ]
+                    },
+                    { "name": "job", "type": "custom", "query": "node" }
+                ]
+            }
+        }"#;
+        let path = std::env::temp_dir().join("grafatui-adhoc-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            dashboard.filters,
+            vec![
+                crate::app::LabelMatcher::new("cluster", crate::app::MatchOp::Equal, "eu-1"),
+                crate::app::LabelMatcher::new("env", crate::app::MatchOp::NotRegex, "dev.*"),
+            ]
+        );
+        assert_eq!(dashboard.var_names, vec!["job"]);
+        assert_eq!(dashboard.diagnostics.len(), 1);
+        assert_eq!(dashboard.diagnostics[0].code, "ignored_field");
+        assert_eq!(
+            dashboard.diagnostics[0].path,
+            "templating.list[0].filters[2].operator"
+        );
+    }
+
+    #[test]
+    fn test_import_multi_value_and_all_variables() {
+        let json = r#"
+        {
+            "title": "Multi Vars",
+            "templating": {
+                "list": [
+                    {
+                        "name": "instance",
+                        "type": "custom",
+                        "multi": true,
+                        "current": { "text": ["a", "b"], "value": ["a", "b"] },
+                        "options": [
+                            { "text": "a", "value": "a" },
+                            { "text": "b", "value": "b" },
+                            { "text": "c", "value": "c" }
+                        ]
+                    },
+                    {
+                        "name": "job",
+                        "type": "custom",
+                        "includeAll": true,
+                        "current": { "text": "All", "value": ["$__all"] },
+                        "options": [
+                            { "text": "All", "value": "$__all" },
+                            { "text": "node", "value": "node" },
+                            { "text": "api", "value": "api" }
+                        ]
+                    }
+                ]
+            },
+            "panels": [
+                {
+                    "type": "timeseries",
+                    "title": "Up",
+                    "targets": [
+                        { "expr": "up{instance=~\"$instance\", job=\"$job\"}" }
+                    ]
+                }
+            ]
+        }
+        "#;
+        let path = std::env::temp_dir().join("grafatui-multi-vars-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let instance = &dashboard.vars["instance"];
+        assert!(instance.multi);
+        assert_eq!(instance.current, vec!["a", "b"]);
+        assert_eq!(instance.options, vec!["a", "b", "c"]);
+        assert_eq!(instance.interpolate().as_deref(), Some("(a|b)"));
+
+        let job = &dashboard.vars["job"];
+        assert!(job.all);
+        assert_eq!(job.interpolate().as_deref(), Some("(node|api)"));
+
+        let diagnostics = variable_diagnostics(&dashboard, &dashboard.vars);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "multi_value_equality_matcher");
+        assert!(diagnostics[0].message.contains("$job"));
+    }
+
     #[test]
     fn test_parse_axis_grid_show() {
         let json = r#"
@@ -970,6 +2368,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_query_resolution_options() {
+        let json = r#"
+        {
+            "title": "Resolution Test",
+            "panels": [
+                {
+                    "type": "timeseries",
+                    "title": "CPU",
+                    "maxDataPoints": 500,
+                    "interval": ">30s",
+                    "targets": [
+                        { "expr": "up", "interval": "1m", "intervalFactor": 2 },
+                        { "expr": "up", "interval": "$min_step" }
+                    ]
+                }
+            ]
+        }
+        "#;
+        let path = std::env::temp_dir().join("grafatui-query-resolution-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let resolution = &dashboard.queries[0].resolution;
+        assert_eq!(resolution.max_data_points, Some(500));
+        assert_eq!(
+            resolution.min_interval,
+            Some(std::time::Duration::from_secs(30))
+        );
+        assert_eq!(
+            resolution.target_intervals,
+            vec![Some(std::time::Duration::from_secs(60)), None]
+        );
+        assert_eq!(resolution.interval_factors, vec![2, 1]);
+        assert!(dashboard.diagnostics.iter().any(|diagnostic| {
+            diagnostic.code == "ignored_field" && diagnostic.path == "panels[0].targets[1].interval"
+        }));
+    }
+
     #[test]
     fn test_parse_query_variables() {
         let json = r#"
@@ -1008,18 +2447,101 @@ mod tests {
         let dashboard = load_grafana_dashboard(&path).unwrap();
         std::fs::remove_file(path).unwrap();
 
-        assert_eq!(dashboard.query_vars.len(), 2);
-        assert_eq!(dashboard.query_vars[0].query, "label_values(up, instance)");
-        assert_eq!(dashboard.query_vars[0].regex.as_deref(), Some("/(.+)/"));
-        assert_eq!(dashboard.query_vars[1].query, "label_values(model_name)");
-        assert_eq!(dashboard.vars.get("all_instance"), Some(&".*".to_string()));
+        assert_eq!(dashboard.query_vars.len(), 3);
+        assert_eq!(
+            dashboard.query_vars[0].source,
+            TemplateVarSource::Query {
+                query: "label_values(up, instance)".to_string(),
+                regex: Some("/(.+)/".to_string()),
+                datasource: None,
+            }
+        );
+        assert!(matches!(
+            &dashboard.query_vars[1].source,
+            TemplateVarSource::Query { query, .. } if query == "label_values(model_name)"
+        ));
+        assert_eq!(
+            dashboard.vars["all_instance"].interpolate().as_deref(),
+            Some(".*")
+        );
     }
 
     #[test]
-    fn test_parse_dashboard_refresh_duration() {
+    fn test_import_datasource_references() {
         let json = r#"
         {
-            "title": "Refresh Dash",
+            "title": "Datasources",
+            "templating": {
+                "list": [
+                    {
+                        "name": "datasource",
+                        "type": "datasource",
+                        "query": "prometheus",
+                        "current": { "text": "prod", "value": "P1" }
+                    },
+                    {
+                        "name": "instance",
+                        "type": "query",
+                        "datasource": { "type": "prometheus", "uid": "${datasource}" },
+                        "query": "label_values(up, instance)",
+                        "current": { "text": "node-1", "value": "node-1" }
+                    }
+                ]
+            },
+            "panels": [
+                {
+                    "type": "timeseries",
+                    "title": "Mixed",
+                    "datasource": { "type": "datasource", "uid": "-- Mixed --" },
+                    "targets": [
+                        { "expr": "up", "datasource": { "type": "prometheus", "uid": "S1" } },
+                        { "expr": "up", "datasource": "${DS_PROMETHEUS}" },
+                        { "expr": "up", "datasource": { "type": "loki", "uid": "L1" } }
+                    ]
+                },
+                {
+                    "type": "stat",
+                    "title": "Panel level",
+                    "datasource": { "type": "prometheus", "uid": "P1" },
+                    "targets": [{ "expr": "up" }]
+                }
+            ]
+        }
+        "#;
+        let path = std::env::temp_dir().join("grafatui-datasources-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(dashboard.var_names, vec!["datasource", "instance"]);
+        assert_eq!(dashboard.datasource_vars, vec!["datasource"]);
+        assert!(matches!(
+            &dashboard.query_vars[0].source,
+            TemplateVarSource::Query { datasource: Some(datasource), .. } if datasource == "${datasource}"
+        ));
+        assert_eq!(
+            dashboard.queries[0].datasources,
+            vec![
+                Some("S1".to_string()),
+                Some("${DS_PROMETHEUS}".to_string()),
+                None
+            ]
+        );
+        assert_eq!(
+            dashboard.queries[1].datasources,
+            vec![Some("P1".to_string())]
+        );
+        assert!(dashboard.diagnostics.iter().any(|d| {
+            d.code == "unsupported_datasource" && d.path == "panels[0].targets[2].datasource"
+        }));
+    }
+
+    #[test]
+    fn test_parse_dashboard_refresh_duration() {
+        let json = r#"
+        {
+            "title": "Refresh Dash",
             "refresh": "5s",
             "panels": [
                 {
@@ -1055,7 +2577,10 @@ mod tests {
                             "fillOpacity": 20,
                             "axisPlacement": "hidden",
                             "lineInterpolation": "smooth",
-                            "stacking": { "mode": "normal" }
+                            "stacking": { "mode": "normal", "group": "B" },
+                            "scaleDistribution": { "type": "log", "log": 10 },
+                            "spanNulls": 60000,
+                            "insertNulls": 30000
                         }
                     }
                 }
@@ -1068,7 +2593,10 @@ mod tests {
             refresh_rate_ms: None,
             queries: vec![],
             vars: HashMap::new(),
+            var_names: vec![],
             query_vars: vec![],
+            datasource_vars: vec![],
+            filters: vec![],
             skipped_panels: 0,
             diagnostics: vec![],
         };
@@ -1086,8 +2614,16 @@ mod tests {
             options.axis_placement,
             crate::app::GraphAxisPlacement::Hidden
         );
-        assert_eq!(options.line_interpolation.as_deref(), Some("smooth"));
+        assert_eq!(
+            options.line_interpolation,
+            crate::app::GraphLineInterpolation::Smooth
+        );
         assert_eq!(options.stacking, crate::app::GraphStackingMode::Normal);
+        assert_eq!(options.stacking_group, "B");
+        assert_eq!(options.scale, crate::app::GraphScale::Log { base: 10.0 });
+        assert_eq!(options.span_nulls, crate::app::SpanNulls::Below(60.0));
+        assert_eq!(options.insert_nulls, Some(30.0));
+        assert!(!options.null_as_zero);
     }
 
     #[test]
@@ -1099,14 +2635,18 @@ mod tests {
                     "type": "timeseries",
                     "title": "Unknown values",
                     "targets": [{ "expr": "up" }],
+                    "nullPointMode": "null as zero",
                     "fieldConfig": {
                         "defaults": {
                             "custom": {
                                 "drawStyle": "candles",
                                 "showPoints": "sometimes",
                                 "fillOpacity": 999,
-                                "axisPlacement": "right",
-                                "stacking": { "mode": "percent" }
+                                "axisPlacement": "left",
+                                "stacking": { "mode": "percent" },
+                                "scaleDistribution": { "type": "symlog", "linearThreshold": -1 },
+                                "spanNulls": -1,
+                                "insertNulls": false
                             }
                         }
                     }
@@ -1125,7 +2665,10 @@ mod tests {
             refresh_rate_ms: None,
             queries: vec![],
             vars: HashMap::new(),
+            var_names: vec![],
             query_vars: vec![],
+            datasource_vars: vec![],
+            filters: vec![],
             skipped_panels: 0,
             diagnostics: vec![],
         };
@@ -1147,7 +2690,24 @@ mod tests {
             graph_options.stacking,
             crate::app::GraphStackingMode::Percent
         );
-        assert_eq!(out.queries[1].options, crate::app::PanelOptions::None);
+        assert_eq!(graph_options.stacking_group, "A");
+        assert_eq!(
+            graph_options.scale,
+            crate::app::GraphScale::Symlog {
+                base: 2.0,
+                linear_threshold: 1.0,
+            }
+        );
+        assert_eq!(graph_options.span_nulls, crate::app::SpanNulls::Never);
+        assert_eq!(graph_options.insert_nulls, None);
+        assert!(graph_options.null_as_zero);
+        assert_eq!(
+            out.queries[1].options,
+            crate::app::PanelOptions::Reduce(crate::app::ReduceOptions {
+                calcs: vec![crate::app::Reducer::LastNotNull],
+                ..Default::default()
+            })
+        );
     }
 
     #[test]
@@ -1155,7 +2715,7 @@ mod tests {
         let json = r#"{
             "title": "Skipped",
             "panels": [
-                { "type": "text", "title": "Notes" }
+                { "type": "dashlist", "title": "Notes" }
             ]
         }"#;
         let path = std::env::temp_dir().join("grafatui-skipped-panel-diagnostics.json");
@@ -1171,7 +2731,7 @@ mod tests {
         assert!(
             dashboard.diagnostics[0]
                 .message
-                .contains("unsupported panel type `text`")
+                .contains("unsupported panel type `dashlist`")
         );
         assert!(dashboard.diagnostics[0].message.contains("Notes"));
     }
@@ -1187,16 +2747,9 @@ mod tests {
                     "targets": [
                         { "expr": "up" }
                     ],
-                    "fieldConfig": {
-                        "defaults": {
-                            "mappings": [
-                                { "type": "value", "options": { "0": { "text": "Down" } } }
-                            ]
-                        }
-                    },
                     "options": {
                         "reduceOptions": {
-                            "calcs": ["mean"]
+                            "calcs": ["mean", "allValues"]
                         }
                     }
                 }
@@ -1215,9 +2768,8 @@ mod tests {
             .collect();
 
         assert!(
-            diagnostics.contains(&("ignored_field", "panels[0].fieldConfig.defaults.mappings"))
+            diagnostics.contains(&("ignored_field", "panels[0].options.reduceOptions.calcs[1]"))
         );
-        assert!(diagnostics.contains(&("ignored_field", "panels[0].options.reduceOptions")));
     }
 
     #[test]
@@ -1279,6 +2831,627 @@ mod tests {
         assert_eq!(dashboard.diagnostics[0].path, "panels[0].panels[0]");
     }
 
+    #[test]
+    fn test_import_rows_places_collapsed_panels_below_their_row() {
+        let json = r#"{
+            "title": "Rows",
+            "panels": [
+                {
+                    "type": "row",
+                    "title": "Expanded",
+                    "collapsed": false,
+                    "gridPos": { "x": 0, "y": 0, "w": 24, "h": 1 },
+                    "panels": []
+                },
+                {
+                    "type": "timeseries",
+                    "title": "CPU",
+                    "gridPos": { "x": 0, "y": 1, "w": 12, "h": 8 },
+                    "targets": [{ "expr": "up" }]
+                },
+                {
+                    "type": "row",
+                    "title": "Collapsed",
+                    "collapsed": true,
+                    "gridPos": { "x": 0, "y": 9, "w": 24, "h": 1 },
+                    "panels": [
+                        {
+                            "type": "stat",
+                            "title": "Memory",
+                            "gridPos": { "x": 0, "y": 30, "w": 12, "h": 6 },
+                            "targets": [{ "expr": "node_memory_MemFree_bytes" }]
+                        }
+                    ]
+                },
+                {
+                    "type": "row",
+                    "title": "Last",
+                    "collapsed": false,
+                    "gridPos": { "x": 0, "y": 10, "w": 24, "h": 1 }
+                }
+            ]
+        }"#;
+        let path = std::env::temp_dir().join("grafatui-rows-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let layout: Vec<(&str, crate::app::PanelType, i32)> = dashboard
+            .queries
+            .iter()
+            .map(|q| (q.title.as_str(), q.panel_type, q.grid.unwrap().y))
+            .collect();
+        assert_eq!(
+            layout,
+            vec![
+                ("Expanded", crate::app::PanelType::Row, 0),
+                ("CPU", crate::app::PanelType::Graph, 1),
+                ("Collapsed", crate::app::PanelType::Row, 9),
+                ("Memory", crate::app::PanelType::Stat, 10),
+                ("Last", crate::app::PanelType::Row, 16),
+            ]
+        );
+        assert_eq!(
+            dashboard.queries[2].options,
+            crate::app::PanelOptions::Row { collapsed: true }
+        );
+        assert_eq!(
+            dashboard.queries[0].options,
+            crate::app::PanelOptions::Row { collapsed: false }
+        );
+        assert_eq!(dashboard.skipped_panels, 0);
+    }
+
+    #[test]
+    fn test_import_repeat_options() {
+        let json = r#"{
+            "title": "Repeats",
+            "panels": [
+                {
+                    "type": "row",
+                    "title": "Host $host",
+                    "repeat": "host",
+                    "gridPos": { "x": 0, "y": 0, "w": 24, "h": 1 }
+                },
+                {
+                    "type": "timeseries",
+                    "title": "CPU $cpu",
+                    "repeat": "cpu",
+                    "repeatDirection": "v",
+                    "gridPos": { "x": 0, "y": 1, "w": 12, "h": 8 },
+                    "targets": [{ "expr": "up" }]
+                },
+                {
+                    "type": "stat",
+                    "title": "Disk",
+                    "repeat": "disk",
+                    "repeatDirection": "h",
+                    "maxPerRow": 3,
+                    "gridPos": { "x": 12, "y": 1, "w": 12, "h": 8 },
+                    "targets": [{ "expr": "up" }]
+                },
+                {
+                    "type": "stat",
+                    "title": "Disk",
+                    "repeatPanelId": 3,
+                    "gridPos": { "x": 18, "y": 1, "w": 6, "h": 8 },
+                    "targets": [{ "expr": "up" }]
+                },
+                {
+                    "type": "stat",
+                    "title": "Plain",
+                    "repeat": "",
+                    "gridPos": { "x": 0, "y": 9, "w": 12, "h": 8 },
+                    "targets": [{ "expr": "up" }]
+                }
+            ]
+        }"#;
+        let path = std::env::temp_dir().join("grafatui-repeat-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let repeats: Vec<_> = dashboard.queries.iter().map(|q| q.repeat.clone()).collect();
+        assert_eq!(
+            repeats,
+            vec![
+                Some(crate::app::PanelRepeat {
+                    var: "host".to_string(),
+                    direction: crate::app::RepeatDirection::Horizontal,
+                    max_per_row: None,
+                }),
+                Some(crate::app::PanelRepeat {
+                    var: "cpu".to_string(),
+                    direction: crate::app::RepeatDirection::Vertical,
+                    max_per_row: None,
+                }),
+                Some(crate::app::PanelRepeat {
+                    var: "disk".to_string(),
+                    direction: crate::app::RepeatDirection::Horizontal,
+                    max_per_row: Some(3),
+                }),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_text_panels() {
+        let json = r##"{
+            "title": "Text",
+            "panels": [
+                {
+                    "type": "text",
+                    "title": "Runbook",
+                    "gridPos": { "x": 0, "y": 0, "w": 12, "h": 4 },
+                    "options": { "mode": "markdown", "content": "# Steps" }
+                },
+                {
+                    "type": "text",
+                    "title": "Legacy",
+                    "mode": "html",
+                    "content": "<b>old</b>"
+                },
+                {
+                    "type": "text",
+                    "title": "Chart",
+                    "options": { "mode": "mermaid", "content": "graph TD" }
+                }
+            ]
+        }"##;
+        let path = std::env::temp_dir().join("grafatui-text-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let options: Vec<_> = dashboard
+            .queries
+            .iter()
+            .map(|q| q.options.clone())
+            .collect();
+        let text = |mode, content: &str| {
+            crate::app::PanelOptions::Text(crate::app::TextOptions {
+                mode,
+                content: content.to_string(),
+            })
+        };
+        assert_eq!(
+            options,
+            vec![
+                text(crate::app::TextMode::Markdown, "# Steps"),
+                text(crate::app::TextMode::Html, "<b>old</b>"),
+                text(crate::app::TextMode::Markdown, "graph TD"),
+            ]
+        );
+        assert!(
+            dashboard
+                .queries
+                .iter()
+                .all(|q| q.panel_type == crate::app::PanelType::Text && q.exprs.is_empty())
+        );
+        assert_eq!(dashboard.skipped_panels, 0);
+        assert_eq!(dashboard.diagnostics.len(), 1);
+        assert_eq!(dashboard.diagnostics[0].path, "panels[2].options.mode");
+    }
+
+    #[test]
+    fn test_import_value_mappings() {
+        let json = r##"{
+            "title": "Mappings",
+            "panels": [
+                {
+                    "type": "stat",
+                    "title": "Status",
+                    "targets": [{ "expr": "up" }],
+                    "fieldConfig": {
+                        "defaults": {
+                            "mappings": [
+                                {
+                                    "type": "value",
+                                    "options": {
+                                        "0": { "text": "DOWN", "color": "red", "index": 0 },
+                                        "1": { "text": "UP", "color": "#00ff00", "index": 1 }
+                                    }
+                                },
+                                {
+                                    "type": "range",
+                                    "options": { "from": 2, "to": null, "result": { "text": "DEGRADED" } }
+                                },
+                                {
+                                    "type": "regex",
+                                    "options": { "pattern": "(", "result": { "text": "x" } }
+                                },
+                                {
+                                    "type": "special",
+                                    "options": { "match": "null+nan", "result": { "text": "N/A", "color": "gray" } }
+                                },
+                                {
+                                    "type": "special",
+                                    "options": { "match": "empty", "result": { "text": "-" } }
+                                },
+                                { "id": 1, "type": 1, "value": "3", "text": "Legacy" },
+                                { "id": 2, "type": 2, "from": "10", "to": "", "text": "Many" }
+                            ]
+                        }
+                    }
+                }
+            ]
+        }"##;
+        let path = std::env::temp_dir().join("grafatui-value-mappings-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let display = &dashboard.queries[0].display;
+        assert_eq!(display.mappings.len(), 6);
+        assert_eq!(display.format_value(Some(0.0)), "DOWN");
+        assert_eq!(
+            display.mapped_color(Some(0.0)),
+            Some(ratatui::style::Color::Red)
+        );
+        assert_eq!(display.format_value(Some(1.0)), "UP");
+        assert_eq!(
+            display.mapped_color(Some(1.0)),
+            Some(ratatui::style::Color::Rgb(0, 255, 0))
+        );
+        assert_eq!(display.format_value(Some(2.0)), "DEGRADED");
+        assert_eq!(display.format_value(None), "N/A");
+        assert_eq!(display.format_value(Some(f64::NAN)), "N/A");
+
+        let legacy = crate::ui::DisplayFormat {
+            mappings: display.mappings[4..].to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(legacy.format_value(Some(3.0)), "Legacy");
+        assert_eq!(legacy.format_value(Some(50.0)), "Many");
+
+        let diagnostics: Vec<_> = dashboard
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.path.as_str())
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "panels[0].fieldConfig.defaults.mappings[2]",
+                "panels[0].fieldConfig.defaults.mappings[4]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_field_overrides() {
+        let json = r##"{
+            "title": "Overrides",
+            "panels": [
+                {
+                    "type": "timeseries",
+                    "title": "Usage",
+                    "targets": [
+                        { "expr": "memory_bytes", "refId": "A" },
+                        { "expr": "cpu_ratio" }
+                    ],
+                    "fieldConfig": {
+                        "defaults": { "unit": "bytes" },
+                        "overrides": [
+                            {
+                                "matcher": { "id": "byFrameRefID", "options": "B" },
+                                "properties": [
+                                    { "id": "unit", "value": "percentunit" },
+                                    { "id": "displayName", "value": "CPU" },
+                                    { "id": "color", "value": { "mode": "fixed", "fixedColor": "red" } },
+                                    { "id": "custom.lineWidth", "value": 2 }
+                                ]
+                            },
+                            {
+                                "matcher": {
+                                    "id": "byNames",
+                                    "options": { "mode": "exclude", "names": ["CPU"] }
+                                },
+                                "properties": [
+                                    { "id": "custom.hideFrom", "value": { "legend": false, "viz": true } },
+                                    { "id": "custom.axisPlacement", "value": "right" },
+                                    { "id": "custom.stacking", "value": { "mode": "normal", "group": "io" } }
+                                ]
+                            },
+                            {
+                                "matcher": { "id": "byValue", "options": { "reducer": "max" } },
+                                "properties": [{ "id": "unit", "value": "s" }]
+                            }
+                        ]
+                    }
+                }
+            ]
+        }"##;
+        let path = std::env::temp_dir().join("grafatui-field-overrides-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        use crate::app::{FieldMatcher, OverrideProperty};
+        let panel = &dashboard.queries[0];
+        assert_eq!(panel.ref_ids, vec!["A", "B"]);
+        assert_eq!(panel.overrides.len(), 2);
+        assert_eq!(
+            panel.overrides[0].matcher,
+            FieldMatcher::RefId("B".to_string())
+        );
+        assert_eq!(
+            panel.overrides[0].properties,
+            vec![
+                OverrideProperty::Unit("percentunit".to_string()),
+                OverrideProperty::DisplayName("CPU".to_string()),
+                OverrideProperty::Color(ratatui::style::Color::Red),
+            ]
+        );
+        assert_eq!(
+            panel.overrides[1].matcher,
+            FieldMatcher::Names {
+                names: vec!["CPU".to_string()],
+                exclude: true,
+            }
+        );
+        assert_eq!(
+            panel.overrides[1].properties,
+            vec![
+                OverrideProperty::HideFrom {
+                    legend: false,
+                    viz: true,
+                },
+                OverrideProperty::AxisPlacement(crate::app::GraphAxisPlacement::Right),
+                OverrideProperty::Stacking {
+                    mode: crate::app::GraphStackingMode::Normal,
+                    group: "io".to_string(),
+                },
+            ]
+        );
+
+        let diagnostics: Vec<_> = dashboard
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.path.as_str())
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "panels[0].fieldConfig.overrides[0].properties[3]",
+                "panels[0].fieldConfig.overrides[2].matcher",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_legend_options() {
+        let json = r#"{
+            "title": "Legends",
+            "panels": [
+                {
+                    "type": "timeseries",
+                    "title": "Table",
+                    "targets": [{ "expr": "up" }],
+                    "options": {
+                        "legend": {
+                            "displayMode": "table",
+                            "placement": "right",
+                            "calcs": ["mean", "lastNotNull", "logmin"],
+                            "sortBy": "Last *",
+                            "sortDesc": true
+                        }
+                    }
+                },
+                {
+                    "type": "timeseries",
+                    "title": "Hidden",
+                    "targets": [{ "expr": "up" }],
+                    "options": { "legend": { "displayMode": "list", "showLegend": false } }
+                }
+            ]
+        }"#;
+        let path = std::env::temp_dir().join("grafatui-legend-options-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        use crate::app::{
+            LegendDisplayMode, LegendOptions, LegendPlacement, PanelOptions, Reducer,
+        };
+        let legend = |index: usize| match &dashboard.queries[index].options {
+            PanelOptions::Graph(options) => options.legend.clone(),
+            other => panic!("expected graph options, got {other:?}"),
+        };
+        assert_eq!(
+            legend(0),
+            LegendOptions {
+                display_mode: LegendDisplayMode::Table,
+                placement: LegendPlacement::Right,
+                calcs: vec![Reducer::Mean, Reducer::LastNotNull],
+                sort_by: Some("Last *".to_string()),
+                sort_desc: true,
+            }
+        );
+        assert_eq!(legend(1).display_mode, LegendDisplayMode::Hidden);
+
+        let diagnostics: Vec<_> = dashboard
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.path.as_str())
+            .collect();
+        assert_eq!(diagnostics, vec!["panels[0].options.legend.calcs[2]"]);
+    }
+
+    #[test]
+    fn test_import_heatmap_options() {
+        let json = r##"{
+            "title": "Heatmaps",
+            "panels": [
+                {
+                    "type": "heatmap",
+                    "title": "Scheme",
+                    "targets": [{ "expr": "sum by (le) (rate(latency_bucket[5m]))", "format": "heatmap" }],
+                    "options": {
+                        "calculate": false,
+                        "color": { "mode": "scheme", "scheme": "Viridis", "reverse": true, "max": 50 },
+                        "yAxis": { "unit": "s", "decimals": 1 },
+                        "legend": { "show": false }
+                    }
+                },
+                {
+                    "type": "heatmap",
+                    "title": "Opacity",
+                    "targets": [{ "expr": "latency" }],
+                    "options": {
+                        "calculate": true,
+                        "color": { "mode": "opacity", "fill": "#ff0000", "scale": "linear" }
+                    }
+                },
+                {
+                    "type": "heatmap",
+                    "title": "Legacy",
+                    "targets": [{ "expr": "latency_bucket" }],
+                    "dataFormat": "tsbuckets",
+                    "color": { "mode": "spectrum", "colorScheme": "interpolateBlues", "colorScale": "sqrt", "exponent": 0.4 },
+                    "yAxis": { "format": "short", "decimals": null },
+                    "legend": { "show": false }
+                },
+                {
+                    "type": "heatmap",
+                    "title": "Unknown scheme",
+                    "targets": [{ "expr": "latency_bucket" }],
+                    "options": { "color": { "mode": "scheme", "scheme": "Rainbow" } }
+                }
+            ]
+        }"##;
+        let path = std::env::temp_dir().join("grafatui-heatmap-options-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        use crate::app::{HeatmapColor, HeatmapColorMode, HeatmapOptions, PanelOptions};
+        let options = |index: usize| match &dashboard.queries[index].options {
+            PanelOptions::Heatmap(options) => options.clone(),
+            other => panic!("expected heatmap options, got {other:?}"),
+        };
+        assert_eq!(
+            options(0),
+            HeatmapOptions {
+                color: HeatmapColor {
+                    mode: HeatmapColorMode::Scheme("Viridis".to_string()),
+                    exponent: Some(0.5),
+                    reverse: true,
+                    min: None,
+                    max: Some(50.0),
+                },
+                show_legend: false,
+                y_axis: crate::ui::DisplayFormat {
+                    unit: Some("s".to_string()),
+                    decimals: Some(1),
+                    ..Default::default()
+                },
+            }
+        );
+        let opacity = options(1);
+        assert_eq!(
+            opacity.color.mode,
+            HeatmapColorMode::Opacity(ratatui::style::Color::Rgb(255, 0, 0))
+        );
+        assert_eq!(opacity.color.exponent, None);
+        assert!(opacity.show_legend);
+
+        let legacy = options(2);
+        assert_eq!(
+            legacy.color.mode,
+            HeatmapColorMode::Scheme("Blues".to_string())
+        );
+        assert_eq!(legacy.color.exponent, Some(0.4));
+        assert_eq!(legacy.y_axis, crate::ui::DisplayFormat::default());
+        assert!(!legacy.show_legend);
+
+        assert_eq!(options(3).color, HeatmapColor::default());
+
+        let diagnostics: Vec<_> = dashboard
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.path.as_str())
+            .collect();
+        assert_eq!(
+            diagnostics,
+            vec![
+                "panels[1].options.calculate",
+                "panels[3].options.color.scheme"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_reduce_options() {
+        let json = r#"{
+            "title": "Reduce",
+            "panels": [
+                {
+                    "type": "table",
+                    "title": "Latency",
+                    "targets": [{ "expr": "latency_seconds" }],
+                    "options": {
+                        "reduceOptions": {
+                            "calcs": ["mean", "p95", "max"],
+                            "values": true,
+                            "limit": 10,
+                            "fields": "/api_.*/"
+                        }
+                    }
+                },
+                {
+                    "type": "stat",
+                    "title": "Up",
+                    "targets": [{ "expr": "up" }]
+                },
+                {
+                    "type": "timeseries",
+                    "title": "Graph",
+                    "targets": [{ "expr": "up" }],
+                    "options": { "reduceOptions": { "calcs": ["max"] } }
+                }
+            ]
+        }"#;
+        let path = std::env::temp_dir().join("grafatui-reduce-options-test.json");
+        std::fs::write(&path, json).unwrap();
+
+        let dashboard = load_grafana_dashboard(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        use crate::app::{FieldSelector, PanelOptions, ReduceOptions, Reducer};
+        assert_eq!(
+            dashboard.queries[0].options,
+            PanelOptions::Reduce(ReduceOptions {
+                calcs: vec![Reducer::Mean, Reducer::Percentile(95), Reducer::Max],
+                values: true,
+                limit: Some(10),
+                fields: Some(FieldSelector::Regex(
+                    crate::ui::GrafanaRegex::new("/api_.*/").unwrap()
+                )),
+            })
+        );
+        assert_eq!(
+            dashboard.queries[1].options,
+            PanelOptions::Reduce(ReduceOptions {
+                calcs: vec![Reducer::LastNotNull],
+                ..Default::default()
+            })
+        );
+        assert!(matches!(
+            dashboard.queries[2].options,
+            PanelOptions::Graph(_)
+        ));
+        assert!(dashboard.diagnostics.is_empty());
+    }
+
     #[test]
     fn test_variable_diagnostics_report_modifiers_and_unresolved_variables() {
         let json = r#"{
@@ -1301,7 +3474,7 @@ mod tests {
                     "title": "CPU",
                     "targets": [
                         { "expr": "up{job=\"$job\", instance=\"${instance}\"}" },
-                        { "expr": "up{job=~\"${job:regex}\", cluster=\"$cluster\", interval=\"$__interval\", range=\"$__range_s\"}" }
+                        { "expr": "up{job=~\"${job:regex}\", instance=~\"${instance:date:iso}\", cluster=\"$cluster\", interval=\"$__interval\", range=\"$__range_s\"}" }
                     ]
                 }
             ]
@@ -1317,7 +3490,7 @@ mod tests {
         assert!(diagnostics.iter().any(|diagnostic| {
             diagnostic.code == "unsupported_variable_modifier"
                 && diagnostic.path == "panels[0].targets[1].expr"
-                && diagnostic.message.contains("${job:regex}")
+                && diagnostic.message.contains("${instance:date:iso}")
         }));
         assert!(diagnostics.iter().any(|diagnostic| {
             diagnostic.code == "unresolved_variable"
@@ -22,12 +22,13 @@ mod prom;
 mod theme;
 mod ui;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::time::Duration;
 
 use anyhow::{Context, Result, anyhow, bail};
 use clap::Parser;
-use config::Config;
+use config::{Config, VarSetting};
 use crossterm::{
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode},
@@ -82,15 +83,13 @@ async fn main() -> Result<()> {
             anyhow!("--validate requires --grafana-json or grafana_json in config")
         })?;
         let dashboard = grafana::load_grafana_dashboard(&path)?;
-        let summary = validate_dashboard_import(dashboard, config.vars.clone(), &args.var);
+        let summary =
+            validate_dashboard_import(dashboard, config.vars.clone(), &args.var, &args.filter);
         print_validation_summary(&summary, args.format, args.strict)?;
         return Ok(());
     }
 
-    let prometheus_url = args
-        .prometheus_url
-        .or(config.prometheus_url)
-        .unwrap_or_else(|| "http://localhost:9090".to_string());
+    let datasources = build_datasources(&config, &args)?;
 
     let range_str = args
         .range
@@ -125,12 +124,13 @@ async fn main() -> Result<()> {
         .filter(|color| *color != ratatui::style::Color::Reset)
         .unwrap_or(ratatui::style::Color::DarkGray);
 
-    let mut vars: HashMap<String, String> = HashMap::new();
+    let mut vars: HashMap<String, app::Variable> = HashMap::new();
+    let mut var_order = Vec::new();
     let mut query_vars = Vec::new();
+    let mut datasource_vars = Vec::new();
+    let mut filters = args.filter.clone();
     let mut dashboard_refresh_rate_ms = None;
 
-    let prom = prom::PromClient::new(prometheus_url);
-
     // Build panels from Grafana import or simple queries.
     let (title, panels, skipped_panels) = if let Some(path) = dashboard_path {
         let d = grafana::load_grafana_dashboard(&path)?;
@@ -138,7 +138,10 @@ async fn main() -> Result<()> {
         print_import_diagnostics(&import_context.diagnostics);
         dashboard_refresh_rate_ms = d.refresh_rate_ms;
         vars = import_context.vars;
+        var_order = d.var_names;
         query_vars = import_context.query_vars;
+        datasource_vars = d.datasource_vars;
+        filters = merge_filters(d.filters, &args.filter);
 
         let ps = d
             .queries
@@ -148,9 +151,15 @@ async fn main() -> Result<()> {
                 exprs: q.exprs,
                 legends: q.legends,
                 query_modes: q.query_modes,
+                datasources: q.datasources,
+                ref_ids: q.ref_ids,
+                resolution: q.resolution,
                 series: vec![],
                 last_error: None,
                 last_url: None,
+                last_step: None,
+                loading: false,
+                stale: false,
                 last_samples: 0,
                 grid: q.grid.map(|g| app::GridUnit {
                     x: g.x,
@@ -159,13 +168,17 @@ async fn main() -> Result<()> {
                     h: g.h,
                 }),
                 y_axis_mode: app::YAxisMode::Auto,
+                legend_scroll: 0,
                 panel_type: q.panel_type,
                 thresholds: q.thresholds,
                 min: q.min,
                 max: q.max,
                 autogrid: q.autogrid,
                 display: q.display,
+                overrides: q.overrides,
                 options: q.options,
+                repeat: q.repeat,
+                scoped_vars: std::collections::HashMap::new(),
             })
             .collect();
         (format!("{} (imported)", d.title), ps, d.skipped_panels)
@@ -194,7 +207,7 @@ async fn main() -> Result<()> {
     let refresh_every = Duration::from_millis(refresh_rate);
 
     let mut state = app::AppState::new(
-        prom,
+        datasources,
         range,
         step,
         refresh_every,
@@ -213,8 +226,11 @@ async fn main() -> Result<()> {
     state.autogrid_enabled = autogrid_enabled;
     state.autogrid_color = autogrid_color;
     state.vars = vars; // <— pass variables into the app
+    state.var_order = var_order;
     state.query_vars = query_vars;
-    state.refresh().await?;
+    state.datasource_vars = datasource_vars;
+    state.filters = filters;
+    state.init_repeats();
 
     // Terminal setup
     crossterm::terminal::enable_raw_mode()?;
@@ -259,7 +275,7 @@ fn resolve_refresh_rate_ms(
 
 #[derive(Debug)]
 struct ImportContext {
-    vars: HashMap<String, String>,
+    vars: HashMap<String, app::Variable>,
     query_vars: Vec<grafana::TemplateQueryVar>,
     diagnostics: Vec<grafana::ImportDiagnostic>,
 }
@@ -268,36 +284,76 @@ struct ImportContext {
 struct ImportValidationSummary {
     title: String,
     panel_count: usize,
+    variables: Vec<ValidatedVariable>,
+    filters: Vec<String>,
     diagnostics: Vec<grafana::ImportDiagnostic>,
 }
 
+/// A template variable as it would start in the TUI.
+#[derive(Debug, Serialize)]
+struct ValidatedVariable {
+    name: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    value: String,
+}
+
 fn validate_dashboard_import(
     dashboard: grafana::DashboardImport,
-    config_vars: Option<HashMap<String, String>>,
+    config_vars: Option<HashMap<String, VarSetting>>,
     cli_vars: &[(String, String)],
+    cli_filters: &[app::LabelMatcher],
 ) -> ImportValidationSummary {
     let import_context = build_import_context(&dashboard, config_vars, cli_vars);
+    let variables = dashboard
+        .var_names
+        .iter()
+        .map(|name| {
+            let var = import_context.vars.get(name);
+            let kind = var.map_or_else(
+                || {
+                    // Query variables without a current value get one on refresh.
+                    if dashboard.query_vars.iter().any(|q| q.name == *name) {
+                        app::VariableKind::Query
+                    } else {
+                        app::VariableKind::User
+                    }
+                },
+                |var| var.kind,
+            );
+            ValidatedVariable {
+                name: name.clone(),
+                kind: kind.as_str(),
+                value: var.map_or_else(|| "-".to_string(), app::Variable::display),
+            }
+        })
+        .collect();
     ImportValidationSummary {
         title: dashboard.title,
-        panel_count: dashboard.queries.len(),
+        panel_count: dashboard
+            .queries
+            .iter()
+            .filter(|panel| panel.panel_type != app::PanelType::Row)
+            .count(),
+        variables,
+        filters: merge_filters(dashboard.filters, cli_filters)
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
         diagnostics: import_context.diagnostics,
     }
 }
 
 fn build_import_context(
     dashboard: &grafana::DashboardImport,
-    config_vars: Option<HashMap<String, String>>,
+    config_vars: Option<HashMap<String, VarSetting>>,
     cli_vars: &[(String, String)],
 ) -> ImportContext {
     let mut vars = dashboard.vars.clone();
-    let pinned_vars = merge_user_vars(&mut vars, config_vars, cli_vars);
-
-    let query_vars = dashboard
-        .query_vars
-        .iter()
-        .filter(|var| !pinned_vars.contains(&var.name))
-        .cloned()
-        .collect();
+    // Pinned query variables keep their values while their options are
+    // still refreshed for the picker.
+    merge_user_vars(&mut vars, config_vars, cli_vars);
+    let query_vars = dashboard.query_vars.clone();
     let mut diagnostics = dashboard.diagnostics.clone();
     diagnostics.extend(grafana::variable_diagnostics(dashboard, &vars));
 
@@ -308,25 +364,173 @@ fn build_import_context(
     }
 }
 
+/// Applies `[vars]` and `--var` overrides, marking the variables pinned.
+///
+/// Repeating a `--var` key selects several values. Dashboard variables keep
+/// their type and options.
 fn merge_user_vars(
-    vars: &mut HashMap<String, String>,
-    config_vars: Option<HashMap<String, String>>,
+    vars: &mut HashMap<String, app::Variable>,
+    config_vars: Option<HashMap<String, VarSetting>>,
     cli_vars: &[(String, String)],
-) -> HashSet<String> {
-    let mut pinned_vars = HashSet::new();
+) {
     if let Some(config_vars) = config_vars {
         for (k, v) in config_vars {
-            pinned_vars.insert(k.clone());
-            vars.insert(k, v);
+            pin_variable(vars, k, v.into_values());
         }
     }
 
+    let mut cli_values: HashMap<&str, Vec<String>> = HashMap::new();
     for (k, v) in cli_vars {
-        pinned_vars.insert(k.clone());
-        vars.insert(k.clone(), v.clone());
+        cli_values.entry(k).or_default().push(v.clone());
+    }
+    for (k, values) in cli_values {
+        pin_variable(vars, k.to_string(), values);
+    }
+}
+
+/// Combines dashboard ad-hoc filters with `--filter` ones.
+///
+/// A `--filter` replaces every dashboard filter on the same label.
+fn merge_filters(
+    dashboard_filters: Vec<app::LabelMatcher>,
+    cli_filters: &[app::LabelMatcher],
+) -> Vec<app::LabelMatcher> {
+    let mut filters: Vec<app::LabelMatcher> = dashboard_filters
+        .into_iter()
+        .filter(|filter| !cli_filters.iter().any(|cli| cli.name == filter.name))
+        .collect();
+    filters.extend(cli_filters.iter().cloned());
+    filters
+}
+
+fn pin_variable(vars: &mut HashMap<String, app::Variable>, name: String, values: Vec<String>) {
+    let var = match vars.entry(name) {
+        Entry::Occupied(entry) => {
+            let var = entry.into_mut();
+            var.select(values);
+            var
+        }
+        Entry::Vacant(entry) => entry.insert(app::Variable::with_values(values)),
+    };
+    var.pinned = true;
+}
+
+/// Builds the datasource registry.
+///
+/// The top-level `prometheus_url`, `[auth]` and `[tls]` settings form a
+/// datasource named `default`, registered when no `[[datasources]]` are
+/// configured or when a Prometheus URL is given explicitly.
+fn build_datasources(config: &Config, args: &Args) -> Result<app::DatasourceRegistry> {
+    let configured = config.datasources.clone().unwrap_or_default();
+    let explicit_url = args
+        .prometheus_url
+        .clone()
+        .or_else(|| config.prometheus_url.clone());
+
+    let mut entries = Vec::new();
+    if configured.is_empty() || explicit_url.is_some() {
+        let auth = merge_auth_config(config.auth.clone(), args)
+            .resolve()
+            .context("prometheus auth")?;
+        let tls = merge_tls_config(config.tls.clone(), args)
+            .resolve()
+            .context("prometheus tls")?;
+        let url = explicit_url.unwrap_or_else(|| "http://localhost:9090".to_string());
+        entries.push(app::Datasource {
+            name: "default".to_string(),
+            uid: None,
+            client: prom::PromClient::with_settings(url, auth, tls)?,
+        });
+    }
+
+    for ds in configured {
+        if entries.iter().any(|e: &app::Datasource| e.name == ds.name) {
+            bail!("duplicate datasource name `{}`", ds.name);
+        }
+        let context = format!("datasource `{}`", ds.name);
+        let auth = ds
+            .auth
+            .unwrap_or_default()
+            .resolve()
+            .with_context(|| context.clone())?;
+        let tls = ds
+            .tls
+            .unwrap_or_default()
+            .resolve()
+            .with_context(|| context.clone())?;
+        entries.push(app::Datasource {
+            name: ds.name,
+            uid: ds.uid,
+            client: prom::PromClient::with_settings(ds.url, auth, tls).context(context)?,
+        });
+    }
+
+    let mut registry =
+        app::DatasourceRegistry::new(entries, 0).ok_or_else(|| anyhow!("no datasources"))?;
+    if let Some(name) = args
+        .datasource
+        .as_deref()
+        .or(config.default_datasource.as_deref())
+        && registry.select_default(name).is_none()
+    {
+        bail!("unknown datasource `{}`", name);
+    }
+    Ok(registry)
+}
+
+/// Applies CLI auth options on top of the config file's `[auth]` table.
+///
+/// Each credential given on the command line replaces its configured
+/// counterpart, so `--basic-auth-password-file` keeps the configured username.
+/// Choosing basic auth on the CLI drops a configured bearer token and vice
+/// versa. Headers are merged with CLI values taking precedence.
+fn merge_auth_config(config_auth: Option<config::AuthConfig>, args: &Args) -> config::AuthConfig {
+    let mut auth = config_auth.unwrap_or_default();
+    let cli_basic = args.basic_auth_user.is_some() || args.basic_auth_password_file.is_some();
+    let cli_bearer = args.bearer_token_file.is_some() || args.bearer_token_env.is_some();
+    if cli_basic {
+        auth.bearer_token_file = None;
+        auth.bearer_token_env = None;
+        if let Some(user) = &args.basic_auth_user {
+            auth.username = Some(user.clone());
+        }
+        if let Some(path) = &args.basic_auth_password_file {
+            auth.password = None;
+            auth.password_file = Some(path.clone());
+        }
+    }
+    if cli_bearer {
+        auth.username = None;
+        auth.password = None;
+        auth.password_file = None;
+        auth.bearer_token_file = args.bearer_token_file.clone();
+        auth.bearer_token_env = args.bearer_token_env.clone();
+    }
+    for (name, value) in &args.header {
+        auth.headers.insert(name.clone(), value.clone());
     }
+    auth
+}
 
-    pinned_vars
+/// Applies CLI TLS options on top of the config file's `[tls]` table.
+fn merge_tls_config(config_tls: Option<config::TlsConfig>, args: &Args) -> config::TlsConfig {
+    let config_tls = config_tls.unwrap_or_default();
+    let cli_identity = args.tls_cert_file.is_some();
+    config::TlsConfig {
+        ca_file: args.tls_ca_file.clone().or(config_tls.ca_file),
+        cert_file: if cli_identity {
+            args.tls_cert_file.clone()
+        } else {
+            config_tls.cert_file
+        },
+        key_file: if cli_identity {
+            args.tls_key_file.clone()
+        } else {
+            config_tls.key_file
+        },
+        server_name: args.tls_server_name.clone().or(config_tls.server_name),
+        insecure_skip_verify: args.tls_insecure_skip_verify || config_tls.insecure_skip_verify,
+    }
 }
 
 fn print_import_diagnostics(diagnostics: &[grafana::ImportDiagnostic]) {
@@ -361,9 +565,17 @@ fn print_validation_summary(
                 );
             }
             println!(
-                "Grafana dashboard is importable: {} ({} panel(s))",
-                summary.title, summary.panel_count
+                "Grafana dashboard is importable: {} ({} panel(s), {} variable(s))",
+                summary.title,
+                summary.panel_count,
+                summary.variables.len()
             );
+            for var in &summary.variables {
+                println!("  ${} ({}) = {}", var.name, var.kind, var.value);
+            }
+            for filter in &summary.filters {
+                println!("  filter {filter}");
+            }
         }
         cli::ValidateFormat::Json => {
             println!("{}", serde_json::to_string_pretty(summary)?);
@@ -394,6 +606,125 @@ mod tests {
         assert_eq!(resolve_refresh_rate_ms(None, None, None), 1000);
     }
 
+    #[test]
+    fn test_build_datasources_registers_configured_entries() {
+        let config: Config = toml::from_str(
+            r#"
+            default_datasource = "staging"
+
+            [[datasources]]
+            name = "prod"
+            uid = "P1"
+            url = "http://prod:9090"
+
+            [[datasources]]
+            name = "staging"
+            url = "http://staging:9090"
+            "#,
+        )
+        .unwrap();
+
+        let registry = build_datasources(&config, &Args::parse_from(["grafatui"])).unwrap();
+        let names: Vec<_> = registry
+            .entries()
+            .iter()
+            .map(|ds| ds.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["prod", "staging"]);
+        assert_eq!(registry.default_datasource().name, "staging");
+
+        let args = Args::parse_from([
+            "grafatui",
+            "--prometheus-url",
+            "http://local:9090",
+            "--datasource",
+            "P1",
+        ]);
+        let registry = build_datasources(&config, &args).unwrap();
+        let names: Vec<_> = registry
+            .entries()
+            .iter()
+            .map(|ds| ds.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["default", "prod", "staging"]);
+        assert_eq!(registry.default_datasource().name, "prod");
+
+        let args = Args::parse_from(["grafatui", "--datasource", "missing"]);
+        assert!(build_datasources(&config, &args).is_err());
+    }
+
+    #[test]
+    fn test_merge_tls_config_prefers_cli_values() {
+        let config_tls = config::TlsConfig {
+            ca_file: Some("config-ca.pem".into()),
+            cert_file: Some("config-cert.pem".into()),
+            key_file: Some("config-key.pem".into()),
+            server_name: Some("config.internal".to_string()),
+            insecure_skip_verify: false,
+        };
+        let args = Args::parse_from([
+            "grafatui",
+            "--tls-cert-file",
+            "cli-cert.pem",
+            "--tls-key-file",
+            "cli-key.pem",
+        ]);
+
+        let tls = merge_tls_config(Some(config_tls), &args);
+
+        assert_eq!(tls.ca_file, Some("config-ca.pem".into()));
+        assert_eq!(tls.cert_file, Some("cli-cert.pem".into()));
+        assert_eq!(tls.key_file, Some("cli-key.pem".into()));
+        assert_eq!(tls.server_name, Some("config.internal".to_string()));
+        assert!(!tls.insecure_skip_verify);
+    }
+
+    #[test]
+    fn test_merge_auth_config_cli_credentials_replace_config() {
+        let config_auth = config::AuthConfig {
+            username: Some("config-user".to_string()),
+            password: Some("config-pass".to_string()),
+            headers: HashMap::from([
+                ("X-Scope-OrgID".to_string(), "config-tenant".to_string()),
+                ("X-Team".to_string(), "sre".to_string()),
+            ]),
+            ..config::AuthConfig::default()
+        };
+        let args = Args::parse_from([
+            "grafatui",
+            "--bearer-token-env",
+            "PROM_TOKEN",
+            "--header",
+            "X-Scope-OrgID=cli-tenant",
+        ]);
+
+        let auth = merge_auth_config(Some(config_auth), &args);
+
+        assert_eq!(auth.username, None);
+        assert_eq!(auth.password, None);
+        assert_eq!(auth.bearer_token_env, Some("PROM_TOKEN".to_string()));
+        assert_eq!(auth.headers.get("X-Scope-OrgID").unwrap(), "cli-tenant");
+        assert_eq!(auth.headers.get("X-Team").unwrap(), "sre");
+    }
+
+    #[test]
+    fn test_merge_auth_config_cli_password_file_keeps_config_username() {
+        let config_auth = config::AuthConfig {
+            username: Some("config-user".to_string()),
+            password: Some("config-pass".to_string()),
+            bearer_token_env: Some("CONFIG_TOKEN".to_string()),
+            ..config::AuthConfig::default()
+        };
+        let args = Args::parse_from(["grafatui", "--basic-auth-password-file", "cli-pass.txt"]);
+
+        let auth = merge_auth_config(Some(config_auth), &args);
+
+        assert_eq!(auth.username, Some("config-user".to_string()));
+        assert_eq!(auth.password, None);
+        assert_eq!(auth.password_file, Some("cli-pass.txt".into()));
+        assert_eq!(auth.bearer_token_env, None);
+    }
+
     #[test]
     fn test_validate_dashboard_import_adds_variable_diagnostics_without_prometheus() {
         let json = r#"{
@@ -413,33 +744,108 @@ mod tests {
         let dashboard = grafana::load_grafana_dashboard(&path).unwrap();
         std::fs::remove_file(path).unwrap();
 
-        let summary =
-            validate_dashboard_import(dashboard, None, &[("job".to_string(), "node".to_string())]);
+        let summary = validate_dashboard_import(
+            dashboard,
+            None,
+            &[("job".to_string(), "node".to_string())],
+            &[],
+        );
 
         assert_eq!(summary.title, "Validate");
         assert_eq!(summary.panel_count, 1);
+        assert!(summary.variables.is_empty());
         assert_eq!(summary.diagnostics.len(), 1);
         assert_eq!(summary.diagnostics[0].code, "unresolved_variable");
         assert!(summary.diagnostics[0].message.contains("$cluster"));
     }
 
+    #[test]
+    fn test_merge_filters_replaces_dashboard_filters_on_the_same_label() {
+        let dashboard_filters = vec![
+            app::LabelMatcher::new("cluster", app::MatchOp::Equal, "eu-1"),
+            app::LabelMatcher::new("env", app::MatchOp::NotEqual, "dev"),
+        ];
+        let cli_filters = [
+            app::LabelMatcher::parse("cluster=~us-.*").unwrap(),
+            app::LabelMatcher::parse("team=sre").unwrap(),
+        ];
+
+        let filters: Vec<String> = merge_filters(dashboard_filters, &cli_filters)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            filters,
+            vec![r#"env!="dev""#, r#"cluster=~"us-.*""#, r#"team="sre""#]
+        );
+    }
+
     #[test]
     fn test_merge_user_vars_applies_config_and_cli_overrides() {
         let mut vars = HashMap::new();
-        vars.insert("job".to_string(), "dashboard".to_string());
+        vars.insert("job".to_string(), app::Variable::single("dashboard"));
         let mut config_vars = HashMap::new();
-        config_vars.insert("job".to_string(), "config".to_string());
-        config_vars.insert("instance".to_string(), "config-instance".to_string());
+        config_vars.insert("job".to_string(), VarSetting::One("config".to_string()));
+        config_vars.insert(
+            "instance".to_string(),
+            VarSetting::One("config-instance".to_string()),
+        );
+        config_vars.insert(
+            "cluster".to_string(),
+            VarSetting::Many(vec!["eu".to_string(), "us".to_string()]),
+        );
 
-        let pinned = merge_user_vars(
+        merge_user_vars(
             &mut vars,
             Some(config_vars),
-            &[("job".to_string(), "cli".to_string())],
+            &[
+                ("job".to_string(), "cli".to_string()),
+                ("zone".to_string(), "a".to_string()),
+                ("zone".to_string(), "b".to_string()),
+            ],
+        );
+
+        assert_eq!(vars["job"].current, vec!["cli"]);
+        assert_eq!(vars["instance"].current, vec!["config-instance"]);
+        assert_eq!(vars["cluster"].interpolate().as_deref(), Some("(eu|us)"));
+        assert_eq!(vars["zone"].interpolate().as_deref(), Some("(a|b)"));
+        assert!(vars["job"].pinned);
+        assert!(vars["instance"].pinned);
+    }
+
+    #[test]
+    fn test_merge_user_vars_keeps_dashboard_variable_options() {
+        let mut vars = HashMap::new();
+        vars.insert(
+            "instance".to_string(),
+            app::Variable {
+                kind: app::VariableKind::Query,
+                current: vec!["server-01".to_string()],
+                include_all: true,
+                all_value: Some(".+".to_string()),
+                options: vec!["server-01".to_string(), "server-02".to_string()],
+                ..app::Variable::default()
+            },
+        );
+
+        merge_user_vars(
+            &mut vars,
+            None,
+            &[("instance".to_string(), "server-03".to_string())],
         );
 
-        assert_eq!(vars.get("job"), Some(&"cli".to_string()));
-        assert_eq!(vars.get("instance"), Some(&"config-instance".to_string()));
-        assert!(pinned.contains("job"));
-        assert!(pinned.contains("instance"));
+        let var = &vars["instance"];
+        assert_eq!(var.kind, app::VariableKind::Query);
+        assert_eq!(var.current, vec!["server-03"]);
+        assert!(var.include_all);
+        assert_eq!(var.all_value.as_deref(), Some(".+"));
+        assert_eq!(var.options, vec!["server-01", "server-02"]);
+
+        // Refreshed options keep the pinned value selected.
+        let mut var = var.clone();
+        var.set_options(vec!["server-01".to_string()]);
+        assert_eq!(var.current, vec!["server-03"]);
+        assert_eq!(var.options, vec!["server-01"]);
     }
 }
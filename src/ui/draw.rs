@@ -14,9 +14,12 @@
  * limitations under the License.
  */
 
-use super::layout::{calculate_grid_layout, calculate_two_column_layout};
+use super::layout::{
+    VARIABLE_BAR_SEPARATOR, calculate_grid_layout, calculate_two_column_layout, screen_layout,
+    variable_bar_area, variable_bar_labels,
+};
 use super::panels::render_panel;
-use crate::app::{AppMode, AppState, PanelState};
+use crate::app::{AppMode, AppState, PanelState, VariablePicker, option_label};
 use humantime::format_duration;
 use ratatui::{
     prelude::*,
@@ -26,15 +29,8 @@ use ratatui::{
 pub(crate) fn draw_ui(frame: &mut Frame, app: &AppState) {
     let size = frame.area();
 
-    // Layout: title bar, charts area, footer
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(5),
-            Constraint::Length(2),
-        ])
-        .split(size);
+    // Layout: title bar, variable bar, charts area, footer
+    let layout = screen_layout(size, app);
 
     // Title
     let title_text = format!(
@@ -48,10 +44,12 @@ pub(crate) fn draw_ui(frame: &mut Frame, app: &AppState) {
     let title_block = Block::default()
         .borders(Borders::ALL)
         .title(Line::from(title_text).alignment(Alignment::Center));
-    frame.render_widget(title_block, chunks[0]);
+    frame.render_widget(title_block, layout.title);
+
+    draw_variable_bar(frame, layout.variables, app);
 
     // Charts area: use Grafana grid if any panel has it, else fallback to 2-column flow
-    let area = chunks[1];
+    let area = layout.charts;
     let charts_block = Block::default().borders(Borders::ALL);
     frame.render_widget(charts_block, area);
     let inner_area = area.inner(Margin {
@@ -105,13 +103,22 @@ pub(crate) fn draw_ui(frame: &mut Frame, app: &AppState) {
         AppMode::Fullscreen => "FULLSCREEN",
         AppMode::Inspect => "INSPECT",
         AppMode::FullscreenInspect => "FULLSCREEN INSPECT",
+        AppMode::VariablePicker => "VARIABLES",
+        AppMode::FilterEditor => "FILTERS",
+    };
+
+    let datasource = app.datasources.default_datasource();
+    let prom_display = if app.datasources.entries().len() > 1 {
+        format!("{} ({})", datasource.name, datasource.client.base)
+    } else {
+        datasource.client.base.clone()
     };
 
     let summary = format!(
-        "Mode: {}{} | Prom: {} | range={} step={:?} refresh={} | grid={} | panels={} (skipped {}) errors={} | keys: ↑/↓ scroll, r refresh, e export, Ctrl+E record, +/- range, q quit, ? debug:{}",
+        "Mode: {}{} | Prom: {} | range={} step={:?} refresh={} | grid={} | panels={} (skipped {}) errors={} | keys: ↑/↓ scroll, $ variables, F filters, r refresh, e export, Ctrl+E record, +/- range, q quit, ? debug:{}",
         mode_display,
         if app.recording.is_some() { " REC" } else { "" },
-        app.prometheus.base,
+        prom_display,
         format_duration(app.range),
         app.step,
         format_duration(app.refresh_every),
@@ -149,17 +156,23 @@ pub(crate) fn draw_ui(frame: &mut Frame, app: &AppState) {
         }
     }
 
-    if app.mode == AppMode::Inspect {
-        if let Some(cx) = app.cursor_x {
-            let cursor_time = chrono::DateTime::from_timestamp(cx as i64, 0)
-                .map(|dt| dt.format("%H:%M:%S").to_string())
-                .unwrap_or_default();
-            detail = format!("Cursor: {} | {}", cursor_time, detail);
+    let mut detail_line = Line::raw(detail.clone());
+    if app.mode == AppMode::Inspect
+        && let Some(cx) = app.cursor_x
+    {
+        let cursor_time = chrono::DateTime::from_timestamp(cx as i64, 0)
+            .map(|dt| dt.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        let mut spans = vec![Span::raw(format!("Cursor: {} | ", cursor_time))];
+        if let Some(p) = app.panels.get(app.selected_panel) {
+            spans.extend(cursor_readout(p, app));
         }
+        spans.push(Span::raw(detail));
+        detail_line = Line::from(spans);
     }
 
-    let footer = Paragraph::new(format!("{}\n{}", summary, detail)).wrap(Wrap { trim: true });
-    frame.render_widget(footer, chunks[2]);
+    let footer = Paragraph::new(vec![Line::raw(summary), detail_line]).wrap(Wrap { trim: true });
+    frame.render_widget(footer, layout.footer);
 
     // Search Popup
     if app.mode == AppMode::Search {
@@ -210,6 +223,158 @@ pub(crate) fn draw_ui(frame: &mut Frame, app: &AppState) {
         }
         frame.render_stateful_widget(list, chunks[1], &mut list_state);
     }
+
+    if let Some(picker) = &app.var_picker {
+        draw_variable_picker(frame, picker, app);
+    }
+    if app.mode == AppMode::FilterEditor {
+        draw_filter_editor(frame, app);
+    }
+}
+
+/// Selected panel's series values at the inspect cursor, with value mappings
+/// applied to their text and color.
+fn cursor_readout(p: &PanelState, app: &AppState) -> Vec<Span<'static>> {
+    let values = app.cursor_values(p);
+    let mut spans = Vec::new();
+    for s in p.series.iter().filter(|s| s.visible) {
+        let Some(value) = values.get(&s.name).copied() else {
+            continue;
+        };
+        let color = p.value_color(s, Some(value)).unwrap_or(app.theme.text);
+        spans.push(Span::raw(format!("{}: ", s.name)));
+        spans.push(Span::styled(
+            p.series_display(s).format_value(Some(value)),
+            Style::default().fg(color),
+        ));
+        spans.push(Span::raw(", "));
+    }
+    if spans.pop().is_some() {
+        spans.push(Span::raw(" | "));
+    }
+    spans
+}
+
+/// Draws the template variables and their current values, followed by the
+/// ad-hoc filters.
+fn draw_variable_bar(frame: &mut Frame, area: Rect, app: &AppState) {
+    let editing = app.var_picker.as_ref().map(|picker| picker.name.as_str());
+    let names = app.variable_names();
+    let mut spans = Vec::new();
+    for (idx, label) in variable_bar_labels(app).into_iter().enumerate() {
+        if !spans.is_empty() {
+            spans.push(Span::raw(VARIABLE_BAR_SEPARATOR));
+        }
+        let highlighted = match names.get(idx) {
+            Some(name) => editing == Some(*name),
+            None => app.mode == AppMode::FilterEditor,
+        };
+        let style = if highlighted {
+            Style::default()
+                .fg(app.theme.title)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(app.theme.text)
+        };
+        spans.push(Span::styled(label, style));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), variable_bar_area(area));
+}
+
+/// Draws the popup listing the options of the variable being edited.
+fn draw_variable_picker(frame: &mut Frame, picker: &VariablePicker, app: &AppState) {
+    let area = centered_rect(50, 50, frame.area());
+    let hint = if picker.multi {
+        " Space toggle · Enter apply · Tab next · Esc cancel "
+    } else {
+        " Enter select · Tab next · Esc cancel "
+    };
+    let block = Block::default()
+        .title(format!(" ${} ", picker.name))
+        .title_bottom(Line::from(hint).alignment(Alignment::Right))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border_selected));
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let inner_area = area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner_area);
+
+    let input =
+        Paragraph::new(format!("> {}", picker.query)).style(Style::default().fg(app.theme.text));
+    frame.render_widget(input, chunks[0]);
+
+    let items: Vec<ListItem> = picker
+        .matches()
+        .into_iter()
+        .map(|option| {
+            let checked = picker.is_selected(option);
+            let mark = match (picker.multi, checked) {
+                (true, true) => "[x] ",
+                (true, false) => "[ ] ",
+                (false, true) => "• ",
+                (false, false) => "  ",
+            };
+            ListItem::new(format!("{mark}{}", option_label(option)))
+        })
+        .collect();
+    let empty = items.is_empty();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::TOP))
+        .highlight_style(
+            Style::default()
+                .fg(app.theme.title)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">> ");
+
+    let mut list_state = ratatui::widgets::ListState::default();
+    if !empty {
+        list_state.select(Some(picker.cursor));
+    }
+    frame.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+/// Draws the popup editing the ad-hoc filters as a comma-separated list.
+fn draw_filter_editor(frame: &mut Frame, app: &AppState) {
+    let area = centered_rect(60, 30, frame.area());
+    let block = Block::default()
+        .title(" Ad-hoc filters ")
+        .title_bottom(Line::from(" Enter apply · Esc cancel ").alignment(Alignment::Right))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border_selected));
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let inner_area = area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(inner_area);
+
+    let input = Paragraph::new(format!("> {}", app.filter_input))
+        .style(Style::default().fg(app.theme.text));
+    frame.render_widget(input, chunks[0]);
+
+    let help = match &app.filter_error {
+        Some(error) => Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red)),
+        None => Paragraph::new(r#"e.g. cluster="eu-1", env!~"dev|test""#)
+            .style(Style::default().fg(app.theme.legend_dim)),
+    };
+    frame.render_widget(
+        help.block(Block::default().borders(Borders::TOP))
+            .wrap(Wrap { trim: true }),
+        chunks[1],
+    );
 }
 
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -14,6 +14,7 @@
  * limitations under the License.
  */
 
+use super::mapping::{ValueMapping, map_value};
 use ratatui::style::Color;
 
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -21,15 +22,27 @@ pub(crate) struct DisplayFormat {
     pub(crate) unit: Option<String>,
     pub(crate) decimals: Option<usize>,
     pub(crate) no_value: Option<String>,
+    pub(crate) mappings: Vec<ValueMapping>,
 }
 
 impl DisplayFormat {
+    /// Display text for a value: its value mapping's text, otherwise the
+    /// formatted number or the `noValue` text.
     pub(crate) fn format_value(&self, value: Option<f64>) -> String {
+        if let Some(text) = map_value(&self.mappings, value).and_then(|result| result.text) {
+            return text;
+        }
         value
             .map(|value| self.format_number(value))
             .unwrap_or_else(|| self.no_value.clone().unwrap_or_else(|| "-".to_string()))
     }
 
+    /// Color a value mapping assigns to `value`.
+    pub(crate) fn mapped_color(&self, value: Option<f64>) -> Option<Color> {
+        map_value(&self.mappings, value).and_then(|result| result.color)
+    }
+
+    /// Formats a number with the unit and decimals, ignoring value mappings.
     pub(crate) fn format_number(&self, value: f64) -> String {
         // Keep this first pass intentionally small: these common Grafana units
         // unlock most imported dashboard readability while unknown units retain
@@ -75,20 +88,50 @@ impl DisplayFormat {
     }
 }
 
-/// Maps a normalized value (0.0-1.0) to a heatmap color (blue -> green -> yellow -> red)
-pub(crate) fn value_to_heatmap_color(normalized: f64) -> Color {
-    // Use a simple color gradient for heatmap
-    // 0.0 = Blue (cold), 0.5 = Yellow, 1.0 = Red (hot)
-    if normalized < 0.33 {
-        // Blue to Cyan
-        Color::Cyan
-    } else if normalized < 0.66 {
-        // Yellow/Green
-        Color::Yellow
-    } else {
-        // Red/Magenta for hot values
-        Color::Red
-    }
+/// RGB components of `color`, approximating named and indexed ANSI colors
+/// with the palette used by exports. `None` for the terminal default.
+pub(crate) fn color_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (204, 51, 51),
+        (51, 204, 102),
+        (214, 195, 67),
+        (79, 131, 255),
+        (204, 102, 204),
+        (51, 200, 204),
+        (208, 208, 208),
+        (102, 102, 102),
+        (255, 102, 102),
+        (102, 255, 153),
+        (255, 240, 106),
+        (122, 162, 255),
+        (255, 140, 255),
+        (102, 255, 255),
+        (245, 245, 245),
+    ];
+    const GRAY: (u8, u8, u8) = (160, 160, 160);
+
+    Some(match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => ANSI[0],
+        Color::Red => ANSI[1],
+        Color::Green => ANSI[2],
+        Color::Yellow => ANSI[3],
+        Color::Blue => ANSI[4],
+        Color::Magenta => ANSI[5],
+        Color::Cyan => ANSI[6],
+        Color::Gray => GRAY,
+        Color::DarkGray => ANSI[8],
+        Color::LightRed => ANSI[9],
+        Color::LightGreen => ANSI[10],
+        Color::LightYellow => ANSI[11],
+        Color::LightBlue => ANSI[12],
+        Color::LightMagenta => ANSI[13],
+        Color::LightCyan => ANSI[14],
+        Color::White => ANSI[15],
+        Color::Indexed(value) => ANSI.get(usize::from(value)).copied().unwrap_or(GRAY),
+    })
 }
 
 fn format_si_with_decimals(val: f64, decimals: Option<usize>) -> String {
@@ -250,6 +293,7 @@ mod tests {
             unit: Some("widgets".to_string()),
             decimals: None,
             no_value: None,
+            mappings: Vec::new(),
         };
         assert_eq!(unknown.format_number(1_500.0), "1.50k");
     }
@@ -260,6 +304,7 @@ mod tests {
             unit: Some("bytes".to_string()),
             decimals: None,
             no_value: None,
+            mappings: Vec::new(),
         };
         assert_eq!(bytes.format_number(1_536.0), "1.54KB");
 
@@ -267,6 +312,7 @@ mod tests {
             unit: Some("bits".to_string()),
             decimals: Some(1),
             no_value: None,
+            mappings: Vec::new(),
         };
         assert_eq!(bits.format_number(1_536.0), "1.5Kb");
     }
@@ -277,6 +323,7 @@ mod tests {
             unit: Some("percent".to_string()),
             decimals: Some(1),
             no_value: None,
+            mappings: Vec::new(),
         };
         assert_eq!(percent.format_number(42.42), "42.4%");
 
@@ -284,6 +331,7 @@ mod tests {
             unit: Some("percentunit".to_string()),
             decimals: Some(0),
             no_value: None,
+            mappings: Vec::new(),
         };
         assert_eq!(percent_unit.format_number(0.4242), "42%");
     }
@@ -294,6 +342,7 @@ mod tests {
             unit: Some("reqps".to_string()),
             decimals: Some(0),
             no_value: Some("n/a".to_string()),
+            mappings: Vec::new(),
         };
         assert_eq!(rate.format_number(1234.56), "1k req/s");
         assert_eq!(rate.format_value(None), "n/a");
@@ -302,6 +351,41 @@ mod tests {
         assert_eq!(default.format_value(None), "-");
     }
 
+    #[test]
+    fn test_display_format_applies_value_mappings() {
+        use super::super::mapping::{MappingResult, SpecialMatch};
+
+        let status = DisplayFormat {
+            unit: Some("short".to_string()),
+            decimals: Some(0),
+            no_value: Some("n/a".to_string()),
+            mappings: vec![
+                ValueMapping::Value {
+                    value: "1".to_string(),
+                    result: MappingResult {
+                        text: Some("UP".to_string()),
+                        color: Some(Color::Green),
+                    },
+                },
+                ValueMapping::Special {
+                    matcher: SpecialMatch::Null,
+                    result: MappingResult {
+                        text: None,
+                        color: Some(Color::Gray),
+                    },
+                },
+            ],
+        };
+
+        assert_eq!(status.format_value(Some(1.0)), "UP");
+        assert_eq!(status.mapped_color(Some(1.0)), Some(Color::Green));
+        assert_eq!(status.format_value(Some(2.0)), "2");
+        assert_eq!(status.mapped_color(Some(2.0)), None);
+        assert_eq!(status.format_value(None), "n/a");
+        assert_eq!(status.mapped_color(None), Some(Color::Gray));
+        assert_eq!(status.format_number(1.0), "1");
+    }
+
     #[test]
     fn test_format_axis_time_uses_time_for_short_ranges() {
         use chrono::TimeZone;
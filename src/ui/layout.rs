@@ -14,7 +14,7 @@
  * limitations under the License.
  */
 
-use crate::app::{AppMode, AppState, PanelState};
+use crate::app::{AppMode, AppState, GridUnit, PanelState, format_matchers};
 use ratatui::prelude::*;
 
 /// Returns a list of (Rect, panel_index) for all panels to be rendered.
@@ -29,42 +29,40 @@ pub(crate) fn calculate_grid_layout(area: Rect, app: &AppState) -> Vec<(Rect, us
 
     // Render grid-backed panels with scroll offset
     let scroll_offset = app.vertical_scroll as u16 * cell_h;
+    let placements = grid_placements(app, cell_h);
 
-    for (i, p) in app.panels.iter().enumerate() {
-        if let Some(g) = p.grid {
-            if g.x < 0 || g.y < 0 || g.w <= 0 || g.h <= 0 {
-                continue;
-            }
-            let x = area.x.saturating_add((g.x as u16).saturating_mul(cell_w));
-            let y_absolute = (g.y as u16).saturating_mul(cell_h);
-
-            // Apply scroll offset
-            if y_absolute < scroll_offset {
-                // Panel is scrolled out of view at the top
-                continue;
-            }
-            let y = area
-                .y
-                .saturating_add(y_absolute.saturating_sub(scroll_offset));
-
-            let w = (g.w as u16).saturating_mul(cell_w);
-            let h = (g.h as u16).saturating_mul(cell_h);
-
-            // Skip panels that are completely below the visible area
-            if y >= area.bottom() {
-                continue;
-            }
-
-            // Clamp to area
-            let rect = Rect {
-                x,
-                y,
-                width: w.min(area.right().saturating_sub(x)),
-                height: h.min(area.bottom().saturating_sub(y)),
-            };
-            if rect.width >= 8 && rect.height >= 4 {
-                results.push((rect, i));
-            }
+    for &(i, g, y_absolute, h) in &placements {
+        if g.x < 0 || g.w <= 0 || g.h <= 0 {
+            continue;
+        }
+        let x = area.x.saturating_add((g.x as u16).saturating_mul(cell_w));
+
+        // Apply scroll offset
+        if y_absolute < scroll_offset {
+            // Panel is scrolled out of view at the top
+            continue;
+        }
+        let y = area
+            .y
+            .saturating_add(y_absolute.saturating_sub(scroll_offset));
+
+        let w = (g.w as u16).saturating_mul(cell_w);
+
+        // Skip panels that are completely below the visible area
+        if y >= area.bottom() {
+            continue;
+        }
+
+        // Clamp to area
+        let rect = Rect {
+            x,
+            y,
+            width: w.min(area.right().saturating_sub(x)),
+            height: h.min(area.bottom().saturating_sub(y)),
+        };
+        let min_height = if app.panels[i].is_row() { 1 } else { 4 };
+        if rect.width >= 8 && rect.height >= min_height {
+            results.push((rect, i));
         }
     }
 
@@ -73,23 +71,17 @@ pub(crate) fn calculate_grid_layout(area: Rect, app: &AppState) -> Vec<(Rect, us
         .panels
         .iter()
         .enumerate()
-        .filter(|(_, p)| p.grid.is_none())
+        .filter(|(i, p)| p.grid.is_none() && !p.is_row() && !app.is_panel_hidden(*i))
         .collect();
     if !extras.is_empty() {
         // Place extras in a vertical stack under the grid.
-        let max_y_h = app
-            .panels
+        let grid_bottom = placements
             .iter()
-            .filter_map(|p| {
-                let g = p.grid?;
-                Some(g.y + g.h)
-            })
+            .map(|&(_, _, y, h)| y.saturating_add(h))
             .max()
             .unwrap_or(0);
 
-        let start_y_px = area
-            .y
-            .saturating_add((max_y_h as u16).saturating_mul(cell_h));
+        let start_y_px = area.y.saturating_add(grid_bottom);
 
         if start_y_px < area.bottom() {
             let extras_area = Rect {
@@ -110,8 +102,76 @@ pub(crate) fn calculate_grid_layout(area: Rect, app: &AppState) -> Vec<(Rect, us
     results
 }
 
+/// Places the grid panels shown on screen as `(index, grid, y, height)`.
+///
+/// Panels inside collapsed rows are left out, and the panels below a
+/// collapsed row move up by the height of the panels it hides. `y` and
+/// `height` are terminal lines from the top of the grid: panels span
+/// `cell_h` lines per grid unit, while row headers take a single line.
+fn grid_placements(app: &AppState, cell_h: u16) -> Vec<(usize, GridUnit, u16, u16)> {
+    let grid_bottom = |i: usize| app.panels[i].grid.map(|g| g.y + g.h);
+
+    // Height hidden by each collapsed row, keyed by the row's position.
+    let collapsed: Vec<(i32, i32)> = app
+        .panels
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.is_collapsed_row())
+        .filter_map(|(row, p)| {
+            let g = p.grid?;
+            let bottom = (row + 1..app.panels.len())
+                .take_while(|&i| !app.panels[i].is_row())
+                .filter_map(grid_bottom)
+                .max()?;
+            Some((g.y, (bottom - (g.y + g.h)).max(0)))
+        })
+        .collect();
+
+    let visible: Vec<(usize, GridUnit)> = app
+        .panels
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !app.is_panel_hidden(*i))
+        .filter_map(|(i, p)| {
+            let mut g = p.grid?;
+            let hidden: i32 = collapsed
+                .iter()
+                .filter(|(row_y, _)| *row_y < g.y)
+                .map(|(_, span)| span)
+                .sum();
+            g.y -= hidden;
+            Some((i, g))
+        })
+        .filter(|(_, g)| g.y >= 0)
+        .collect();
+
+    let row_ys: Vec<i32> = visible
+        .iter()
+        .filter(|(i, _)| app.panels[*i].is_row())
+        .map(|(_, g)| g.y)
+        .collect();
+
+    visible
+        .into_iter()
+        .map(|(i, g)| {
+            let rows_above = row_ys.iter().filter(|row_y| **row_y < g.y).count() as u16;
+            let y = (g.y as u16)
+                .saturating_mul(cell_h)
+                .saturating_sub(rows_above.saturating_mul(cell_h - 1));
+            let height = if app.panels[i].is_row() {
+                1
+            } else {
+                (g.h as u16).saturating_mul(cell_h)
+            };
+            (i, g, y, height)
+        })
+        .collect()
+}
+
 pub(crate) fn calculate_two_column_layout(area: Rect, app: &AppState) -> Vec<(Rect, usize)> {
-    let indices: Vec<usize> = (0..app.panels.len()).collect();
+    let indices: Vec<usize> = (0..app.panels.len())
+        .filter(|&i| !app.panels[i].is_row() && !app.is_panel_hidden(i))
+        .collect();
     calculate_two_column_layout_subset(area, app, &indices)
 }
 
@@ -177,17 +237,109 @@ pub(crate) fn calculate_two_column_layout_subset(
     results
 }
 
-pub(crate) fn visible_panel_rects(area: Rect, app: &AppState) -> Vec<(Rect, usize)> {
+/// Areas making up the dashboard screen.
+pub(crate) struct ScreenLayout {
+    pub(crate) title: Rect,
+    /// Template variable bar; empty when there are no variables or filters.
+    pub(crate) variables: Rect,
+    pub(crate) charts: Rect,
+    pub(crate) footer: Rect,
+}
+
+/// Splits the screen into title bar, variable bar, charts area and footer.
+pub(crate) fn screen_layout(area: Rect, app: &AppState) -> ScreenLayout {
+    let variables_height = if app.variable_names().is_empty() && app.filters.is_empty() {
+        0
+    } else {
+        1
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
+            Constraint::Length(variables_height),
             Constraint::Min(5),
             Constraint::Length(2),
         ])
         .split(area);
 
-    let charts_area = chunks[1];
+    ScreenLayout {
+        title: chunks[0],
+        variables: chunks[1],
+        charts: chunks[2],
+        footer: chunks[3],
+    }
+}
+
+/// Separator between entries of the variable bar.
+pub(crate) const VARIABLE_BAR_SEPARATOR: &str = "  ";
+
+/// Area the variable bar entries are written to, inset from the screen edge.
+pub(crate) fn variable_bar_area(bar: Rect) -> Rect {
+    bar.inner(Margin {
+        vertical: 0,
+        horizontal: 1,
+    })
+}
+
+/// An entry of the variable bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VariableBarEntry {
+    /// The variable at this index of [`AppState::variable_names`].
+    Variable(usize),
+    /// The ad-hoc filters, shown after the variables.
+    Filters,
+}
+
+/// Text of each variable bar entry, in display order.
+pub(crate) fn variable_bar_labels(app: &AppState) -> Vec<String> {
+    let mut labels: Vec<String> = app
+        .variable_names()
+        .into_iter()
+        .map(|name| {
+            let value = app
+                .vars
+                .get(name)
+                .map_or_else(|| "-".to_string(), |var| var.display());
+            format!("${name}: {value}")
+        })
+        .collect();
+    if !app.filters.is_empty() {
+        labels.push(format!("filters: {}", format_matchers(&app.filters)));
+    }
+    labels
+}
+
+/// Determines which variable bar entry is located at the given coordinates.
+pub(crate) fn variable_hit_test(
+    app: &AppState,
+    area: Rect,
+    x: u16,
+    y: u16,
+) -> Option<VariableBarEntry> {
+    let bar = variable_bar_area(screen_layout(area, app).variables);
+    if !bar.contains(ratatui::layout::Position { x, y }) {
+        return None;
+    }
+
+    let variable_count = app.variable_names().len();
+    let mut start = bar.x;
+    for (idx, label) in variable_bar_labels(app).iter().enumerate() {
+        let end = start.saturating_add(label.chars().count() as u16);
+        if (start..end).contains(&x) {
+            return Some(if idx < variable_count {
+                VariableBarEntry::Variable(idx)
+            } else {
+                VariableBarEntry::Filters
+            });
+        }
+        start = end.saturating_add(VARIABLE_BAR_SEPARATOR.len() as u16);
+    }
+    None
+}
+
+pub(crate) fn visible_panel_rects(area: Rect, app: &AppState) -> Vec<(Rect, usize)> {
+    let charts_area = screen_layout(area, app).charts;
     let inner_area = charts_area.inner(Margin {
         vertical: 1,
         horizontal: 1,
@@ -217,16 +369,7 @@ pub(crate) fn visible_panel_rects(area: Rect, app: &AppState) -> Vec<(Rect, usiz
 ///
 /// An `Option` containing a tuple of `(panel_index, panel_rect)` if a panel was hit.
 pub(crate) fn hit_test(app: &AppState, area: Rect, x: u16, y: u16) -> Option<(usize, Rect)> {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(5),
-            Constraint::Length(2),
-        ])
-        .split(area);
-
-    let charts_area = chunks[1];
+    let charts_area = screen_layout(area, app).charts;
     let inner_area = charts_area.inner(Margin {
         vertical: 1,
         horizontal: 1,
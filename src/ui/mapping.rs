@@ -0,0 +1,216 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use ratatui::style::Color;
+use regex::Regex;
+
+/// Display text and color a value mapping assigns to the values it matches.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct MappingResult {
+    pub(crate) text: Option<String>,
+    pub(crate) color: Option<Color>,
+}
+
+/// A Grafana value mapping. Mappings are tried in order and the first match wins.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ValueMapping {
+    /// Matches a value equal to `value`, compared as text or as a number.
+    Value {
+        value: String,
+        result: MappingResult,
+    },
+    /// Matches numbers within `from..=to`; a missing bound is open.
+    Range {
+        from: Option<f64>,
+        to: Option<f64>,
+        result: MappingResult,
+    },
+    /// Matches the value's text; the result text may refer to capture groups.
+    Regex {
+        pattern: GrafanaRegex,
+        result: MappingResult,
+    },
+    Special {
+        matcher: SpecialMatch,
+        result: MappingResult,
+    },
+}
+
+/// Values matched by a `special` mapping. Prometheus samples are numbers, so
+/// `True` and `False` match 1 and 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SpecialMatch {
+    Null,
+    NaN,
+    NullAndNaN,
+    True,
+    False,
+}
+
+/// A regex in Grafana's syntax, as used by `regex` mappings and `byRegexp`
+/// matchers: anchored to the whole text unless written as `/pattern/flags`.
+#[derive(Debug, Clone)]
+pub(crate) struct GrafanaRegex(Regex);
+
+impl GrafanaRegex {
+    pub(crate) fn new(pattern: &str) -> Result<Self, regex::Error> {
+        let source = match pattern
+            .strip_prefix('/')
+            .and_then(|rest| rest.rsplit_once('/'))
+        {
+            Some((body, flags)) if flags.contains('i') => format!("(?i){body}"),
+            Some((body, _)) => body.to_string(),
+            None => format!("^(?:{pattern})$"),
+        };
+        Regex::new(&source).map(Self)
+    }
+
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl PartialEq for GrafanaRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl ValueMapping {
+    fn apply(&self, value: Option<f64>) -> Option<MappingResult> {
+        match self {
+            Self::Value { value: key, result } => {
+                let value = value?;
+                let matches = *key == value_text(value)
+                    || key.trim().parse::<f64>().is_ok_and(|key| key == value);
+                matches.then(|| result.clone())
+            }
+            Self::Range { from, to, result } => {
+                let value = value.filter(|value| !value.is_nan())?;
+                let matches = value >= from.unwrap_or(f64::NEG_INFINITY)
+                    && value <= to.unwrap_or(f64::INFINITY);
+                matches.then(|| result.clone())
+            }
+            Self::Regex { pattern, result } => {
+                let text = value_text(value?);
+                if !pattern.is_match(&text) {
+                    return None;
+                }
+                Some(MappingResult {
+                    text: result
+                        .text
+                        .as_ref()
+                        .map(|replacement| pattern.0.replace(&text, replacement.as_str()).into()),
+                    color: result.color,
+                })
+            }
+            Self::Special { matcher, result } => {
+                let matches = match matcher {
+                    SpecialMatch::Null => value.is_none(),
+                    SpecialMatch::NaN => value.is_some_and(f64::is_nan),
+                    SpecialMatch::NullAndNaN => value.is_none_or(f64::is_nan),
+                    SpecialMatch::True => value == Some(1.0),
+                    SpecialMatch::False => value == Some(0.0),
+                };
+                matches.then(|| result.clone())
+            }
+        }
+    }
+}
+
+/// Result of the first mapping matching `value`, if any.
+pub(crate) fn map_value(mappings: &[ValueMapping], value: Option<f64>) -> Option<MappingResult> {
+    mappings.iter().find_map(|mapping| mapping.apply(value))
+}
+
+/// Text Grafana compares against value and regex mappings, e.g. `1` or `0.5`.
+fn value_text(value: f64) -> String {
+    value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(text: &str) -> MappingResult {
+        MappingResult {
+            text: Some(text.to_string()),
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_mapping_wins() {
+        let mappings = vec![
+            ValueMapping::Value {
+                value: "1".to_string(),
+                result: result("UP"),
+            },
+            ValueMapping::Range {
+                from: Some(0.0),
+                to: Some(10.0),
+                result: result("LOW"),
+            },
+            ValueMapping::Range {
+                from: Some(10.0),
+                to: None,
+                result: result("HIGH"),
+            },
+        ];
+
+        let text = |value| map_value(&mappings, value).and_then(|result| result.text);
+        assert_eq!(text(Some(1.0)).as_deref(), Some("UP"));
+        assert_eq!(text(Some(2.5)).as_deref(), Some("LOW"));
+        assert_eq!(text(Some(1e6)).as_deref(), Some("HIGH"));
+        assert_eq!(text(Some(-1.0)), None);
+        assert_eq!(text(None), None);
+    }
+
+    #[test]
+    fn test_regex_mappings_substitute_capture_groups() {
+        let anchored = ValueMapping::Regex {
+            pattern: GrafanaRegex::new(r"(\d)\.5").unwrap(),
+            result: result("$1 and a half"),
+        };
+        assert_eq!(
+            anchored.apply(Some(2.5)).and_then(|result| result.text),
+            Some("2 and a half".to_string())
+        );
+        assert_eq!(anchored.apply(Some(12.5)), None);
+
+        let literal = ValueMapping::Regex {
+            pattern: GrafanaRegex::new("/NAN/i").unwrap(),
+            result: result("broken"),
+        };
+        assert!(literal.apply(Some(f64::NAN)).is_some());
+    }
+
+    #[test]
+    fn test_special_mappings_match_null_nan_and_booleans() {
+        let special = |matcher| ValueMapping::Special {
+            matcher,
+            result: result("hit"),
+        };
+
+        assert!(special(SpecialMatch::Null).apply(None).is_some());
+        assert!(special(SpecialMatch::Null).apply(Some(f64::NAN)).is_none());
+        assert!(special(SpecialMatch::NaN).apply(Some(f64::NAN)).is_some());
+        assert!(special(SpecialMatch::NullAndNaN).apply(None).is_some());
+        assert!(special(SpecialMatch::True).apply(Some(1.0)).is_some());
+        assert!(special(SpecialMatch::False).apply(Some(0.0)).is_some());
+        assert!(special(SpecialMatch::False).apply(Some(1.0)).is_none());
+    }
+}
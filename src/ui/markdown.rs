@@ -0,0 +1,728 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Text panel content as styled lines.
+//!
+//! Markdown is reduced to what a terminal can show: headings, emphasis,
+//! lists, quotes, rules, code and links, with each link's target written
+//! after its text. HTML is stripped to readable text. The result is shared
+//! by the TUI renderer and image exports.
+
+use crate::app::TextMode;
+
+/// Inline style of a piece of text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct SpanStyle {
+    pub(crate) bold: bool,
+    pub(crate) italic: bool,
+    pub(crate) strike: bool,
+    pub(crate) code: bool,
+    pub(crate) link: bool,
+    /// Secondary text, such as a link target.
+    pub(crate) dim: bool,
+}
+
+/// A run of text sharing one style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TextSpan {
+    pub(crate) text: String,
+    pub(crate) style: SpanStyle,
+}
+
+/// Block a line belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineKind {
+    /// Heading of level 1 to 6.
+    Heading(u8),
+    Text,
+    /// Line of a code block, kept verbatim.
+    Code,
+    Quote,
+    /// Horizontal rule; has no spans.
+    Rule,
+}
+
+/// A rendered line of text panel content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TextLine {
+    pub(crate) kind: LineKind,
+    /// Nesting depth of list items.
+    pub(crate) indent: usize,
+    pub(crate) spans: Vec<TextSpan>,
+}
+
+impl TextLine {
+    fn new(kind: LineKind, spans: Vec<TextSpan>) -> Self {
+        Self {
+            kind,
+            indent: 0,
+            spans,
+        }
+    }
+
+    fn plain(kind: LineKind, text: &str) -> Self {
+        let spans = if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![TextSpan {
+                text: text.to_string(),
+                style: SpanStyle {
+                    code: kind == LineKind::Code,
+                    ..SpanStyle::default()
+                },
+            }]
+        };
+        Self::new(kind, spans)
+    }
+
+    fn is_blank(&self) -> bool {
+        self.kind == LineKind::Text && self.spans.is_empty()
+    }
+}
+
+/// Renders `content` according to `mode`.
+pub(crate) fn render_text(content: &str, mode: TextMode) -> Vec<TextLine> {
+    match mode {
+        TextMode::Markdown => render_markdown(content),
+        TextMode::Html => render_html(content),
+        TextMode::Code => content
+            .lines()
+            .map(|line| TextLine::plain(LineKind::Code, line))
+            .collect(),
+    }
+}
+
+/// Renders Markdown content.
+pub(crate) fn render_markdown(content: &str) -> Vec<TextLine> {
+    let mut lines = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut fence: Option<&str> = None;
+    // Indented lines continue a list rather than starting a code block.
+    let mut in_list = false;
+
+    for raw in content.lines() {
+        if let Some(marker) = fence {
+            if raw.trim_start().starts_with(marker) {
+                fence = None;
+            } else {
+                lines.push(TextLine::plain(LineKind::Code, raw));
+            }
+            continue;
+        }
+
+        let trimmed = raw.trim_start();
+        let indent = raw.len() - trimmed.len();
+        let list = list_item(trimmed);
+        in_list = list.is_some() || (in_list && (trimmed.is_empty() || indent > 0));
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            flush_paragraph(&mut paragraph, &mut lines);
+            fence = Some(&trimmed[..3]);
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut lines);
+            push_blank(&mut lines);
+        } else if indent >= 4 && paragraph.is_empty() && !in_list {
+            lines.push(TextLine::plain(
+                LineKind::Code,
+                raw.get(4..).unwrap_or(trimmed),
+            ));
+        } else if let Some((level, text)) = atx_heading(trimmed) {
+            flush_paragraph(&mut paragraph, &mut lines);
+            lines.push(TextLine::new(LineKind::Heading(level), parse_inline(text)));
+        } else if let Some(level) = setext_underline(trimmed).filter(|_| !paragraph.is_empty()) {
+            let text = paragraph.join(" ");
+            paragraph.clear();
+            lines.push(TextLine::new(LineKind::Heading(level), parse_inline(&text)));
+        } else if is_rule(trimmed) {
+            flush_paragraph(&mut paragraph, &mut lines);
+            lines.push(TextLine::new(LineKind::Rule, Vec::new()));
+        } else if let Some((marker, text)) = list {
+            flush_paragraph(&mut paragraph, &mut lines);
+            let mut spans = vec![TextSpan {
+                text: format!("{marker} "),
+                style: SpanStyle::default(),
+            }];
+            spans.extend(parse_inline(text));
+            lines.push(TextLine {
+                kind: LineKind::Text,
+                indent: indent / 2,
+                spans,
+            });
+        } else if let Some(text) = trimmed.strip_prefix('>') {
+            flush_paragraph(&mut paragraph, &mut lines);
+            lines.push(TextLine::new(
+                LineKind::Quote,
+                parse_inline(text.trim_start()),
+            ));
+        } else {
+            // Two trailing spaces or a backslash end the line early.
+            let hard_break = raw.ends_with("  ") || raw.ends_with('\\');
+            paragraph.push(trimmed.trim_end().trim_end_matches('\\'));
+            if hard_break {
+                flush_paragraph(&mut paragraph, &mut lines);
+            }
+        }
+    }
+    flush_paragraph(&mut paragraph, &mut lines);
+    while lines.last().is_some_and(TextLine::is_blank) {
+        lines.pop();
+    }
+    lines
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, lines: &mut Vec<TextLine>) {
+    if !paragraph.is_empty() {
+        lines.push(TextLine::new(
+            LineKind::Text,
+            parse_inline(&paragraph.join(" ")),
+        ));
+        paragraph.clear();
+    }
+}
+
+/// Adds an empty line between blocks, never two in a row.
+fn push_blank(lines: &mut Vec<TextLine>) {
+    if lines.last().is_some_and(|line| !line.is_blank()) {
+        lines.push(TextLine::new(LineKind::Text, Vec::new()));
+    }
+}
+
+fn atx_heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.bytes().take_while(|b| *b == b'#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((level as u8, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn setext_underline(line: &str) -> Option<u8> {
+    let line = line.trim_end();
+    if !line.is_empty() && line.bytes().all(|b| b == b'=') {
+        Some(1)
+    } else if line.len() >= 2 && line.bytes().all(|b| b == b'-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let marks: Vec<u8> = line.bytes().filter(|b| *b != b' ').collect();
+    marks.len() >= 3
+        && matches!(marks[0], b'-' | b'*' | b'_')
+        && marks.iter().all(|b| *b == marks[0])
+}
+
+/// Splits a list item into the marker to show and its text.
+fn list_item(line: &str) -> Option<(String, &str)> {
+    if let Some(text) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))
+    {
+        return Some(("•".to_string(), text));
+    }
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+    let rest = &line[digits..];
+    if (1..=9).contains(&digits)
+        && let Some(text) = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))
+    {
+        return Some((line[..digits + 1].to_string(), text));
+    }
+    None
+}
+
+/// Parses inline Markdown: emphasis, code spans, links, autolinks, inline
+/// HTML tags (dropped) and character references.
+pub(crate) fn parse_inline(text: &str) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    inline_into(text, SpanStyle::default(), &mut spans);
+    spans
+}
+
+fn inline_into(text: &str, style: SpanStyle, out: &mut Vec<TextSpan>) {
+    let mut rest = text;
+    let mut prev: Option<char> = None;
+    while let Some(ch) = rest.chars().next() {
+        let consumed = match ch {
+            '\\' => rest[1..]
+                .chars()
+                .next()
+                .filter(char::is_ascii_punctuation)
+                .map(|escaped| {
+                    push_span(out, &escaped.to_string(), style);
+                    2
+                }),
+            '`' => code_span(rest).map(|(code, len)| {
+                push_span(
+                    out,
+                    code,
+                    SpanStyle {
+                        code: true,
+                        ..style
+                    },
+                );
+                len
+            }),
+            '*' | '_' | '~' => emphasis(rest, prev, style, out),
+            '[' | '!' => link(rest, style, out),
+            '<' => angle(rest, style, out),
+            '&' => entity(rest).map(|(decoded, len)| {
+                push_span(out, &decoded.to_string(), style);
+                len
+            }),
+            _ => None,
+        };
+        let len = consumed.unwrap_or_else(|| {
+            push_span(out, &rest[..ch.len_utf8()], style);
+            ch.len_utf8()
+        });
+        prev = rest[..len].chars().last();
+        rest = &rest[len..];
+    }
+}
+
+/// Appends `text`, merging it into the last span when the style matches.
+fn push_span(out: &mut Vec<TextSpan>, text: &str, style: SpanStyle) {
+    match out.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(text),
+        _ => out.push(TextSpan {
+            text: text.to_string(),
+            style,
+        }),
+    }
+}
+
+fn code_span(text: &str) -> Option<(&str, usize)> {
+    let ticks = text.bytes().take_while(|b| *b == b'`').count();
+    let body = &text[ticks..];
+    let mut offset = 0;
+    while let Some(found) = body[offset..].find('`') {
+        let start = offset + found;
+        let run = body[start..].bytes().take_while(|b| *b == b'`').count();
+        if run == ticks {
+            let code = &body[..start];
+            let code = match code.strip_prefix(' ').and_then(|c| c.strip_suffix(' ')) {
+                Some(inner) if !inner.trim().is_empty() => inner,
+                _ => code,
+            };
+            return Some((code, ticks + start + run));
+        }
+        offset = start + run;
+    }
+    None
+}
+
+/// Parses `*em*`, `**strong**`, `***both***`, their `_` forms and
+/// `~~strike~~` at the start of `text`.
+fn emphasis(
+    text: &str,
+    prev: Option<char>,
+    style: SpanStyle,
+    out: &mut Vec<TextSpan>,
+) -> Option<usize> {
+    let mark = text.as_bytes()[0];
+    let run = text.bytes().take_while(|b| *b == mark).count();
+    let width = if mark == b'~' {
+        (run == 2).then_some(2)?
+    } else {
+        run.min(3)
+    };
+    // Underscores inside words, as in snake_case names, are literal.
+    if mark == b'_' && prev.is_some_and(char::is_alphanumeric) {
+        return None;
+    }
+    let body = &text[width..];
+    if body.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let end = closing_delimiter(body, mark, width)?;
+    let inner = SpanStyle {
+        bold: style.bold || (width >= 2 && mark != b'~'),
+        italic: style.italic || (width != 2 && mark != b'~'),
+        strike: style.strike || mark == b'~',
+        ..style
+    };
+    inline_into(&body[..end], inner, out);
+    Some(width + end + width)
+}
+
+fn closing_delimiter(body: &str, mark: u8, width: usize) -> Option<usize> {
+    let bytes = body.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'`'
+            && let Some((_, len)) = code_span(&body[i..])
+        {
+            i += len;
+            continue;
+        }
+        if bytes[i] != mark {
+            i += 1;
+            continue;
+        }
+        let run = bytes[i..].iter().take_while(|b| **b == mark).count();
+        let after = bytes.get(i + run);
+        if i > 0
+            && run == width
+            && !bytes[i - 1].is_ascii_whitespace()
+            && !(mark == b'_' && after.is_some_and(u8::is_ascii_alphanumeric))
+        {
+            return Some(i);
+        }
+        i += run;
+    }
+    None
+}
+
+/// Parses `[text](url)` and `![alt](url)`; the target follows the text.
+fn link(text: &str, style: SpanStyle, out: &mut Vec<TextSpan>) -> Option<usize> {
+    let start = usize::from(text.starts_with('!'));
+    let label_end = start + matching(&text[start..], b'[', b']')?;
+    let label = &text[start + 1..label_end];
+    let target = &text[label_end + 1..];
+    if !target.starts_with('(') {
+        return None;
+    }
+    let target_end = matching(target, b'(', b')')?;
+    let url = target[1..target_end]
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+
+    let link_style = SpanStyle {
+        link: true,
+        ..style
+    };
+    if label.trim().is_empty() {
+        push_span(out, url, link_style);
+    } else {
+        inline_into(label, link_style, out);
+        if !url.is_empty() && url != label {
+            push_span(out, &format!(" ({url})"), SpanStyle { dim: true, ..style });
+        }
+    }
+    Some(label_end + 1 + target_end + 1)
+}
+
+/// Byte offset of the bracket closing the one `text` starts with.
+fn matching(text: &str, open: u8, close: u8) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes.first() != Some(&open) {
+        return None;
+    }
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b if b == open => depth += 1,
+            b if b == close => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Parses an autolink such as `<https://example.com>`, or drops an inline
+/// HTML tag.
+fn angle(text: &str, style: SpanStyle, out: &mut Vec<TextSpan>) -> Option<usize> {
+    let end = text.find('>')?;
+    let inner = &text[1..end];
+    if inner.contains('<') {
+        return None;
+    }
+    if ["http://", "https://", "mailto:"]
+        .iter()
+        .any(|scheme| inner.starts_with(scheme))
+        && !inner.contains(char::is_whitespace)
+    {
+        push_span(
+            out,
+            inner,
+            SpanStyle {
+                link: true,
+                ..style
+            },
+        );
+        return Some(end + 1);
+    }
+    let name = inner.strip_prefix('/').unwrap_or(inner);
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        .then_some(end + 1)
+}
+
+/// Decodes a character reference such as `&amp;` or `&#39;`.
+fn entity(text: &str) -> Option<(char, usize)> {
+    let end = text.find(';').filter(|end| *end <= 10)?;
+    let name = &text[1..end];
+    let decoded = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ => {
+            let code = match name.strip_prefix('#')? {
+                hex if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16).ok()?,
+                dec => dec.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+    Some((decoded, end + 1))
+}
+
+/// Renders HTML content as plain text.
+///
+/// Block elements and `<br>` break lines, list items get a bullet, link
+/// targets follow the link text, and scripts and styles are dropped.
+pub(crate) fn render_html(content: &str) -> Vec<TextLine> {
+    let mut text = String::new();
+    let mut href: Option<String> = None;
+    let mut pre = false;
+    let mut rest = content;
+
+    while let Some(ch) = rest.chars().next() {
+        if ch == '<'
+            && let Some(end) = rest.find('>')
+        {
+            let tag = &rest[1..end];
+            let closing = tag.starts_with('/');
+            let name: String = tag
+                .trim_start_matches('/')
+                .chars()
+                .take_while(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_ascii_lowercase();
+            rest = &rest[end + 1..];
+            match name.as_str() {
+                "script" | "style" if !closing => {
+                    let close = format!("</{name}");
+                    let skip = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                    rest = &rest[skip..];
+                }
+                "br" | "p" | "div" | "tr" | "table" | "ul" | "ol" | "blockquote" | "h1" | "h2"
+                | "h3" | "h4" | "h5" | "h6" | "hr" => text.push('\n'),
+                "li" if !closing => text.push_str("\n• "),
+                "pre" => {
+                    pre = !closing;
+                    text.push('\n');
+                }
+                "td" | "th" if closing => text.push(' '),
+                "a" if closing => {
+                    if let Some(url) = href.take() {
+                        text.push_str(&format!(" ({url})"));
+                    }
+                }
+                "a" => href = attribute(tag, "href"),
+                _ => {}
+            }
+            continue;
+        }
+        if ch == '&'
+            && let Some((decoded, len)) = entity(rest)
+        {
+            text.push(decoded);
+            rest = &rest[len..];
+            continue;
+        }
+        if ch.is_whitespace() && !pre {
+            if !text.ends_with([' ', '\n']) {
+                text.push(' ');
+            }
+        } else {
+            text.push(ch);
+        }
+        rest = &rest[ch.len_utf8()..];
+    }
+
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let line = line.trim_end();
+        let line = if pre { line } else { line.trim_start() };
+        if line.is_empty() {
+            push_blank(&mut lines);
+        } else {
+            lines.push(TextLine::plain(LineKind::Text, line));
+        }
+    }
+    while lines.last().is_some_and(TextLine::is_blank) {
+        lines.pop();
+    }
+    if lines.first().is_some_and(TextLine::is_blank) {
+        lines.remove(0);
+    }
+    lines
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let lower = tag.to_ascii_lowercase();
+    let start = lower.find(&format!("{name}="))? + name.len() + 1;
+    let value = &tag[start..];
+    let value = match value.chars().next()? {
+        quote @ ('"' | '\'') => value[1..].split(quote).next()?,
+        _ => value.split_whitespace().next()?,
+    };
+    Some(value.to_string())
+}
+
+/// Word-wraps spans to lines of at most `width` characters.
+pub(crate) fn wrap_spans(spans: &[TextSpan], width: usize) -> Vec<Vec<TextSpan>> {
+    let width = width.max(1);
+    let mut lines = vec![Vec::new()];
+    let mut used = 0;
+    for span in spans {
+        for word in span.text.split_inclusive(' ') {
+            let len = word.chars().count();
+            let visible = word.trim_end().chars().count();
+            if used > 0 && used + visible > width {
+                lines.push(Vec::new());
+                used = 0;
+            }
+            let line = lines.last_mut().expect("lines is never empty");
+            push_span(line, word, span.style);
+            used += len;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &TextLine) -> String {
+        line.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_markdown_blocks() {
+        let lines = render_markdown(
+            "# Runbook #\n\nCheck the\n*queue* first.\n\n- one\n  - two\n3. three\n\n> quoted\n\n---\n```\nkubectl get pods\n```",
+        );
+        let kinds: Vec<(LineKind, usize, String)> = lines
+            .iter()
+            .map(|line| (line.kind, line.indent, text(line)))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (LineKind::Heading(1), 0, "Runbook".to_string()),
+                (LineKind::Text, 0, String::new()),
+                (LineKind::Text, 0, "Check the queue first.".to_string()),
+                (LineKind::Text, 0, String::new()),
+                (LineKind::Text, 0, "• one".to_string()),
+                (LineKind::Text, 1, "• two".to_string()),
+                (LineKind::Text, 0, "3. three".to_string()),
+                (LineKind::Text, 0, String::new()),
+                (LineKind::Quote, 0, "quoted".to_string()),
+                (LineKind::Text, 0, String::new()),
+                (LineKind::Rule, 0, String::new()),
+                (LineKind::Code, 0, "kubectl get pods".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markdown_inline_styles() {
+        let spans = parse_inline(
+            "**bold** _it_ snake_case `a*b` ~~gone~~ [docs](https://x.io \"t\") <https://y.io> &lt;b&gt;",
+        );
+        let bold = SpanStyle {
+            bold: true,
+            ..SpanStyle::default()
+        };
+        let italic = SpanStyle {
+            italic: true,
+            ..SpanStyle::default()
+        };
+        let code = SpanStyle {
+            code: true,
+            ..SpanStyle::default()
+        };
+        let strike = SpanStyle {
+            strike: true,
+            ..SpanStyle::default()
+        };
+        let link = SpanStyle {
+            link: true,
+            ..SpanStyle::default()
+        };
+        let dim = SpanStyle {
+            dim: true,
+            ..SpanStyle::default()
+        };
+        let plain = SpanStyle::default();
+        let expected = [
+            ("bold", bold),
+            (" ", plain),
+            ("it", italic),
+            (" snake_case ", plain),
+            ("a*b", code),
+            (" ", plain),
+            ("gone", strike),
+            (" ", plain),
+            ("docs", link),
+            (" (https://x.io)", dim),
+            (" ", plain),
+            ("https://y.io", link),
+            (" <b>", plain),
+        ];
+        let actual: Vec<(&str, SpanStyle)> = spans
+            .iter()
+            .map(|span| (span.text.as_str(), span.style))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_html_is_stripped_to_text() {
+        let lines = render_html(
+            "<h2>On call</h2><p>Page <b>ops</b> &amp; see <a href=\"https://wiki\">the wiki</a>.</p><ul><li>one</li><li>two</li></ul><script>alert(1)</script>",
+        );
+        let texts: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "On call",
+                "",
+                "Page ops & see the wiki (https://wiki).",
+                "",
+                "• one",
+                "• two"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_spans_breaks_between_words() {
+        let spans = parse_inline("alpha **beta** gamma");
+        let wrapped: Vec<String> = wrap_spans(&spans, 11)
+            .iter()
+            .map(|line| line.iter().map(|span| span.text.as_str()).collect())
+            .collect();
+        assert_eq!(wrapped, vec!["alpha beta ", "gamma"]);
+    }
+}
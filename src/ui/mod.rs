@@ -17,9 +17,22 @@
 mod draw;
 mod format;
 mod layout;
+mod mapping;
+mod markdown;
 mod panels;
 
 pub(crate) use draw::draw_ui;
-pub(crate) use format::{DisplayFormat, format_time, get_hash_color, value_to_heatmap_color};
-pub(crate) use layout::{hit_test, visible_panel_rects};
-pub(crate) use panels::calculate_y_bounds;
+pub(crate) use format::{DisplayFormat, color_rgb, format_time, get_hash_color};
+pub(crate) use layout::{
+    VARIABLE_BAR_SEPARATOR, VariableBarEntry, hit_test, screen_layout, variable_bar_area,
+    variable_bar_labels, variable_hit_test, visible_panel_rects,
+};
+pub(crate) use mapping::{GrafanaRegex, MappingResult, SpecialMatch, ValueMapping};
+pub(crate) use markdown::{LineKind, TextSpan, wrap_spans};
+pub(crate) use panels::{
+    Legend, LegendRow, axis_layout, axis_value_ticks, build_legend, calculate_right_y_bounds,
+    calculate_y_bounds, heatmap_color, heatmap_grid, heatmap_labels, interpolate_points,
+    is_heatmap_scheme, is_right_scaled, line_segments, monotone_tangents, non_finite_samples,
+    panel_text, remap_position, right_axis_display, row_header, scale_points, stack_series,
+    threshold_bands, y_axis_display,
+};
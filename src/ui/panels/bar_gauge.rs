@@ -29,17 +29,14 @@ pub(super) fn render_bar_gauge(frame: &mut Frame, area: Rect, p: &PanelState, ap
 
     // Map intermediate valid series
     let mut valid_series: Vec<_> = p
-        .series
-        .iter()
-        .filter(|s| s.visible && s.value.is_some())
+        .reduced_values()
+        .into_iter()
+        .filter_map(|(s, values)| values.first().copied().flatten().map(|v| (s, v)))
         .collect();
 
     // Sort descending safely
-    valid_series.sort_by(|a, b| {
-        let v_a = a.value.unwrap_or(0.0);
-        let v_b = b.value.unwrap_or(0.0);
-        v_b.partial_cmp(&v_a).unwrap_or(std::cmp::Ordering::Equal)
-    });
+    valid_series
+        .sort_by(|(_, v_a), (_, v_b)| v_b.partial_cmp(v_a).unwrap_or(std::cmp::Ordering::Equal));
 
     // Truncate based on area width
     let max_bars = (area.width / 4).saturating_sub(1).max(1) as usize;
@@ -47,13 +44,12 @@ pub(super) fn render_bar_gauge(frame: &mut Frame, area: Rect, p: &PanelState, ap
 
     let mut bars = Vec::with_capacity(valid_series.len());
 
-    for s in valid_series {
-        let v = s.value.unwrap();
+    for (s, v) in valid_series {
         max_label_len = max_label_len.max(s.name.len());
-        let color = p.get_color_for_value(v).unwrap_or(theme.palette[0]);
+        let color = p.value_color(s, Some(v)).unwrap_or(theme.palette[0]);
         let bar = Bar::default()
             .value((v * scale) as u64)
-            .text_value(p.display.format_number(v))
+            .text_value(p.series_display(s).format_value(Some(v)))
             .label(Line::from(s.name.as_str()))
             .style(Style::default().fg(color))
             .value_style(Style::default().fg(theme.text).bg(color));
@@ -23,20 +23,22 @@ use ratatui::{
 pub(super) fn render_gauge(frame: &mut Frame, area: Rect, p: &PanelState, app: &AppState) {
     let theme = &app.theme;
 
-    // Find the latest value from the first visible series
-    let (value, name) = p
-        .series
+    // Find the reduced value of the first visible series
+    let reduced = p.reduced_values();
+    let series = reduced
         .iter()
-        .filter(|s| s.visible)
-        .find_map(|s| s.value.map(|v| (v, s.name.clone())))
-        .unwrap_or((0.0, "No data".to_string()));
+        .find_map(|(s, values)| values.first().copied().flatten().map(|v| (v, *s)));
+    let (value, name) = series.map_or((0.0, "No data"), |(v, s)| (v, s.name.as_str()));
 
     let min = p.min.unwrap_or(0.0);
     let max = p
         .max
         .unwrap_or(if value > 100.0 { value * 1.2 } else { 100.0 });
 
-    let color = p.get_color_for_value(value).unwrap_or(theme.palette[0]);
+    let display = series.map_or(&p.display, |(_, s)| p.series_display(s));
+    let color = series
+        .and_then(|(_, s)| p.value_color(s, Some(value)))
+        .unwrap_or(theme.palette[0]);
 
     let ratio = if max > min {
         ((value - min) / (max - min)).clamp(0.0, 1.0)
@@ -48,7 +50,7 @@ pub(super) fn render_gauge(frame: &mut Frame, area: Rect, p: &PanelState, app: &
         .block(Block::default().borders(Borders::NONE))
         .gauge_style(Style::default().fg(color).bg(Color::DarkGray))
         .ratio(ratio)
-        .label(format!("{} ({})", p.display.format_number(value), name));
+        .label(format!("{} ({})", display.format_value(Some(value)), name));
 
     frame.render_widget(gauge, area);
 }
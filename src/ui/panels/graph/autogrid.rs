@@ -14,14 +14,58 @@
  * limitations under the License.
  */
 
-pub(super) fn calculate_value_grid_ticks(y_bounds: [f64; 2], chart_height: u16) -> Vec<f64> {
-    let min = y_bounds[0];
-    let max = y_bounds[1];
-    if !min.is_finite() || !max.is_finite() || max <= min || chart_height < 4 {
+use crate::app::GraphScale;
+
+pub(super) fn calculate_value_grid_ticks(
+    y_bounds: [f64; 2],
+    chart_height: u16,
+    scale: GraphScale,
+) -> Vec<f64> {
+    if chart_height < 4 {
         return Vec::new();
     }
 
     let target_lines = (usize::from(chart_height) / 6).clamp(2, 4);
+    axis_value_ticks(y_bounds, target_lines, scale)
+}
+
+/// Interior ticks of a value axis, as axis positions of `scale`. Log and
+/// symlog axes tick on powers of their base, thinned to about
+/// `target_lines`; axes spanning less than one power fall back to round
+/// values.
+pub(crate) fn axis_value_ticks(
+    y_bounds: [f64; 2],
+    target_lines: usize,
+    scale: GraphScale,
+) -> Vec<f64> {
+    let [min, max] = y_bounds;
+    if !min.is_finite() || !max.is_finite() || max <= min {
+        return Vec::new();
+    }
+    if scale == GraphScale::Linear {
+        return linear_ticks(min, max, target_lines);
+    }
+
+    let ticks: Vec<f64> = power_ticks(scale, min, max)
+        .into_iter()
+        .filter_map(|value| scale.position_of(value))
+        .filter(|tick| *tick > min && *tick < max)
+        .collect();
+    if ticks.is_empty() {
+        let value_min = scale.value_at(min);
+        let value_max = scale.value_at(max);
+        return linear_ticks(value_min, value_max, target_lines)
+            .into_iter()
+            .filter_map(|value| scale.position_of(value))
+            .filter(|tick| *tick > min && *tick < max)
+            .collect();
+    }
+
+    let every = ticks.len().div_ceil(target_lines.max(1));
+    ticks.into_iter().step_by(every).collect()
+}
+
+fn linear_ticks(min: f64, max: f64, target_lines: usize) -> Vec<f64> {
     let step = nice_grid_step(max - min, target_lines);
     if step <= 0.0 || !step.is_finite() {
         return Vec::new();
@@ -38,6 +82,42 @@ pub(super) fn calculate_value_grid_ticks(y_bounds: [f64; 2], chart_height: u16)
     ticks
 }
 
+/// Powers of the scale base, in ascending order, that may fall between the
+/// axis positions `min` and `max`.
+fn power_ticks(scale: GraphScale, min: f64, max: f64) -> Vec<f64> {
+    // Exponents are kept to powers that are finite floats, so far-off or
+    // overflowing bounds cannot ask for billions of ticks.
+    let clamp_exponent = |base: f64, exponent: f64| {
+        let limit = f64::MAX.log(base).floor();
+        exponent.max(-limit).min(limit) as i32
+    };
+    match scale {
+        GraphScale::Linear => Vec::new(),
+        GraphScale::Log { base } => (clamp_exponent(base, min.ceil())
+            ..=clamp_exponent(base, max.floor()))
+            .map(|exponent| base.powi(exponent))
+            .collect(),
+        GraphScale::Symlog {
+            base,
+            linear_threshold,
+        } => {
+            let largest = scale.value_at(min).abs().max(scale.value_at(max).abs());
+            let first = clamp_exponent(base, (linear_threshold.ln() / base.ln()).floor());
+            let last = clamp_exponent(base, (largest.ln() / base.ln()).ceil());
+            let powers: Vec<f64> = (first..=last.max(first))
+                .map(|exponent| base.powi(exponent))
+                .collect();
+            powers
+                .iter()
+                .rev()
+                .map(|power| -power)
+                .chain(std::iter::once(0.0))
+                .chain(powers.iter().copied())
+                .collect()
+        }
+    }
+}
+
 fn nice_grid_step(range: f64, target_lines: usize) -> f64 {
     if range <= 0.0 || !range.is_finite() || target_lines == 0 {
         return 0.0;
@@ -191,23 +271,70 @@ mod tests {
 
     #[test]
     fn test_calculate_value_grid_ticks_round_values() {
-        let ticks = calculate_value_grid_ticks([329.0, 1287.0], 20);
+        let ticks = calculate_value_grid_ticks([329.0, 1287.0], 20, GraphScale::Linear);
         assert_eq!(ticks, vec![500.0, 1000.0]);
     }
 
     #[test]
     fn test_calculate_value_grid_ticks_excludes_boundaries() {
-        let ticks = calculate_value_grid_ticks([0.0, 100.0], 20);
+        let ticks = calculate_value_grid_ticks([0.0, 100.0], 20, GraphScale::Linear);
         assert!(!ticks.contains(&0.0));
         assert!(!ticks.contains(&100.0));
     }
 
     #[test]
     fn test_calculate_value_grid_ticks_invalid_ranges() {
-        assert!(calculate_value_grid_ticks([1.0, 1.0], 20).is_empty());
-        assert!(calculate_value_grid_ticks([2.0, 1.0], 20).is_empty());
-        assert!(calculate_value_grid_ticks([f64::NAN, 1.0], 20).is_empty());
-        assert!(calculate_value_grid_ticks([0.0, 1.0], 3).is_empty());
+        let linear = GraphScale::Linear;
+        assert!(calculate_value_grid_ticks([1.0, 1.0], 20, linear).is_empty());
+        assert!(calculate_value_grid_ticks([2.0, 1.0], 20, linear).is_empty());
+        assert!(calculate_value_grid_ticks([f64::NAN, 1.0], 20, linear).is_empty());
+        assert!(calculate_value_grid_ticks([0.0, 1.0], 3, linear).is_empty());
+    }
+
+    #[test]
+    fn test_axis_value_ticks_on_log_and_symlog_scales() {
+        let log10 = GraphScale::Log { base: 10.0 };
+        assert_eq!(
+            axis_value_ticks([-2.5, 3.5], 8, log10),
+            vec![-2.0, -1.0, 0.0, 1.0, 2.0, 3.0]
+        );
+        // Thinned to every other decade.
+        assert_eq!(
+            axis_value_ticks([-2.5, 3.5], 3, log10),
+            vec![-2.0, 0.0, 2.0]
+        );
+        // Within a single decade, ticks fall back to round values.
+        let ticks: Vec<f64> = axis_value_ticks([0.35, 0.95], 4, log10)
+            .into_iter()
+            .map(|tick| log10.value_at(tick).round())
+            .collect();
+        assert_eq!(ticks, vec![4.0, 6.0, 8.0]);
+
+        let symlog = GraphScale::Symlog {
+            base: 10.0,
+            linear_threshold: 1.0,
+        };
+        let values: Vec<f64> = axis_value_ticks([-2.5, 2.5], 8, symlog)
+            .into_iter()
+            .map(|tick| symlog.value_at(tick).round())
+            .collect();
+        assert_eq!(values, vec![-100.0, -10.0, -1.0, 0.0, 1.0, 10.0, 100.0]);
+    }
+
+    #[test]
+    fn test_axis_value_ticks_with_overflowing_bounds() {
+        let symlog = GraphScale::Symlog {
+            base: 10.0,
+            linear_threshold: 1.0,
+        };
+        // The top of the axis lies beyond the largest finite float.
+        assert!(symlog.value_at(400.0).is_infinite());
+        let ticks = axis_value_ticks([0.0, 400.0], 4, symlog);
+        assert!(!ticks.is_empty() && ticks.len() <= 4);
+
+        let log10 = GraphScale::Log { base: 10.0 };
+        let ticks = axis_value_ticks([-1e12, 1e12], 4, log10);
+        assert!(!ticks.is_empty() && ticks.len() <= 4);
     }
 
     #[test]
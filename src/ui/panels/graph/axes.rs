@@ -0,0 +1,148 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::stacking::y_axis_display;
+use crate::app::{GraphAxisPlacement, PanelState};
+use crate::ui::DisplayFormat;
+
+/// Which y-axes a graph panel draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AxisLayout {
+    /// Whether the left axis shows labels.
+    pub(crate) left: bool,
+    /// Whether an axis is drawn right of the plot.
+    pub(crate) right: bool,
+    /// Whether right-axis series have bounds of their own. When every series
+    /// is on the right, the right axis is the only one and shares the bounds.
+    pub(crate) dual: bool,
+}
+
+/// Axis placement of series `index`: its `custom.axisPlacement` override or
+/// the panel setting.
+pub(crate) fn series_axis_placement(p: &PanelState, index: usize) -> GraphAxisPlacement {
+    p.series[index]
+        .overrides
+        .axis_placement
+        .unwrap_or_else(|| p.graph_options().axis_placement)
+}
+
+/// Axes needed by the visible series, or by the panel setting when no
+/// series is visible.
+pub(crate) fn axis_layout(p: &PanelState) -> AxisLayout {
+    let mut placements: Vec<_> = (0..p.series.len())
+        .filter(|&index| p.series[index].visible)
+        .map(|index| series_axis_placement(p, index))
+        .collect();
+    if placements.is_empty() {
+        placements.push(p.graph_options().axis_placement);
+    }
+
+    let right = placements.contains(&GraphAxisPlacement::Right);
+    AxisLayout {
+        left: placements.contains(&GraphAxisPlacement::Visible),
+        right,
+        dual: right
+            && placements
+                .iter()
+                .any(|placement| *placement != GraphAxisPlacement::Right),
+    }
+}
+
+/// Whether series `index` is scaled against the bounds of the right axis.
+pub(crate) fn is_right_scaled(p: &PanelState, layout: AxisLayout, index: usize) -> bool {
+    layout.dual && series_axis_placement(p, index) == GraphAxisPlacement::Right
+}
+
+/// Format of right-axis labels: the unit of the first visible series on the
+/// right, so a latency axis shows seconds next to a request rate axis.
+pub(crate) fn right_axis_display(p: &PanelState) -> DisplayFormat {
+    (0..p.series.len())
+        .filter(|&index| {
+            p.series[index].visible && series_axis_placement(p, index) == GraphAxisPlacement::Right
+        })
+        .find_map(|index| p.series[index].overrides.display.clone())
+        .unwrap_or_else(|| y_axis_display(p))
+}
+
+/// Moves axis position `value` of the `from` bounds to the same height
+/// within the `to` bounds.
+pub(crate) fn remap_position(value: f64, from: [f64; 2], to: [f64; 2]) -> f64 {
+    if from[1] <= from[0] {
+        return to[0];
+    }
+    to[0] + (value - from[0]) / (from[1] - from[0]) * (to[1] - to[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::SeriesView;
+
+    fn panel(placements: &[Option<GraphAxisPlacement>]) -> PanelState {
+        let mut p = PanelState {
+            series: (0..placements.len())
+                .map(|index| SeriesView {
+                    name: format!("s{index}"),
+                    visible: true,
+                    ..Default::default()
+                })
+                .collect(),
+            ..crate::ui::panels::test_panel("axes")
+        };
+        for (series, placement) in p.series.iter_mut().zip(placements) {
+            series.overrides.axis_placement = *placement;
+        }
+        p
+    }
+
+    #[test]
+    fn test_axis_layout_splits_series_between_axes() {
+        let right = Some(GraphAxisPlacement::Right);
+        let mut p = panel(&[None, right]);
+        p.series[1].overrides.display = Some(DisplayFormat {
+            unit: Some("s".to_string()),
+            ..Default::default()
+        });
+        let layout = axis_layout(&p);
+        assert_eq!(
+            layout,
+            AxisLayout {
+                left: true,
+                right: true,
+                dual: true,
+            }
+        );
+        assert!(!is_right_scaled(&p, layout, 0));
+        assert!(is_right_scaled(&p, layout, 1));
+        assert_eq!(right_axis_display(&p).unit.as_deref(), Some("s"));
+
+        // Hiding the left series leaves a single axis on the right.
+        p.series[0].visible = false;
+        let layout = axis_layout(&p);
+        assert!(!layout.left && layout.right && !layout.dual);
+        assert!(!is_right_scaled(&p, layout, 1));
+
+        let hidden = panel(&[Some(GraphAxisPlacement::Hidden), right]);
+        let layout = axis_layout(&hidden);
+        assert!(!layout.left && layout.right && layout.dual);
+    }
+
+    #[test]
+    fn test_remap_position_keeps_relative_height() {
+        assert_eq!(remap_position(5.0, [0.0, 10.0], [100.0, 200.0]), 150.0);
+        assert_eq!(remap_position(5.0, [1.0, 1.0], [100.0, 200.0]), 100.0);
+    }
+}
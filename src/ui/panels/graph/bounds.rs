@@ -14,41 +14,88 @@
  * limitations under the License.
  */
 
-use crate::app::{PanelState, ThresholdMode, YAxisMode};
-
+use super::axes::{axis_layout, is_right_scaled};
+use super::stacking::{is_percent_stacked, series_points, stack_series};
+use crate::app::{GraphScale, PanelState, ThresholdMode, YAxisMode};
+
+/// Y-axis bounds fitting the drawn series, stacked totals included, and the
+/// panel thresholds. Bounds are axis positions of the panel scale, so they
+/// are exponents on log axes. Series with a separate right axis are left
+/// out; see [`calculate_right_y_bounds`].
 pub(crate) fn calculate_y_bounds(p: &PanelState) -> [f64; 2] {
+    axis_bounds(p, false)
+}
+
+/// Bounds of the right y-axis, when series placed on the right are scaled
+/// apart from the others.
+pub(crate) fn calculate_right_y_bounds(p: &PanelState) -> Option<[f64; 2]> {
+    axis_layout(p).dual.then(|| axis_bounds(p, true))
+}
+
+fn axis_bounds(p: &PanelState, right: bool) -> [f64; 2] {
+    let scale = p.graph_options().scale;
+    let layout = axis_layout(p);
     let mut min = f64::INFINITY;
     let mut max = f64::NEG_INFINITY;
     let mut has_data = false;
 
-    for s in &p.series {
-        if !s.visible {
+    let stacked = stack_series(p);
+    for (index, s) in p.series.iter().enumerate() {
+        if !s.visible || is_right_scaled(p, layout, index) != right {
             continue;
         }
-        for &(_, v) in &s.points {
-            observe_value(v, &mut min, &mut max, &mut has_data);
+        for &(_, v) in series_points(p, stacked.as_deref(), index) {
+            observe_value(scale.position_of(v), &mut min, &mut max, &mut has_data);
         }
     }
 
-    observe_thresholds(p, &mut min, &mut max, &mut has_data);
+    // Thresholds, min and max belong to the left axis.
+    if right {
+        if !has_data {
+            return fallback_bounds(None, None);
+        }
+        return padded_bounds(p, scale, [min, max], None, None);
+    }
 
-    let explicit_min = p.min.filter(|value| value.is_finite());
-    let explicit_max = p.max.filter(|value| value.is_finite());
+    observe_thresholds(p, scale, &mut min, &mut max, &mut has_data);
+
+    let mut explicit_min = p.min.and_then(|value| scale.position_of(value));
+    let mut explicit_max = p.max.and_then(|value| scale.position_of(value));
+    // Percent stacks fill the 0-100% range unless negative values go below it.
+    if is_percent_stacked(p) {
+        explicit_min = explicit_min.or(scale.position_of(0.0).filter(|zero| min >= *zero));
+        explicit_max = explicit_max.or(scale.position_of(1.0).filter(|one| max <= *one));
+    }
 
     if !has_data {
         return fallback_bounds(explicit_min, explicit_max);
     }
 
+    padded_bounds(p, scale, [min, max], explicit_min, explicit_max)
+}
+
+/// Widens `[min, max]` to the y-axis mode and explicit bounds, with some
+/// padding on the automatic sides.
+fn padded_bounds(
+    p: &PanelState,
+    scale: GraphScale,
+    [mut min, mut max]: [f64; 2],
+    explicit_min: Option<f64>,
+    explicit_max: Option<f64>,
+) -> [f64; 2] {
     if min == max {
         min -= 1.0;
         max += 1.0;
     }
 
-    if p.y_axis_mode == YAxisMode::ZeroBased {
-        if min > 0.0 {
-            min = 0.0;
-        } else if max < 0.0 {
-            max = 0.0;
+    // Log axes cannot show zero.
+    if p.y_axis_mode == YAxisMode::ZeroBased
+        && let Some(zero) = scale.position_of(0.0)
+    {
+        if min > zero {
+            min = zero;
+        } else if max < zero {
+            max = zero;
         }
     }
 
@@ -79,7 +126,21 @@ pub(crate) fn calculate_y_bounds(p: &PanelState) -> [f64; 2] {
     ]
 }
 
-fn observe_thresholds(p: &PanelState, min: &mut f64, max: &mut f64, has_data: &mut bool) {
+/// `points` as axis positions of `scale`, without the values it cannot show.
+pub(crate) fn scale_points(points: &[(f64, f64)], scale: GraphScale) -> Vec<(f64, f64)> {
+    points
+        .iter()
+        .filter_map(|&(x, y)| Some((x, scale.position_of(y)?)))
+        .collect()
+}
+
+fn observe_thresholds(
+    p: &PanelState,
+    scale: GraphScale,
+    min: &mut f64,
+    max: &mut f64,
+    has_data: &mut bool,
+) {
     let Some(thresholds) = &p.thresholds else {
         return;
     };
@@ -93,14 +154,14 @@ fn observe_thresholds(p: &PanelState, min: &mut f64, max: &mut f64, has_data: &m
                 min + (step / 100.0) * (max - min)
             }
         };
-        observe_value(value, min, max, has_data);
+        observe_value(scale.position_of(value), min, max, has_data);
     }
 }
 
-fn observe_value(value: f64, min: &mut f64, max: &mut f64, has_data: &mut bool) {
-    if !value.is_finite() {
+fn observe_value(value: Option<f64>, min: &mut f64, max: &mut f64, has_data: &mut bool) {
+    let Some(value) = value.filter(|value| value.is_finite()) else {
         return;
-    }
+    };
 
     *min = min.min(value);
     *max = max.max(value);
@@ -120,30 +181,13 @@ fn fallback_bounds(explicit_min: Option<f64>, explicit_max: Option<f64>) -> [f64
 mod tests {
     use super::*;
     use crate::app::{
-        GraphOptions, PanelOptions, SeriesView, ThresholdMode, ThresholdStep, Thresholds,
+        GraphOptions, GraphStackingMode, PanelOptions, SeriesView, ThresholdMode, ThresholdStep,
+        Thresholds,
     };
     use ratatui::style::Color;
 
     fn create_test_panel() -> PanelState {
-        PanelState {
-            title: "test".to_string(),
-            exprs: vec![],
-            legends: vec![],
-            query_modes: vec![],
-            series: vec![],
-            last_error: None,
-            last_url: None,
-            last_samples: 0,
-            grid: None,
-            y_axis_mode: YAxisMode::Auto,
-            panel_type: crate::app::PanelType::Graph,
-            thresholds: None,
-            min: None,
-            max: None,
-            autogrid: None,
-            display: crate::ui::DisplayFormat::default(),
-            options: PanelOptions::Graph(GraphOptions::default()),
-        }
+        crate::ui::panels::test_panel("test")
     }
 
     #[test]
@@ -151,9 +195,9 @@ mod tests {
         let mut p = create_test_panel();
         p.series.push(SeriesView {
             name: "test".to_string(),
-            value: None,
             points: vec![(0.0, 10.0), (1.0, 20.0)],
             visible: true,
+            ..Default::default()
         });
 
         let bounds = calculate_y_bounds(&p);
@@ -166,9 +210,9 @@ mod tests {
         let mut p = create_test_panel();
         p.series.push(SeriesView {
             name: "test".to_string(),
-            value: None,
             points: vec![(0.0, 10.0), (1.0, f64::NAN), (2.0, 20.0)],
             visible: true,
+            ..Default::default()
         });
 
         let bounds = calculate_y_bounds(&p);
@@ -181,9 +225,9 @@ mod tests {
         let mut p = create_test_panel();
         p.series.push(SeriesView {
             name: "test".to_string(),
-            value: None,
             points: vec![(0.0, 10.0), (1.0, f64::INFINITY), (2.0, 20.0)],
             visible: true,
+            ..Default::default()
         });
 
         let bounds = calculate_y_bounds(&p);
@@ -197,9 +241,9 @@ mod tests {
         p.y_axis_mode = YAxisMode::ZeroBased;
         p.series.push(SeriesView {
             name: "test".to_string(),
-            value: None,
             points: vec![(0.0, 10.0), (1.0, 20.0)],
             visible: true,
+            ..Default::default()
         });
 
         let bounds = calculate_y_bounds(&p);
@@ -215,9 +259,9 @@ mod tests {
         p.min = Some(0.0);
         p.series.push(SeriesView {
             name: "requests".to_string(),
-            value: None,
             points: vec![(0.0, 4.5), (1.0, 11_200.0)],
             visible: true,
+            ..Default::default()
         });
 
         let bounds = calculate_y_bounds(&p);
@@ -226,15 +270,62 @@ mod tests {
         assert!(bounds[1] > 11_200.0);
     }
 
+    #[test]
+    fn test_calculate_y_bounds_fits_stacked_totals() {
+        let mut p = create_test_panel();
+        p.options = PanelOptions::Graph(GraphOptions {
+            stacking: GraphStackingMode::Normal,
+            ..GraphOptions::default()
+        });
+        for name in ["a", "b"] {
+            p.series.push(SeriesView {
+                name: name.to_string(),
+                points: vec![(0.0, 10.0), (1.0, 20.0)],
+                visible: true,
+                ..Default::default()
+            });
+        }
+
+        let bounds = calculate_y_bounds(&p);
+        assert!(bounds[1] > 40.0);
+
+        p.options = PanelOptions::Graph(GraphOptions {
+            stacking: GraphStackingMode::Percent,
+            ..GraphOptions::default()
+        });
+        assert_eq!(calculate_y_bounds(&p), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_calculate_y_bounds_uses_log_axis_positions() {
+        let mut p = create_test_panel();
+        p.y_axis_mode = YAxisMode::ZeroBased;
+        p.options = PanelOptions::Graph(GraphOptions {
+            scale: GraphScale::Log { base: 10.0 },
+            ..GraphOptions::default()
+        });
+        p.series.push(SeriesView {
+            name: "latency".to_string(),
+            points: vec![(0.0, 0.0), (1.0, 0.01), (2.0, 1000.0)],
+            visible: true,
+            ..Default::default()
+        });
+
+        // Zero cannot be placed: the axis spans 0.01 to 1000 plus padding.
+        let bounds = calculate_y_bounds(&p);
+        assert!((bounds[0] - -2.25).abs() < 1e-9);
+        assert!((bounds[1] - 3.25).abs() < 1e-9);
+    }
+
     #[test]
     fn test_calculate_y_bounds_includes_threshold_lines() {
         let mut p = create_test_panel();
         p.min = Some(0.0);
         p.series.push(SeriesView {
             name: "latency".to_string(),
-            value: None,
             points: vec![(0.0, 0.5), (1.0, 1.0)],
             visible: true,
+            ..Default::default()
         });
         p.thresholds = Some(Thresholds {
             mode: ThresholdMode::Absolute,
@@ -0,0 +1,156 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::GraphLineInterpolation;
+
+/// Points drawn between two samples of a smooth line in the terminal.
+const SMOOTH_SUBDIVISIONS: usize = 8;
+
+/// The polyline drawing `points` with `interpolation`: steps get a corner
+/// point between samples, and smooth lines are sampled along a monotone
+/// cubic. The x positions only depend on those of `points`, so a stack base
+/// interpolated the same way stays parallel to its series.
+pub(crate) fn interpolate_points(
+    points: &[(f64, f64)],
+    interpolation: GraphLineInterpolation,
+) -> Vec<(f64, f64)> {
+    let Some(&first) = points.first() else {
+        return Vec::new();
+    };
+
+    let mut out = vec![first];
+    match interpolation {
+        GraphLineInterpolation::Linear => out.extend_from_slice(&points[1..]),
+        GraphLineInterpolation::StepAfter => {
+            for pair in points.windows(2) {
+                out.push((pair[1].0, pair[0].1));
+                out.push(pair[1]);
+            }
+        }
+        GraphLineInterpolation::StepBefore => {
+            for pair in points.windows(2) {
+                out.push((pair[0].0, pair[1].1));
+                out.push(pair[1]);
+            }
+        }
+        GraphLineInterpolation::Smooth => {
+            let tangents = monotone_tangents(points);
+            for (index, pair) in points.windows(2).enumerate() {
+                let [(x0, y0), (x1, y1)] = [pair[0], pair[1]];
+                let width = x1 - x0;
+                let (m0, m1) = (tangents[index] * width, tangents[index + 1] * width);
+                for step in 1..SMOOTH_SUBDIVISIONS {
+                    let t = step as f64 / SMOOTH_SUBDIVISIONS as f64;
+                    let (t2, t3) = (t * t, t * t * t);
+                    let y = (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                        + (t3 - 2.0 * t2 + t) * m0
+                        + (3.0 * t2 - 2.0 * t3) * y1
+                        + (t3 - t2) * m1;
+                    out.push((x0 + t * width, y));
+                }
+                out.push(pair[1]);
+            }
+        }
+    }
+    out
+}
+
+/// Slopes at each of `points` of a cubic that stays monotone between
+/// neighbouring points, so smoothing never overshoots a peak: zero at local
+/// extremes and the harmonic mean of the neighbouring slopes elsewhere.
+pub(crate) fn monotone_tangents(points: &[(f64, f64)]) -> Vec<f64> {
+    let slopes: Vec<f64> = points
+        .windows(2)
+        .map(|pair| {
+            let width = pair[1].0 - pair[0].0;
+            if width > 0.0 {
+                (pair[1].1 - pair[0].1) / width
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let (Some(&first), Some(&last)) = (slopes.first(), slopes.last()) else {
+        return vec![0.0; points.len()];
+    };
+
+    let mut tangents = vec![first];
+    tangents.extend(slopes.windows(2).map(|pair| {
+        let (before, after) = (pair[0], pair[1]);
+        if before * after <= 0.0 {
+            0.0
+        } else {
+            2.0 * before * after / (before + after)
+        }
+    }));
+    tangents.push(last);
+    tangents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_points_draws_staircases() {
+        let points = [(0.0, 1.0), (10.0, 3.0), (20.0, 2.0)];
+        assert_eq!(
+            interpolate_points(&points, GraphLineInterpolation::StepAfter),
+            vec![
+                (0.0, 1.0),
+                (10.0, 1.0),
+                (10.0, 3.0),
+                (20.0, 3.0),
+                (20.0, 2.0)
+            ]
+        );
+        assert_eq!(
+            interpolate_points(&points, GraphLineInterpolation::StepBefore),
+            vec![
+                (0.0, 1.0),
+                (0.0, 3.0),
+                (10.0, 3.0),
+                (10.0, 2.0),
+                (20.0, 2.0)
+            ]
+        );
+        assert_eq!(
+            interpolate_points(&points, GraphLineInterpolation::Linear),
+            points.to_vec()
+        );
+        assert!(interpolate_points(&[], GraphLineInterpolation::Smooth).is_empty());
+    }
+
+    #[test]
+    fn test_smooth_interpolation_passes_samples_without_overshooting() {
+        let points = [(0.0, 0.0), (10.0, 10.0), (20.0, 10.0), (30.0, 0.0)];
+        assert_eq!(monotone_tangents(&points), vec![1.0, 0.0, 0.0, -1.0]);
+
+        let smooth = interpolate_points(&points, GraphLineInterpolation::Smooth);
+        assert_eq!(smooth.len(), 3 * SMOOTH_SUBDIVISIONS + 1);
+        for point in points {
+            assert!(smooth.contains(&point));
+        }
+        assert!(smooth.iter().all(|(_, y)| (0.0..=10.0).contains(y)));
+        // The plateau stays flat instead of bulging above the samples.
+        assert!(
+            smooth
+                .iter()
+                .filter(|(x, _)| (10.0..=20.0).contains(x))
+                .all(|(_, y)| *y == 10.0)
+        );
+    }
+}
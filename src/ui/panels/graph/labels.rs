@@ -14,7 +14,9 @@
  * limitations under the License.
  */
 
+use super::axes::remap_position;
 use super::overlay::is_blank_cell;
+use crate::app::GraphScale;
 use crate::ui::format::{DisplayFormat, format_axis_time};
 use ratatui::prelude::*;
 
@@ -37,14 +39,92 @@ pub(super) struct YLabelContext<'a> {
     pub(super) autogrid_ticks: &'a [f64],
     pub(super) threshold_labels: &'a [(f64, Color)],
     pub(super) display: &'a DisplayFormat,
+    /// Scale of the axis; ticks are its axis positions.
+    pub(super) scale: GraphScale,
     pub(super) color: Color,
 }
 
+/// A y-axis drawn right of the plot, for series with bounds of their own.
+pub(super) struct RightYAxis<'a> {
+    /// Bounds of the plot, which the autogrid ticks are positions of.
+    pub(super) y_bounds: [f64; 2],
+    /// Bounds the right axis labels.
+    pub(super) bounds: [f64; 2],
+    pub(super) autogrid_ticks: &'a [f64],
+    pub(super) display: &'a DisplayFormat,
+    pub(super) scale: GraphScale,
+    pub(super) text_color: Color,
+    pub(super) grid_color: Color,
+}
+
+impl RightYAxis<'_> {
+    /// Labels as plot positions: the bounds at both ends, and the right-axis
+    /// values level with the autogrid lines in between.
+    fn labels(&self) -> Vec<(f64, String, Color)> {
+        let format = |position| self.display.format_number(self.scale.value_at(position));
+        let mut labels = vec![
+            (self.y_bounds[0], format(self.bounds[0]), self.text_color),
+            (self.y_bounds[1], format(self.bounds[1]), self.text_color),
+        ];
+        labels.extend(self.autogrid_ticks.iter().map(|tick| {
+            let position = remap_position(*tick, self.y_bounds, self.bounds);
+            (*tick, format(position), self.grid_color)
+        }));
+        labels
+    }
+
+    /// Columns taken by the axis line and its widest label.
+    pub(super) fn width(&self) -> u16 {
+        self.labels()
+            .iter()
+            .map(|(_, label, _)| label.chars().count() as u16)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// Draws the axis line at column `left`, joined to the x-axis on the
+    /// bottom row of `plot`, and the labels after it, cut to `width` columns.
+    pub(super) fn render(&self, frame: &mut Frame, left: u16, width: u16, plot: PlotBounds) {
+        if width == 0 || plot.bottom <= plot.top {
+            return;
+        }
+
+        let line_style = Style::default().fg(Color::Gray);
+        let buf = frame.buffer_mut();
+        for y in plot.top..=plot.bottom {
+            let symbol = if y == plot.bottom {
+                ratatui::symbols::line::BOTTOM_RIGHT
+            } else {
+                ratatui::symbols::line::VERTICAL
+            };
+            if let Some(cell) = buf.cell_mut((left, y)) {
+                cell.set_symbol(symbol).set_style(line_style);
+            }
+        }
+
+        for (position, label, color) in self.labels() {
+            let y = if position == self.y_bounds[0] {
+                Some(plot.bottom - 1)
+            } else if position == self.y_bounds[1] {
+                Some(plot.top)
+            } else {
+                value_to_y_label_row(position, self.y_bounds, plot)
+            };
+            if let Some(y) = y {
+                let label: String = label.chars().take(usize::from(width - 1)).collect();
+                write_label(frame, left + 1, y, &label, color, false);
+            }
+        }
+    }
+}
+
 pub(super) fn y_label_width(
     axis_labels: &[Span<'_>],
     autogrid_ticks: &[f64],
     threshold_labels: &[(f64, Color)],
     display: &DisplayFormat,
+    scale: GraphScale,
 ) -> u16 {
     // Reserve space using the same unit formatter that will draw the labels;
     // otherwise bytes/percent suffixes can clip or push into the plot area.
@@ -55,12 +135,12 @@ pub(super) fn y_label_width(
         .unwrap_or(0);
     let grid_width = autogrid_ticks
         .iter()
-        .map(|tick| display.format_number(*tick).len() as u16)
+        .map(|tick| display.format_number(scale.value_at(*tick)).len() as u16)
         .max()
         .unwrap_or(0);
     let threshold_width = threshold_labels
         .iter()
-        .map(|(tick, _)| display.format_number(*tick).len() as u16)
+        .map(|(tick, _)| display.format_number(scale.value_at(*tick)).len() as u16)
         .max()
         .unwrap_or(0);
 
@@ -84,7 +164,7 @@ pub(super) fn render_intermediate_y_labels(
                 label_area.left,
                 y,
                 label_area.width,
-                &labels.display.format_number(*tick),
+                &labels.display.format_number(labels.scale.value_at(*tick)),
                 labels.color,
             );
         }
@@ -97,7 +177,7 @@ pub(super) fn render_intermediate_y_labels(
                 label_area.left,
                 y,
                 label_area.width,
-                &labels.display.format_number(*tick),
+                &labels.display.format_number(labels.scale.value_at(*tick)),
                 *color,
             );
         }
@@ -207,7 +287,7 @@ fn centered_label_start(center: u16, label_width: u16, min_x: u16, max_x: u16) -
     // For even-length labels, bias one cell right so the visual midpoint better matches
     // the target chart column instead of consistently leaning left.
     let mut start_x = center.checked_sub(half_width)?;
-    if label_width % 2 == 0 {
+    if label_width.is_multiple_of(2) {
         start_x = start_x.saturating_add(1);
     }
     let end_x_exclusive = start_x.saturating_add(label_width);
@@ -0,0 +1,276 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::{AppState, LegendDisplayMode, LegendPlacement, PanelState};
+use crate::theme::Theme;
+use ratatui::{
+    prelude::*,
+    widgets::{Cell, Paragraph, Row, Table, Wrap},
+};
+use std::cmp::Ordering;
+
+/// Graph legend contents, shared by the TUI and exports so both show the
+/// same rows, columns and order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Legend {
+    pub(crate) mode: LegendDisplayMode,
+    pub(crate) placement: LegendPlacement,
+    /// Value column headers: the calc labels, or `Value` for the cursor or
+    /// latest value when the legend has no calcs.
+    pub(crate) columns: Vec<String>,
+    /// Whether the columns are legend calcs rather than the current value.
+    pub(crate) calcs: bool,
+    pub(crate) rows: Vec<LegendRow>,
+    /// First row shown by vertical legends.
+    pub(crate) scroll: usize,
+}
+
+/// A series in the legend.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LegendRow {
+    pub(crate) name: String,
+    pub(crate) color: Color,
+    /// Whether the series is drawn; hidden series are shown dimmed.
+    pub(crate) visible: bool,
+    /// Value of each column, formatted with the series display format.
+    pub(crate) values: Vec<Option<String>>,
+}
+
+impl Legend {
+    /// Whether rows are laid out one per line rather than wrapped inline.
+    pub(crate) fn is_vertical(&self) -> bool {
+        self.mode == LegendDisplayMode::Table || self.placement == LegendPlacement::Right
+    }
+
+    /// Rows from the scroll offset on, keeping `visible` rows on screen.
+    pub(crate) fn scrolled_rows(&self, visible: usize) -> &[LegendRow] {
+        let start = self.scroll.min(self.rows.len().saturating_sub(visible));
+        &self.rows[start..]
+    }
+
+    /// Text of a row in list mode, e.g. `cpu (42%)` or `cpu  Min: 1  Max: 9`.
+    pub(crate) fn list_label(&self, row: &LegendRow) -> String {
+        let mut label = row.name.clone();
+        if self.calcs {
+            for (column, value) in self.columns.iter().zip(&row.values) {
+                label.push_str(&format!("  {column}: {}", value.as_deref().unwrap_or("-")));
+            }
+        } else if let Some(Some(value)) = row.values.first() {
+            label.push_str(&format!(" ({value})"));
+        }
+        label
+    }
+}
+
+/// Builds the legend of a graph panel from its `options.legend`.
+pub(crate) fn build_legend(p: &PanelState, app: &AppState) -> Legend {
+    let options = p.graph_options().legend;
+    let cursor_values = app.cursor_values(p);
+    let columns = if options.calcs.is_empty() {
+        vec!["Value".to_string()]
+    } else {
+        options
+            .calcs
+            .iter()
+            .map(|reducer| reducer.label())
+            .collect()
+    };
+
+    let mut rows: Vec<(LegendRow, Vec<Option<f64>>)> = p
+        .series
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.overrides.hide_from_legend)
+        .map(|(i, s)| {
+            let values: Vec<Option<f64>> = if options.calcs.is_empty() {
+                vec![cursor_values.get(&s.name).copied().or(s.value)]
+            } else {
+                options.calcs.iter().map(|r| s.calc(*r)).collect()
+            };
+            let display = p.series_display(s);
+            let name = if s.name.is_empty() {
+                format!("Series {i}")
+            } else {
+                s.name.clone()
+            };
+            let row = LegendRow {
+                name,
+                color: p.series_color(i, &app.theme),
+                visible: s.visible,
+                values: values
+                    .iter()
+                    .map(|value| value.map(|v| display.format_value(Some(v))))
+                    .collect(),
+            };
+            (row, values)
+        })
+        .collect();
+
+    if let Some(sort_by) = &options.sort_by {
+        if sort_by == "Name" {
+            rows.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+        } else if let Some(column) = columns.iter().position(|c| c == sort_by) {
+            rows.sort_by(|(_, a), (_, b)| compare_values(a[column], b[column]));
+        }
+        if options.sort_desc {
+            rows.reverse();
+        }
+    }
+
+    Legend {
+        mode: options.display_mode,
+        placement: options.placement,
+        columns,
+        calcs: !options.calcs.is_empty(),
+        rows: rows.into_iter().map(|(row, _)| row).collect(),
+        scroll: p.legend_scroll,
+    }
+}
+
+/// Orders missing values before any number.
+fn compare_values(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// Splits a graph panel into its chart and legend areas.
+pub(super) fn legend_layout(area: Rect, legend: &Legend) -> (Rect, Option<Rect>) {
+    if legend.mode == LegendDisplayMode::Hidden || legend.rows.is_empty() || area.height <= 5 {
+        return (area, None);
+    }
+
+    if legend.placement == LegendPlacement::Right {
+        let width = legend_width(legend).clamp(10, (area.width / 2).max(10));
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(width)])
+            .split(area);
+        return (chunks[0], Some(chunks[1]));
+    }
+
+    let height = match legend.mode {
+        LegendDisplayMode::Table => (legend.rows.len() as u16 + 1).min((area.height / 3).max(2)),
+        _ => 2,
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(height)])
+        .split(area);
+    (chunks[0], Some(chunks[1]))
+}
+
+/// Columns a right-hand legend needs to show its rows in full.
+fn legend_width(legend: &Legend) -> u16 {
+    let width = match legend.mode {
+        LegendDisplayMode::Table => {
+            let name = legend.rows.iter().map(|r| r.name.len()).max().unwrap_or(0);
+            let values: usize = (0..legend.columns.len())
+                .map(|column| column_width(legend, column) + 1)
+                .sum();
+            name + 2 + values
+        }
+        _ => legend
+            .rows
+            .iter()
+            .map(|row| legend.list_label(row).len())
+            .max()
+            .unwrap_or(0),
+    };
+    (width + 3) as u16
+}
+
+fn column_width(legend: &Legend, column: usize) -> usize {
+    legend
+        .rows
+        .iter()
+        .filter_map(|row| row.values[column].as_ref().map(String::len))
+        .chain([legend.columns[column].len()])
+        .max()
+        .unwrap_or(0)
+}
+
+pub(super) fn render_legend(frame: &mut Frame, area: Rect, legend: &Legend, theme: &Theme) {
+    let text_style = |row: &LegendRow| {
+        Style::default().fg(if row.visible {
+            theme.text
+        } else {
+            theme.legend_dim
+        })
+    };
+    let marker = |row: &LegendRow| Span::styled("■ ", Style::default().fg(row.color));
+
+    if legend.mode == LegendDisplayMode::Table {
+        let visible = area.height.saturating_sub(1) as usize;
+        let rows = legend.scrolled_rows(visible).iter().map(|row| {
+            let name = Line::from(vec![
+                marker(row),
+                Span::styled(row.name.clone(), text_style(row)),
+            ]);
+            Row::new(
+                std::iter::once(Cell::from(name)).chain(
+                    row.values
+                        .iter()
+                        .map(|value| Cell::from(value.clone().unwrap_or_default()))
+                        .map(|cell| cell.style(text_style(row))),
+                ),
+            )
+        });
+        let header = std::iter::once(String::new()).chain(legend.columns.iter().cloned());
+        let widths = std::iter::once(Constraint::Fill(1)).chain(
+            (0..legend.columns.len())
+                .map(|column| Constraint::Length(column_width(legend, column) as u16)),
+        );
+        let table = Table::new(rows, widths)
+            .header(
+                Row::new(header).style(
+                    Style::default()
+                        .fg(theme.title)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            )
+            .column_spacing(1);
+        frame.render_widget(table, area);
+    } else if legend.is_vertical() {
+        let lines: Vec<Line> = legend
+            .scrolled_rows(area.height as usize)
+            .iter()
+            .map(|row| {
+                Line::from(vec![
+                    marker(row),
+                    Span::styled(legend.list_label(row), text_style(row)),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), area);
+    } else {
+        let spans: Vec<Span> = legend
+            .rows
+            .iter()
+            .flat_map(|row| {
+                [
+                    marker(row),
+                    Span::styled(format!("{}  ", legend.list_label(row)), text_style(row)),
+                ]
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(Line::from(spans)).wrap(Wrap { trim: true }),
+            area,
+        );
+    }
+}
@@ -15,28 +15,43 @@
  */
 
 mod autogrid;
+mod axes;
 mod bounds;
+mod interpolation;
 mod labels;
+mod legend;
+mod nulls;
 mod overlay;
+mod stacking;
 mod thresholds;
 
 use autogrid::{build_autogrid_datasets, calculate_time_grid_ticks, calculate_value_grid_ticks};
 use labels::{
-    PlotBounds, YLabelArea, YLabelContext, render_autogrid_time_labels,
+    PlotBounds, RightYAxis, YLabelArea, YLabelContext, render_autogrid_time_labels,
     render_intermediate_y_labels, y_label_width,
 };
+use legend::{legend_layout, render_legend};
 use overlay::{merge_overlay_buffer, merge_overlay_buffer_preserving_data};
-use thresholds::{prepare_thresholds, render_raw_threshold_lines, threshold_marker};
+use stacking::series_points;
+use thresholds::{
+    prepare_thresholds, render_raw_threshold_lines, render_threshold_bands, threshold_marker,
+};
 
 use crate::app::{AppState, PanelState};
-use crate::ui::format::{format_axis_time, get_hash_color};
+use crate::ui::format::format_axis_time;
 use ratatui::{
     prelude::*,
-    widgets::{Axis, Chart, Dataset, GraphType, Paragraph, Wrap},
+    widgets::{Axis, Chart, Dataset, GraphType},
 };
-use std::collections::HashMap;
 
-pub(crate) use bounds::calculate_y_bounds;
+pub(crate) use autogrid::axis_value_ticks;
+pub(crate) use axes::{axis_layout, is_right_scaled, remap_position, right_axis_display};
+pub(crate) use bounds::{calculate_right_y_bounds, calculate_y_bounds, scale_points};
+pub(crate) use interpolation::{interpolate_points, monotone_tangents};
+pub(crate) use legend::{Legend, LegendRow, build_legend};
+pub(crate) use nulls::{line_segments, non_finite_samples};
+pub(crate) use stacking::{stack_series, y_axis_display};
+pub(crate) use thresholds::threshold_bands;
 
 fn graph_type_for_draw_style(draw_style: crate::app::GraphDrawStyle) -> GraphType {
     match draw_style {
@@ -51,18 +66,15 @@ fn should_overlay_points(options: &crate::app::GraphOptions) -> bool {
         && options.draw_style != crate::app::GraphDrawStyle::Points
 }
 
-fn area_fill_baseline(y_bounds: [f64; 2]) -> f64 {
-    if y_bounds[0] <= 0.0 && y_bounds[1] >= 0.0 {
-        0.0
-    } else {
-        y_bounds[0]
+/// Axis position areas fill down to: zero when the axis shows it, otherwise
+/// the bottom of the axis.
+fn area_fill_baseline(y_bounds: [f64; 2], scale: crate::app::GraphScale) -> f64 {
+    match scale.position_of(0.0) {
+        Some(zero) if y_bounds[0] <= zero && y_bounds[1] >= zero => zero,
+        _ => y_bounds[0],
     }
 }
 
-fn is_y_axis_hidden(options: &crate::app::GraphOptions) -> bool {
-    options.axis_placement == crate::app::GraphAxisPlacement::Hidden
-}
-
 fn chart_plot_left(
     chart_area: Rect,
     y_label_width: u16,
@@ -153,6 +165,32 @@ fn render_forced_point_markers(
     }
 }
 
+/// Marks NaN and infinite samples with a cross on the top (`+Inf`) or bottom
+/// row of the plot.
+fn render_non_finite_markers(
+    frame: &mut Frame,
+    markers: &[(f64, bool, Color)],
+    x_bounds: [f64; 2],
+    y_bounds: [f64; 2],
+    plot: PlotBounds,
+) {
+    let buf = frame.buffer_mut();
+    for (x, top, color) in markers {
+        let Some((cell_x, _)) = point_to_braille_cell(*x, y_bounds[1], x_bounds, y_bounds, plot)
+        else {
+            continue;
+        };
+        let cell_y = if *top {
+            plot.top
+        } else {
+            plot.bottom.saturating_sub(1).max(plot.top)
+        };
+        if let Some(cell) = buf.cell_mut((cell_x, cell_y)) {
+            cell.set_symbol("×").set_style(Style::default().fg(*color));
+        }
+    }
+}
+
 pub(super) fn render_graph_panel(
     frame: &mut Frame,
     area: Rect,
@@ -161,51 +199,10 @@ pub(super) fn render_graph_panel(
     cursor_x: Option<f64>,
 ) {
     let theme = &app.theme;
-    let use_hash_colors = p.series.len() > theme.palette.len();
-
-    // If inspecting, find values at cursor
-    let cursor_values: HashMap<String, f64> = if let Some(cx) = cursor_x {
-        p.series
-            .iter()
-            .filter_map(|s| {
-                // Find point closest to cursor_x
-                let closest = s.points.iter().min_by(|a, b| {
-                    let da = (a.0 - cx).abs();
-                    let db = (b.0 - cx).abs();
-                    da.partial_cmp(&db).unwrap_or(std::cmp::Ordering::Equal)
-                });
-
-                if let Some((ts, val)) = closest {
-                    // Only consider if within reasonable distance (e.g. 2 steps)
-                    if (ts - cx).abs() <= app.step.as_secs_f64() * 2.0 {
-                        Some((s.name.clone(), *val))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect()
-    } else {
-        HashMap::new()
-    };
 
     // Split inner area into chart and legend
-    // If we have series, reserve space for legend
-    let legend_height = if !p.series.is_empty() && area.height > 5 {
-        2
-    } else {
-        0
-    };
-
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Min(0), Constraint::Length(legend_height)])
-        .split(area);
-
-    let chart_area = chunks[0];
-    let legend_area = chunks[1];
+    let legend = build_legend(p, app);
+    let (chart_area, legend_area) = legend_layout(area, &legend);
 
     // Determine x bounds from the last refreshed query window.
     let (start, now) = app.time_bounds();
@@ -214,20 +211,41 @@ pub(super) fn render_graph_panel(
     let y_bounds = calculate_y_bounds(p);
     let show_autogrid = app.autogrid_enabled && p.autogrid.unwrap_or(true);
     let graph_options = p.graph_options();
-    let hide_y_axis = is_y_axis_hidden(&graph_options);
+    let layout = axis_layout(p);
+    let hide_y_axis = !layout.left;
+    let right_bounds = calculate_right_y_bounds(p).unwrap_or(y_bounds);
+    let stacked = stack_series(p);
+    let axis_display = y_axis_display(p);
+    let right_display = right_axis_display(p);
+    let scale = graph_options.scale;
+    // Series are drawn at the axis positions of the panel scale. Series on a
+    // right axis of their own are moved to the same height in the left
+    // bounds, which the chart is drawn with.
+    let scaled_points: Vec<_> = (0..p.series.len())
+        .map(|i| {
+            let points = scale_points(series_points(p, stacked.as_deref(), i), scale);
+            if !is_right_scaled(p, layout, i) {
+                return points;
+            }
+            points
+                .into_iter()
+                .map(|(x, y)| (x, remap_position(y, right_bounds, y_bounds)))
+                .collect()
+        })
+        .collect();
 
     // Prepare datasets (without names for the chart itself to avoid built-in legend)
     let mut chart_datasets = Vec::new();
     let mut strong_data_datasets = Vec::new();
-    let mut legend_items = Vec::new();
     let mut forced_point_markers = Vec::new();
+    let mut non_finite_markers = Vec::new();
 
     // Declare helper datasets to extend their lifetimes
     let mut cursor_dataset = vec![];
     let threshold_data = prepare_thresholds(p, &app.threshold_marker, [start, now]);
     let mut threshold_overlay_datasets = Vec::new();
 
-    if !app.threshold_marker.ends_with("line") {
+    if threshold_data.show_lines && !app.threshold_marker.ends_with("line") {
         let (marker, graph_type) = threshold_marker(&app.threshold_marker);
         for (i, (_, color)) in threshold_data.labels.iter().enumerate() {
             threshold_overlay_datasets.push(
@@ -241,63 +259,80 @@ pub(super) fn render_graph_panel(
         }
     }
 
-    for (i, s) in p.series.iter().enumerate() {
-        let color = if use_hash_colors {
-            get_hash_color(&s.name)
+    // Stacked series are drawn top of the stack first, so the fill of each
+    // series only shows between it and the series below.
+    let mut draw_order: Vec<usize> = (0..p.series.len()).collect();
+    if stacked.is_some() {
+        draw_order.reverse();
+    }
+    // Lines break at nulls and follow the line interpolation; points and
+    // bars are drawn at the samples.
+    let drawn_points: Vec<Vec<Vec<(f64, f64)>>> = (0..p.series.len())
+        .map(|i| {
+            let data = &scaled_points[i];
+            if !p.series[i].visible {
+                Vec::new()
+            } else if graph_options.draw_style == crate::app::GraphDrawStyle::Line {
+                line_segments(p, i, data)
+                    .into_iter()
+                    .map(|segment| {
+                        interpolate_points(&data[segment], graph_options.line_interpolation)
+                    })
+                    .collect()
+            } else {
+                vec![data.clone()]
+            }
+        })
+        .collect();
+    for i in draw_order {
+        let color = p.series_color(i, theme);
+
+        let data = if p.series[i].visible {
+            scaled_points[i].as_slice()
         } else {
-            theme.palette[i % theme.palette.len()]
+            &[]
         };
 
-        let data = if s.visible { s.points.as_slice() } else { &[] };
-
-        // For legend display
-        let mut name = s.name.clone();
-        if let Some(val) = cursor_values.get(&s.name) {
-            name.push_str(&format!(" ({})", p.display.format_number(*val)));
-        } else if let Some(val) = s.value {
-            name.push_str(&format!(" ({})", p.display.format_number(val)));
-        }
-        if name.is_empty() {
-            name = format!("Series {}", i);
-        }
-
-        legend_items.push(Span::styled("■ ".to_string(), Style::default().fg(color)));
-        legend_items.push(Span::styled(
-            format!("{}  ", name),
-            Style::default().fg(theme.text),
-        ));
-
-        // For chart (no name to avoid legend)
-        let mut dataset = Dataset::default()
-            .name("")
-            .marker(ratatui::symbols::Marker::Braille)
-            .graph_type(graph_type_for_draw_style(graph_options.draw_style))
-            .style(Style::default().fg(color))
-            .data(data);
-
         let is_area_filled = graph_options.fill_opacity.unwrap_or(0) > 0
             && graph_options.draw_style == crate::app::GraphDrawStyle::Line;
+        let baseline = if is_right_scaled(p, layout, i) {
+            let baseline = area_fill_baseline(right_bounds, scale);
+            remap_position(baseline, right_bounds, y_bounds)
+        } else {
+            area_fill_baseline(y_bounds, scale)
+        };
 
-        if is_area_filled {
-            strong_data_datasets.push(
-                Dataset::default()
-                    .name("")
-                    .marker(ratatui::symbols::Marker::Braille)
-                    .graph_type(GraphType::Line)
-                    .style(Style::default().fg(color))
-                    .data(data),
-            );
-
-            dataset = dataset
-                .graph_type(GraphType::Area)
-                .fill_to_y(area_fill_baseline(y_bounds));
+        for data in &drawn_points[i] {
+            // For chart (no name to avoid legend)
+            let mut dataset = Dataset::default()
+                .name("")
+                .marker(ratatui::symbols::Marker::Braille)
+                .graph_type(graph_type_for_draw_style(graph_options.draw_style))
+                .style(Style::default().fg(color))
+                .data(data);
+
+            if is_area_filled {
+                strong_data_datasets.push(
+                    Dataset::default()
+                        .name("")
+                        .marker(ratatui::symbols::Marker::Braille)
+                        .graph_type(GraphType::Line)
+                        .style(Style::default().fg(color))
+                        .data(data),
+                );
+
+                dataset = dataset.graph_type(GraphType::Area).fill_to_y(baseline);
+            }
+
+            chart_datasets.push(dataset);
         }
 
-        chart_datasets.push(dataset);
-
         if should_overlay_points(&graph_options) {
             forced_point_markers.extend(data.iter().map(|(x, y)| (*x, *y, color)));
         }
+        if p.series[i].visible {
+            non_finite_markers.extend(non_finite_samples(p, i).map(|(x, top)| (x, top, color)));
+        }
     }
 
     // Add cursor line if inspecting
@@ -344,18 +379,36 @@ pub(super) fn render_graph_panel(
     let y_axis_height = usize::from(plot_height).max(2);
     let mut y_labels = vec![Span::raw(""); y_axis_height];
     let autogrid_value_ticks = if show_autogrid {
-        calculate_value_grid_ticks(y_bounds, plot_height)
+        calculate_value_grid_ticks(y_bounds, plot_height, scale)
     } else {
         Vec::new()
     };
 
+    // The right axis takes a gutter of its own off the chart.
+    let right_axis = layout.right.then(|| RightYAxis {
+        y_bounds,
+        bounds: right_bounds,
+        autogrid_ticks: &autogrid_value_ticks,
+        display: &right_display,
+        scale,
+        text_color: theme.text,
+        grid_color: app.autogrid_color,
+    });
+    let right_axis_width = right_axis
+        .as_ref()
+        .map_or(0, |axis| axis.width().min(chart_area.width / 3));
+    let chart_area = Rect {
+        width: chart_area.width - right_axis_width,
+        ..chart_area
+    };
+
     if !hide_y_axis {
         y_labels[0] = Span::styled(
-            p.display.format_number(y_bounds[0]),
+            axis_display.format_number(scale.value_at(y_bounds[0])),
             Style::default().fg(theme.text),
         );
         y_labels[y_axis_height - 1] = Span::styled(
-            p.display.format_number(y_bounds[1]),
+            axis_display.format_number(scale.value_at(y_bounds[1])),
             Style::default().fg(theme.text),
         );
     }
@@ -368,7 +421,8 @@ pub(super) fn render_graph_panel(
             &y_labels,
             &autogrid_value_ticks,
             &threshold_data.labels,
-            &p.display,
+            &axis_display,
+            scale,
         )
     };
     let chart_y_labels = if hide_y_axis {
@@ -463,14 +517,16 @@ pub(super) fn render_graph_panel(
         }
     }
 
-    render_raw_threshold_lines(
-        frame,
-        &app.threshold_marker,
-        &threshold_data.labels,
-        y_bounds,
-        plot_bounds,
-        strong_data_buf.as_ref(),
-    );
+    if threshold_data.show_lines {
+        render_raw_threshold_lines(
+            frame,
+            &app.threshold_marker,
+            &threshold_data.labels,
+            y_bounds,
+            plot_bounds,
+            strong_data_buf.as_ref(),
+        );
+    }
 
     if show_autogrid && chart_top <= chart_bottom {
         let plot_width = chart_right.saturating_sub(chart_left);
@@ -525,6 +581,10 @@ pub(super) fn render_graph_panel(
         );
     }
 
+    // Bands only set the background, so they go last and keep the symbols
+    // and colors of every layer above.
+    render_threshold_bands(frame, &threshold_data.bands, y_bounds, plot_bounds);
+
     if !hide_y_axis {
         render_intermediate_y_labels(
             frame,
@@ -537,12 +597,17 @@ pub(super) fn render_graph_panel(
                 y_bounds,
                 autogrid_ticks: &autogrid_value_ticks,
                 threshold_labels: &threshold_data.labels,
-                display: &p.display,
+                display: &axis_display,
+                scale,
                 color: app.autogrid_color,
             },
         );
     }
 
+    if let Some(right_axis) = &right_axis {
+        right_axis.render(frame, chart_right, right_axis_width, plot_bounds);
+    }
+
     render_forced_point_markers(
         frame,
         &forced_point_markers,
@@ -550,11 +615,17 @@ pub(super) fn render_graph_panel(
         y_bounds,
         plot_bounds,
     );
+    render_non_finite_markers(
+        frame,
+        &non_finite_markers,
+        [start, now],
+        y_bounds,
+        plot_bounds,
+    );
 
     // Render custom legend
-    if legend_height > 0 {
-        let legend = Paragraph::new(Line::from(legend_items)).wrap(Wrap { trim: true });
-        frame.render_widget(legend, legend_area);
+    if let Some(legend_area) = legend_area {
+        render_legend(frame, legend_area, &legend, theme);
     }
 }
 
@@ -562,8 +633,10 @@ pub(super) fn render_graph_panel(
 mod tests {
     use super::*;
     use crate::app::{
-        GraphAxisPlacement, GraphDrawStyle, GraphOptions, GraphPointMode, GraphStackingMode,
-        PanelOptions, PanelType, QueryMode, SeriesView, YAxisMode,
+        GraphAxisPlacement, GraphDrawStyle, GraphLineInterpolation, GraphOptions, GraphPointMode,
+        GraphScale, GraphStackingMode, LegendDisplayMode, LegendOptions, LegendPlacement,
+        PanelOptions, PanelType, QueryMode, Reducer, SeriesView, ThresholdMode, ThresholdStep,
+        Thresholds, YAxisMode,
     };
     use crate::export::ExportOptions;
     use crate::theme::Theme;
@@ -588,9 +661,11 @@ mod tests {
 
     #[test]
     fn test_should_overlay_points() {
-        let mut options = GraphOptions::default();
-        options.draw_style = GraphDrawStyle::Line;
-        options.show_points = GraphPointMode::Auto;
+        let mut options = GraphOptions {
+            draw_style: GraphDrawStyle::Line,
+            show_points: GraphPointMode::Auto,
+            ..GraphOptions::default()
+        };
         assert!(!should_overlay_points(&options));
 
         options.show_points = GraphPointMode::Always;
@@ -606,25 +681,30 @@ mod tests {
 
     #[test]
     fn test_area_fill_baseline_prefers_zero_when_visible() {
-        assert_eq!(area_fill_baseline([-10.0, 20.0]), 0.0);
-        assert_eq!(area_fill_baseline([5.0, 20.0]), 5.0);
-        assert_eq!(area_fill_baseline([-20.0, -5.0]), -20.0);
+        let linear = GraphScale::Linear;
+        assert_eq!(area_fill_baseline([-10.0, 20.0], linear), 0.0);
+        assert_eq!(area_fill_baseline([5.0, 20.0], linear), 5.0);
+        assert_eq!(area_fill_baseline([-20.0, -5.0], linear), -20.0);
+        let log = GraphScale::Log { base: 10.0 };
+        assert_eq!(area_fill_baseline([-1.0, 2.0], log), -1.0);
     }
 
     #[test]
     fn test_hidden_axis_flag() {
-        let visible = GraphOptions::default();
-        assert!(!is_y_axis_hidden(&visible));
+        let mut panel = area_fill_panel();
+        assert!(axis_layout(&panel).left);
 
-        let hidden = GraphOptions {
+        panel.options = PanelOptions::Graph(GraphOptions {
             axis_placement: GraphAxisPlacement::Hidden,
             draw_style: GraphDrawStyle::Line,
             show_points: GraphPointMode::Auto,
             fill_opacity: None,
-            line_interpolation: Some("smooth".to_string()),
+            line_interpolation: GraphLineInterpolation::Smooth,
             stacking: GraphStackingMode::Normal,
-        };
-        assert!(is_y_axis_hidden(&hidden));
+            ..GraphOptions::default()
+        });
+        let layout = axis_layout(&panel);
+        assert!(!layout.left && !layout.right);
     }
 
     #[test]
@@ -701,37 +781,51 @@ mod tests {
             exprs: vec![],
             legends: vec![],
             query_modes: vec![QueryMode::Range],
+            datasources: vec![],
+            ref_ids: vec![],
+            resolution: crate::app::QueryResolution::default(),
             series: vec![SeriesView {
                 name: "filled".to_string(),
                 value: Some(8.0),
                 points: vec![(0.0, 8.0), (50.0, 8.0), (100.0, 8.0)],
                 visible: true,
+                ..Default::default()
             }],
             last_error: None,
             last_url: None,
+            last_step: None,
+            loading: false,
+            stale: false,
             last_samples: 3,
             grid: None,
             y_axis_mode: YAxisMode::Auto,
+            legend_scroll: 0,
             panel_type: PanelType::Graph,
             thresholds: None,
             min: Some(0.0),
             max: Some(10.0),
             autogrid: Some(true),
             display: crate::ui::DisplayFormat::default(),
+            overrides: vec![],
             options: PanelOptions::Graph(GraphOptions {
                 draw_style: GraphDrawStyle::Line,
                 show_points: GraphPointMode::Never,
                 fill_opacity: Some(30),
                 axis_placement: GraphAxisPlacement::Visible,
-                line_interpolation: None,
+                line_interpolation: GraphLineInterpolation::Linear,
                 stacking: GraphStackingMode::Off,
+                ..GraphOptions::default()
             }),
+            repeat: None,
+            scoped_vars: std::collections::HashMap::new(),
         }
     }
 
     fn area_fill_app(panel: PanelState) -> AppState {
         let mut app = AppState::new(
-            crate::prom::PromClient::new("http://localhost:9090".to_string()),
+            crate::app::DatasourceRegistry::single(crate::prom::PromClient::new(
+                "http://localhost:9090".to_string(),
+            )),
             Duration::from_secs(100),
             Duration::from_secs(5),
             Duration::from_secs(1),
@@ -747,6 +841,91 @@ mod tests {
         app
     }
 
+    #[test]
+    fn test_table_legend_sorts_calc_columns_and_scrolls() {
+        let mut panel = area_fill_panel();
+        let series = panel.series[0].clone();
+        panel.series = [("low", 1.0), ("high", 9.0), ("mid", 5.0)]
+            .into_iter()
+            .map(|(name, value)| SeriesView {
+                name: name.to_string(),
+                points: vec![(0.0, 0.0), (50.0, value)],
+                ..series.clone()
+            })
+            .collect();
+        panel.series[2].visible = false;
+        panel.options = PanelOptions::Graph(GraphOptions {
+            legend: LegendOptions {
+                display_mode: LegendDisplayMode::Table,
+                placement: LegendPlacement::Right,
+                calcs: vec![Reducer::Max, Reducer::Mean],
+                sort_by: Some("Max".to_string()),
+                sort_desc: true,
+            },
+            ..GraphOptions::default()
+        });
+        let mut app = area_fill_app(panel);
+
+        let legend = build_legend(&app.panels[0], &app);
+        assert_eq!(legend.columns, vec!["Max", "Mean"]);
+        let names: Vec<_> = legend.rows.iter().map(|row| row.name.as_str()).collect();
+        assert_eq!(names, vec!["high", "mid", "low"]);
+        assert_eq!(legend.rows[0].values[1].as_deref(), Some("4.50"));
+        assert!(!legend.rows[1].visible);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|frame| {
+                render_graph_panel(frame, Rect::new(0, 0, 80, 20), &app.panels[0], &app, None);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let header: String = (0..80)
+            .map(|x| buffer.cell((x, 0)).unwrap().symbol().to_string())
+            .collect();
+        assert!(header.contains("Max") && header.contains("Mean"));
+
+        app.scroll_legend(1);
+        let legend = build_legend(&app.panels[0], &app);
+        assert_eq!(legend.scrolled_rows(2)[0].name, "mid");
+        // Scrolling stops once the last rows are shown.
+        assert_eq!(legend.scrolled_rows(3)[0].name, "high");
+    }
+
+    #[test]
+    fn test_right_axis_series_use_their_own_bounds_and_gutter() {
+        let mut panel = area_fill_panel();
+        let mut right = panel.series[0].clone();
+        right.name = "latency".to_string();
+        right.points = vec![(0.0, 1000.0), (50.0, 2000.0), (100.0, 1500.0)];
+        right.overrides.axis_placement = Some(GraphAxisPlacement::Right);
+        panel.series.push(right);
+        let app = area_fill_app(panel);
+        let panel = &app.panels[0];
+
+        assert_eq!(calculate_y_bounds(panel), [0.0, 10.0]);
+        let right_bounds = calculate_right_y_bounds(panel).unwrap();
+        assert_eq!(right_bounds, [950.0, 2050.0]);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|frame| {
+                render_graph_panel(frame, Rect::new(0, 0, 80, 20), panel, &app, None);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..80)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+                .collect()
+        };
+        assert!(row(0).starts_with("10.00"));
+        assert!(row(0).ends_with("│2.05k "));
+        assert!(row(8).ends_with("│1.50k "));
+        assert!(row(15).ends_with("│950.00"));
+        assert!(row(16).ends_with("─┘      "));
+    }
+
     #[test]
     fn test_area_fill_keeps_precedence_over_autogrid() {
         let app = area_fill_app(area_fill_panel());
@@ -793,6 +972,47 @@ mod tests {
         assert_eq!(grid_colored_cells_inside_fill, 0);
     }
 
+    #[test]
+    fn test_threshold_area_shades_autogrid_cells() {
+        let mut panel = area_fill_panel();
+        panel.series[0].points = vec![(0.0, 1.0), (100.0, 1.0)];
+        panel.thresholds = Some(Thresholds {
+            mode: ThresholdMode::Absolute,
+            steps: vec![
+                ThresholdStep {
+                    value: None,
+                    color: Color::Rgb(40, 200, 40),
+                },
+                ThresholdStep {
+                    value: Some(5.0),
+                    color: Color::Rgb(200, 40, 40),
+                },
+            ],
+            style: Some("area".to_string()),
+        });
+        let app = area_fill_app(panel);
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+        terminal
+            .draw(|frame| {
+                render_graph_panel(frame, Rect::new(0, 0, 80, 20), &app.panels[0], &app, None);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        // Autogrid cells are drawn in the autogrid color; the plot starts
+        // right of the y-axis line.
+        let grid_cells: Vec<_> = (0..16)
+            .flat_map(|y| (10..80).map(move |x| (x, y)))
+            .filter_map(|pos| buffer.cell(pos))
+            .filter(|cell| cell.fg == Color::Red && !cell.symbol().trim().is_empty())
+            .collect();
+        assert!(!grid_cells.is_empty());
+        assert!(grid_cells.iter().all(|cell| {
+            cell.bg == Color::Rgb(10, 50, 10) || cell.bg == Color::Rgb(50, 10, 10)
+        }));
+    }
+
     #[test]
     fn test_line_forced_points_are_visible_and_use_line_marker_cells() {
         let mut panel = area_fill_panel();
@@ -802,8 +1022,9 @@ mod tests {
             show_points: GraphPointMode::Always,
             fill_opacity: None,
             axis_placement: GraphAxisPlacement::Visible,
-            line_interpolation: None,
+            line_interpolation: GraphLineInterpolation::Linear,
             stacking: GraphStackingMode::Off,
+            ..GraphOptions::default()
         });
         let app = area_fill_app(panel);
         let panel = &app.panels[0];
@@ -0,0 +1,119 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::{PanelState, SpanNulls};
+use std::ops::Range;
+
+/// Index ranges of `points`, the drawn points of series `index`, that a line
+/// connects. The line breaks between two points with a null of the series
+/// in between, unless `custom.spanNulls` spans it.
+pub(crate) fn line_segments(
+    p: &PanelState,
+    index: usize,
+    points: &[(f64, f64)],
+) -> Vec<Range<usize>> {
+    let span_nulls = p.graph_options().span_nulls;
+    let nulls = &p.series[index].nulls;
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut next_null = 0;
+
+    for end in 1..points.len() {
+        let (from, to) = (points[end - 1].0, points[end].0);
+        while nulls.get(next_null).is_some_and(|null| null.ts <= from) {
+            next_null += 1;
+        }
+        let has_null = nulls.get(next_null).is_some_and(|null| null.ts < to);
+        let spanned = match span_nulls {
+            SpanNulls::Never => false,
+            SpanNulls::Always => true,
+            SpanNulls::Below(threshold) => to - from <= threshold,
+        };
+        if has_null && !spanned {
+            segments.push(start..end);
+            start = end;
+        }
+    }
+    if !points.is_empty() {
+        segments.push(start..points.len());
+    }
+    segments
+}
+
+/// Timestamps of the NaN and infinite samples of series `index`, and
+/// whether each is marked at the top of the plot: `+Inf` is, while NaN and
+/// `-Inf` are marked at the bottom.
+pub(crate) fn non_finite_samples(
+    p: &PanelState,
+    index: usize,
+) -> impl Iterator<Item = (f64, bool)> + '_ {
+    p.series[index]
+        .nulls
+        .iter()
+        .filter_map(|null| Some((null.ts, null.value? == f64::INFINITY)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{GraphOptions, NullSample, PanelOptions, SeriesView};
+
+    fn panel(span_nulls: SpanNulls, nulls: Vec<NullSample>) -> PanelState {
+        PanelState {
+            series: vec![SeriesView {
+                name: "s".to_string(),
+                points: vec![(0.0, 1.0), (10.0, 2.0), (40.0, 3.0), (50.0, 4.0)],
+                visible: true,
+                nulls,
+                ..Default::default()
+            }],
+            options: PanelOptions::Graph(GraphOptions {
+                span_nulls,
+                ..GraphOptions::default()
+            }),
+            ..crate::ui::panels::test_panel("nulls")
+        }
+    }
+
+    #[test]
+    fn test_line_segments_break_at_nulls_unless_spanned() {
+        let nulls = vec![
+            NullSample {
+                ts: 20.0,
+                value: None,
+            },
+            NullSample {
+                ts: 45.0,
+                value: Some(f64::INFINITY),
+            },
+        ];
+        let p = panel(SpanNulls::Never, nulls.clone());
+        let points = p.series[0].points.clone();
+        assert_eq!(line_segments(&p, 0, &points), vec![0..2, 2..3, 3..4]);
+        assert_eq!(
+            non_finite_samples(&p, 0).collect::<Vec<_>>(),
+            vec![(45.0, true)]
+        );
+
+        // Only the 30s gap is longer than the threshold.
+        let p = panel(SpanNulls::Below(15.0), nulls.clone());
+        assert_eq!(line_segments(&p, 0, &points), vec![0..2, 2..4]);
+
+        let p = panel(SpanNulls::Always, nulls);
+        assert_eq!(line_segments(&p, 0, &points), vec![0..4]);
+        assert!(line_segments(&p, 0, &[]).is_empty());
+    }
+}
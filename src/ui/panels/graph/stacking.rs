@@ -0,0 +1,228 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::axes::series_axis_placement;
+use crate::app::{GraphAxisPlacement, GraphStackingMode, PanelState};
+use crate::ui::DisplayFormat;
+use std::collections::HashMap;
+
+/// The band one series covers in a stacked graph.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StackedSeries {
+    /// Running total up to and including the series: the line that is drawn.
+    pub(crate) points: Vec<(f64, f64)>,
+    /// Running total of the series stacked below it, where its fill starts.
+    /// Parallel to `points`.
+    pub(crate) base: Vec<(f64, f64)>,
+}
+
+/// Stacks the visible series of each stacking group. Series only stack with
+/// series that share their mode, `custom.stacking.group` and y-axis side, in
+/// panel order.
+///
+/// Samples are summed on their timestamps; a series without a sample at a
+/// timestamp contributes nothing there. Positive and negative values stack
+/// away from zero separately, as in Grafana. In percent mode each sample is
+/// first divided by the absolute total of its group, so stacks reach 1.0.
+///
+/// The result is parallel to `p.series`, with `None` for series that do not
+/// stack. Returns `None` when no series stacks.
+pub(crate) fn stack_series(p: &PanelState) -> Option<Vec<Option<StackedSeries>>> {
+    let options = p.graph_options();
+    let mut groups: Vec<(GraphStackingMode, &str, bool, Vec<usize>)> = Vec::new();
+    for (index, s) in p.series.iter().enumerate() {
+        let mode = s.overrides.stacking.unwrap_or(options.stacking);
+        if !s.visible || mode == GraphStackingMode::Off {
+            continue;
+        }
+        let group = s
+            .overrides
+            .stacking_group
+            .as_deref()
+            .unwrap_or(&options.stacking_group);
+        let right = series_axis_placement(p, index) == GraphAxisPlacement::Right;
+        match groups
+            .iter_mut()
+            .find(|(m, g, r, _)| *m == mode && *g == group && *r == right)
+        {
+            Some((_, _, _, members)) => members.push(index),
+            None => groups.push((mode, group, right, vec![index])),
+        }
+    }
+    if groups.is_empty() {
+        return None;
+    }
+
+    let mut stacked = vec![None; p.series.len()];
+    for (mode, _, _, members) in groups {
+        let totals = (mode == GraphStackingMode::Percent).then(|| {
+            let mut totals: HashMap<u64, f64> = HashMap::new();
+            for &index in &members {
+                for (x, y) in finite_points(&p.series[index].points) {
+                    *totals.entry(x.to_bits()).or_default() += y.abs();
+                }
+            }
+            totals
+        });
+
+        let mut positive: HashMap<u64, f64> = HashMap::new();
+        let mut negative: HashMap<u64, f64> = HashMap::new();
+        for index in members {
+            let mut series = StackedSeries {
+                points: Vec::new(),
+                base: Vec::new(),
+            };
+            for (x, mut y) in finite_points(&p.series[index].points) {
+                if let Some(totals) = &totals {
+                    let total = totals.get(&x.to_bits()).copied().unwrap_or_default();
+                    y = if total > 0.0 { y / total } else { 0.0 };
+                }
+                let running = if y < 0.0 {
+                    &mut negative
+                } else {
+                    &mut positive
+                };
+                let base = running.entry(x.to_bits()).or_default();
+                series.base.push((x, *base));
+                *base += y;
+                series.points.push((x, *base));
+            }
+            stacked[index] = Some(series);
+        }
+    }
+    Some(stacked)
+}
+
+/// Points drawn for series `index`: its stacked totals when it stacks,
+/// otherwise its samples.
+pub(crate) fn series_points<'a>(
+    p: &'a PanelState,
+    stacked: Option<&'a [Option<StackedSeries>]>,
+    index: usize,
+) -> &'a [(f64, f64)] {
+    match stacked.and_then(|stacked| stacked[index].as_ref()) {
+        Some(series) => &series.points,
+        None => &p.series[index].points,
+    }
+}
+
+/// Whether the y-axis shows the ratios of percent stacking.
+pub(crate) fn is_percent_stacked(p: &PanelState) -> bool {
+    p.graph_options().stacking == GraphStackingMode::Percent
+}
+
+/// Format of y-axis labels: percent stacking shows ratios as percentages
+/// whatever the unit of the stacked values.
+pub(crate) fn y_axis_display(p: &PanelState) -> DisplayFormat {
+    if is_percent_stacked(p) {
+        DisplayFormat {
+            unit: Some("percentunit".to_string()),
+            decimals: p.display.decimals,
+            ..DisplayFormat::default()
+        }
+    } else {
+        p.display.clone()
+    }
+}
+
+fn finite_points(points: &[(f64, f64)]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    points
+        .iter()
+        .copied()
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{GraphOptions, PanelOptions, SeriesView};
+
+    fn series(name: &str, points: Vec<(f64, f64)>) -> SeriesView {
+        SeriesView {
+            name: name.to_string(),
+            points,
+            visible: true,
+            ..Default::default()
+        }
+    }
+
+    fn stacked_panel(stacking: GraphStackingMode, series: Vec<SeriesView>) -> PanelState {
+        PanelState {
+            series,
+            options: PanelOptions::Graph(GraphOptions {
+                stacking,
+                ..GraphOptions::default()
+            }),
+            ..crate::ui::panels::test_panel("stacked")
+        }
+    }
+
+    #[test]
+    fn test_stack_series_sums_aligned_samples_and_skips_missing_ones() {
+        let p = stacked_panel(
+            GraphStackingMode::Normal,
+            vec![
+                series("a", vec![(0.0, 1.0), (10.0, 2.0), (20.0, f64::NAN)]),
+                series("b", vec![(0.0, 3.0), (20.0, 4.0)]),
+                series("c", vec![(0.0, -1.0), (10.0, 5.0)]),
+            ],
+        );
+
+        let stacked = stack_series(&p).unwrap();
+        let a = stacked[0].as_ref().unwrap();
+        let b = stacked[1].as_ref().unwrap();
+        let c = stacked[2].as_ref().unwrap();
+        assert_eq!(a.points, vec![(0.0, 1.0), (10.0, 2.0)]);
+        assert_eq!(b.base, vec![(0.0, 1.0), (20.0, 0.0)]);
+        assert_eq!(b.points, vec![(0.0, 4.0), (20.0, 4.0)]);
+        // Negative values stack below zero on their own.
+        assert_eq!(c.base, vec![(0.0, 0.0), (10.0, 2.0)]);
+        assert_eq!(c.points, vec![(0.0, -1.0), (10.0, 7.0)]);
+    }
+
+    #[test]
+    fn test_stack_series_normalizes_percent_and_honors_groups() {
+        let mut p = stacked_panel(
+            GraphStackingMode::Percent,
+            vec![
+                series("a", vec![(0.0, 1.0), (10.0, 0.0)]),
+                series("b", vec![(0.0, 3.0), (10.0, 0.0)]),
+                series("other", vec![(0.0, 50.0)]),
+                series("off", vec![(0.0, 7.0)]),
+            ],
+        );
+        p.series[2].overrides.stacking_group = Some("B".to_string());
+        p.series[3].overrides.stacking = Some(GraphStackingMode::Off);
+
+        let stacked = stack_series(&p).unwrap();
+        assert_eq!(
+            stacked[0].as_ref().unwrap().points,
+            vec![(0.0, 0.25), (10.0, 0.0)]
+        );
+        assert_eq!(
+            stacked[1].as_ref().unwrap().points,
+            vec![(0.0, 1.0), (10.0, 0.0)]
+        );
+        assert_eq!(stacked[2].as_ref().unwrap().points, vec![(0.0, 1.0)]);
+        assert!(stacked[3].is_none());
+        assert_eq!(series_points(&p, Some(&stacked), 3), &[(0.0, 7.0)]);
+        assert_eq!(y_axis_display(&p).format_number(0.5), "50.00%");
+
+        p.options = PanelOptions::Graph(GraphOptions::default());
+        p.series[3].overrides.stacking = None;
+        assert!(stack_series(&p).is_none());
+    }
+}
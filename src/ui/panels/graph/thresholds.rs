@@ -16,12 +16,16 @@
 
 use super::labels::PlotBounds;
 use super::overlay::{is_blank_cell, overlay_cell_if_blank_or_weak_area_fill};
-use crate::app::{PanelState, ThresholdMode};
+use crate::app::PanelState;
+use crate::ui::format::color_rgb;
 use ratatui::{prelude::*, widgets::GraphType};
 
 pub(super) struct ThresholdRenderData {
     pub(super) datasets: Vec<Vec<(f64, f64)>>,
     pub(super) labels: Vec<(f64, Color)>,
+    /// Whether the threshold style draws lines; `area` only shades.
+    pub(super) show_lines: bool,
+    pub(super) bands: Vec<(f64, f64, Color)>,
 }
 
 pub(super) fn prepare_thresholds(
@@ -33,30 +37,112 @@ pub(super) fn prepare_thresholds(
     let mut labels = Vec::new();
 
     let Some(thresholds) = &panel.thresholds else {
-        return ThresholdRenderData { datasets, labels };
+        return ThresholdRenderData {
+            datasets,
+            labels,
+            show_lines: false,
+            bands: Vec::new(),
+        };
     };
+    let scale = panel.graph_options().scale;
 
     for step in thresholds.steps.iter().filter(|step| step.value.is_some()) {
         let value = step.value.unwrap();
-        let threshold_value = match thresholds.mode {
-            ThresholdMode::Absolute => value,
-            ThresholdMode::Percentage => {
-                let min = panel.min.unwrap_or(0.0);
-                let max = panel.max.unwrap_or(100.0);
-                min + (value / 100.0) * (max - min)
-            }
+        let threshold_value = thresholds.absolute_value(value, panel.min, panel.max);
+        // Thresholds are placed at axis positions; log axes skip those at or
+        // below zero.
+        let Some(threshold_value) = scale.position_of(threshold_value) else {
+            continue;
         };
 
         datasets.push(threshold_dataset(
             marker_name,
-            thresholds.style.as_deref(),
+            thresholds.is_dashed(),
             x_bounds,
             threshold_value,
         ));
         labels.push((threshold_value, step.color));
     }
 
-    ThresholdRenderData { datasets, labels }
+    ThresholdRenderData {
+        datasets,
+        labels,
+        show_lines: thresholds.shows_lines(),
+        bands: threshold_bands(panel),
+    }
+}
+
+/// Ranges of the y-axis shaded by the `area` threshold styles, as axis
+/// positions with the color of their step. Each step reaches up to the next
+/// one; the base step has no lower end and the last no upper end.
+pub(crate) fn threshold_bands(panel: &PanelState) -> Vec<(f64, f64, Color)> {
+    let Some(thresholds) = panel.thresholds.as_ref().filter(|t| t.shows_area()) else {
+        return Vec::new();
+    };
+    let scale = panel.graph_options().scale;
+    // The base step has no value, and steps a log axis cannot place lie
+    // below all of it.
+    let position = |value: Option<f64>| {
+        value
+            .and_then(|value| {
+                scale.position_of(thresholds.absolute_value(value, panel.min, panel.max))
+            })
+            .unwrap_or(f64::NEG_INFINITY)
+    };
+
+    thresholds
+        .steps
+        .iter()
+        .enumerate()
+        .filter_map(|(index, step)| {
+            let from = position(step.value);
+            let to = thresholds
+                .steps
+                .get(index + 1)
+                .map_or(f64::INFINITY, |next| position(next.value));
+            (to > from).then_some((from, to, step.color))
+        })
+        .collect()
+}
+
+/// Shades the plot rows within threshold `bands` with a dark shade of the
+/// band color. Only the cell background changes, so series stay readable.
+pub(super) fn render_threshold_bands(
+    frame: &mut Frame,
+    bands: &[(f64, f64, Color)],
+    y_bounds: [f64; 2],
+    plot: PlotBounds,
+) {
+    let height = plot.bottom.saturating_sub(plot.top);
+    if bands.is_empty() || height == 0 || y_bounds[1] <= y_bounds[0] {
+        return;
+    }
+
+    let buf = frame.buffer_mut();
+    // The bottom row holds the x-axis.
+    for y in plot.top..plot.bottom {
+        let ratio = f64::from(plot.bottom - y) / f64::from(height);
+        let value = y_bounds[0] + ratio * (y_bounds[1] - y_bounds[0]);
+        let Some(background) = bands
+            .iter()
+            .find(|(from, to, _)| *from <= value && value < *to)
+            .and_then(|(_, _, color)| band_background(*color))
+        else {
+            continue;
+        };
+        for x in plot.left..plot.right {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                cell.set_bg(background);
+            }
+        }
+    }
+}
+
+/// `color` at a quarter of its brightness, or `None` for the terminal
+/// default color.
+fn band_background(color: Color) -> Option<Color> {
+    let (r, g, b) = color_rgb(color)?;
+    Some(Color::Rgb(r / 4, g / 4, b / 4))
 }
 
 pub(super) fn threshold_marker(marker_name: &str) -> (ratatui::symbols::Marker, GraphType) {
@@ -139,12 +225,12 @@ pub(super) fn render_raw_threshold_lines(
 
 fn threshold_dataset(
     marker_name: &str,
-    threshold_style: Option<&str>,
+    dashed_style: bool,
     x_bounds: [f64; 2],
     threshold_value: f64,
 ) -> Vec<(f64, f64)> {
     let [start, end] = x_bounds;
-    if marker_name.starts_with("dashed") || threshold_style == Some("dashed") {
+    if marker_name.starts_with("dashed") || dashed_style {
         let points_count = 15;
         let step_x = (end - start) / points_count as f64;
         return (0..=points_count)
@@ -229,4 +315,35 @@ mod tests {
         assert_eq!(cell.symbol(), "⣿");
         assert_eq!(cell.style().fg, Some(Color::Blue));
     }
+
+    #[test]
+    fn test_threshold_bands_shade_background_behind_data() {
+        let mut terminal = Terminal::new(TestBackend::new(5, 5)).unwrap();
+        let bands = [
+            (f64::NEG_INFINITY, 5.0, Color::Green),
+            (5.0, f64::INFINITY, Color::Rgb(200, 40, 40)),
+        ];
+
+        terminal
+            .draw(|frame| {
+                frame
+                    .buffer_mut()
+                    .cell_mut((1, 3))
+                    .unwrap()
+                    .set_char('⣿')
+                    .set_style(Style::default().fg(Color::Blue));
+
+                render_threshold_bands(frame, &bands, [0.0, 10.0], plot_bounds());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let data = buffer.cell((1, 3)).unwrap();
+        assert_eq!(data.symbol(), "⣿");
+        assert_eq!(data.style().fg, Some(Color::Blue));
+        assert_eq!(data.style().bg, Some(Color::Rgb(12, 51, 25)));
+        assert_eq!(buffer.cell((4, 0)).unwrap().bg, Color::Rgb(50, 10, 10));
+        // The x-axis row is left alone.
+        assert_eq!(buffer.cell((1, 4)).unwrap().bg, Color::Reset);
+    }
 }
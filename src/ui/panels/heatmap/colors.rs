@@ -0,0 +1,218 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::{HeatmapColor, HeatmapColorMode};
+use crate::ui::format::color_rgb;
+use ratatui::style::Color;
+
+/// Fill of opacity heatmaps whose color has no RGB value: Grafana's
+/// `dark-orange`.
+const DEFAULT_FILL: (u8, u8, u8) = (255, 165, 0);
+
+/// A color scheme as evenly spaced stops from low to high values.
+struct Scheme {
+    name: &'static str,
+    /// Stops as `0xRRGGBB`.
+    stops: [u32; 5],
+    /// Whether the scheme fades from light to dark. Grafana reverses these
+    /// on dark backgrounds so high values stand out, and so does the
+    /// terminal.
+    light_to_dark: bool,
+}
+
+const fn scheme(name: &'static str, stops: [u32; 5], light_to_dark: bool) -> Scheme {
+    Scheme {
+        name,
+        stops,
+        light_to_dark,
+    }
+}
+
+/// Schemes offered by Grafana's heatmap panel, sampled from their d3
+/// interpolators. The first one is the default.
+const SCHEMES: &[Scheme] = &[
+    scheme(
+        "Oranges",
+        [0xfff5eb, 0xfdd0a2, 0xfd8d3c, 0xd94801, 0x7f2704],
+        true,
+    ),
+    scheme(
+        "Reds",
+        [0xfff5f0, 0xfcbba1, 0xfb6a4a, 0xcb181d, 0x67000d],
+        true,
+    ),
+    scheme(
+        "Greens",
+        [0xf7fcf5, 0xc7e9c0, 0x74c476, 0x238b45, 0x00441b],
+        true,
+    ),
+    scheme(
+        "Blues",
+        [0xf7fbff, 0xc6dbef, 0x6baed6, 0x2171b5, 0x08306b],
+        true,
+    ),
+    scheme(
+        "Purples",
+        [0xfcfbfd, 0xdadaeb, 0x9e9ac8, 0x6a51a3, 0x3f007d],
+        true,
+    ),
+    scheme(
+        "Greys",
+        [0xffffff, 0xd9d9d9, 0x969696, 0x525252, 0x000000],
+        true,
+    ),
+    scheme(
+        "YlOrRd",
+        [0xffffcc, 0xfed976, 0xfd8d3c, 0xe31a1c, 0x800026],
+        true,
+    ),
+    scheme(
+        "YlGnBu",
+        [0xffffd9, 0xc7e9b4, 0x41b6c4, 0x225ea8, 0x081d58],
+        true,
+    ),
+    scheme(
+        "RdYlGn",
+        [0xa50026, 0xf46d43, 0xffffbf, 0x66bd63, 0x006837],
+        false,
+    ),
+    scheme(
+        "RdYlBu",
+        [0xa50026, 0xf46d43, 0xffffbf, 0x74add1, 0x313695],
+        false,
+    ),
+    scheme(
+        "Spectral",
+        [0x9e0142, 0xf46d43, 0xffffbf, 0x66c2a5, 0x5e4fa2],
+        false,
+    ),
+    scheme(
+        "Viridis",
+        [0x440154, 0x3b528b, 0x21918c, 0x5ec962, 0xfde725],
+        false,
+    ),
+    scheme(
+        "Magma",
+        [0x000004, 0x51127c, 0xb73779, 0xfc8961, 0xfcfdbf],
+        false,
+    ),
+    scheme(
+        "Inferno",
+        [0x000004, 0x57106e, 0xbc3754, 0xf98e09, 0xfcffa4],
+        false,
+    ),
+    scheme(
+        "Plasma",
+        [0x0d0887, 0x7e03a8, 0xcc4778, 0xf89540, 0xf0f921],
+        false,
+    ),
+    scheme(
+        "Cividis",
+        [0x00224e, 0x414d6b, 0x7c7b78, 0xbcaf6f, 0xfee838],
+        false,
+    ),
+    scheme(
+        "Turbo",
+        [0x30123b, 0x28bbec, 0xa2fc3c, 0xfb7e21, 0x7a0403],
+        false,
+    ),
+];
+
+fn find_scheme(name: &str) -> Option<&'static Scheme> {
+    SCHEMES
+        .iter()
+        .find(|scheme| scheme.name.eq_ignore_ascii_case(name))
+}
+
+/// Whether `name` is a heatmap color scheme, such as `Oranges`.
+pub(crate) fn is_heatmap_scheme(name: &str) -> bool {
+    find_scheme(name).is_some()
+}
+
+/// Color of a heatmap cell at `ratio` (0-1) between the low and the high
+/// end of the color scale. Opacity shades darken the fill color, as if it
+/// were drawn over a black background.
+pub(crate) fn heatmap_color(color: &HeatmapColor, ratio: f64) -> Color {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let ratio = if color.reverse { 1.0 - ratio } else { ratio };
+    match &color.mode {
+        HeatmapColorMode::Scheme(name) => {
+            let scheme = find_scheme(name).unwrap_or(&SCHEMES[0]);
+            let ratio = if scheme.light_to_dark {
+                1.0 - ratio
+            } else {
+                ratio
+            };
+            interpolate(&scheme.stops, ratio)
+        }
+        HeatmapColorMode::Opacity(fill) => {
+            let opacity = color
+                .exponent
+                .map_or(ratio, |exponent| ratio.powf(exponent));
+            let (r, g, b) = color_rgb(*fill).unwrap_or(DEFAULT_FILL);
+            let shade = |channel: u8| (f64::from(channel) * opacity).round() as u8;
+            Color::Rgb(shade(r), shade(g), shade(b))
+        }
+    }
+}
+
+fn interpolate(stops: &[u32], ratio: f64) -> Color {
+    let position = ratio * (stops.len() - 1) as f64;
+    let index = (position.floor() as usize).min(stops.len() - 2);
+    let fraction = position - index as f64;
+    let (low, high) = (stops[index], stops[index + 1]);
+    let mix = |shift: u32| {
+        let (low, high) = (
+            f64::from((low >> shift) as u8),
+            f64::from((high >> shift) as u8),
+        );
+        (low + (high - low) * fraction).round() as u8
+    };
+    Color::Rgb(mix(16), mix(8), mix(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_schemes_are_reversed_so_high_values_are_bright() {
+        let color = HeatmapColor::default();
+        assert_eq!(heatmap_color(&color, 0.0), Color::Rgb(0x7f, 0x27, 0x04));
+        assert_eq!(heatmap_color(&color, 1.0), Color::Rgb(0xff, 0xf5, 0xeb));
+
+        let viridis = HeatmapColor {
+            mode: HeatmapColorMode::Scheme("viridis".to_string()),
+            reverse: true,
+            ..HeatmapColor::default()
+        };
+        assert_eq!(heatmap_color(&viridis, 1.0), Color::Rgb(0x44, 0x01, 0x54));
+        assert_eq!(heatmap_color(&viridis, 0.125), Color::Rgb(0xae, 0xd8, 0x44));
+    }
+
+    #[test]
+    fn test_opacity_mode_shades_fill_color() {
+        let mut color = HeatmapColor {
+            mode: HeatmapColorMode::Opacity(Color::Rgb(200, 100, 0)),
+            exponent: None,
+            ..HeatmapColor::default()
+        };
+        assert_eq!(heatmap_color(&color, 0.5), Color::Rgb(100, 50, 0));
+        color.exponent = Some(0.5);
+        assert_eq!(heatmap_color(&color, 0.25), Color::Rgb(100, 50, 0));
+        assert_eq!(heatmap_color(&color, 0.0), Color::Rgb(0, 0, 0));
+    }
+}
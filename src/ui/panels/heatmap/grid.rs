@@ -0,0 +1,423 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::{PanelState, SeriesView};
+use crate::ui::DisplayFormat;
+use std::ops::Range;
+
+/// Bucket counts up to this are empty cells, as in Grafana.
+const EMPTY_COUNT: f64 = 1e-9;
+
+/// Cells of a heatmap, one row per histogram bucket or series and one
+/// column per slice of the time range.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HeatmapGrid {
+    /// Row labels, bottom row first.
+    pub(crate) labels: Vec<String>,
+    /// Cell values by row, bottom row first, then by column. `None` where no
+    /// sample falls or a bucket is empty.
+    pub(crate) cells: Vec<Vec<Option<f64>>>,
+    /// Whether rows are histogram buckets, whose counts add up when rows
+    /// are merged.
+    pub(crate) buckets: bool,
+    /// Values at the low and high end of the color scale.
+    pub(crate) range: [f64; 2],
+}
+
+impl HeatmapGrid {
+    /// Position of `value` within the color scale, from 0 to 1.
+    pub(crate) fn ratio(&self, value: f64) -> f64 {
+        let [low, high] = self.range;
+        if high > low {
+            ((value - low) / (high - low)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// A heatmap row: its label and the series adding up to it.
+struct HeatmapRow<'a> {
+    label: String,
+    series: Vec<&'a SeriesView>,
+}
+
+/// Rows of a heatmap, bottom first, and whether they are histogram buckets.
+///
+/// When every visible series has an `le` bound, rows are its buckets in
+/// bound order, merging series with the same bound. Otherwise each series
+/// is a row, with the first one on top.
+fn heatmap_rows(p: &PanelState) -> (Vec<HeatmapRow<'_>>, bool) {
+    let visible: Vec<&SeriesView> = p.series.iter().filter(|s| s.visible).collect();
+    let mut buckets: Vec<(f64, &SeriesView)> = visible
+        .iter()
+        .filter_map(|series| Some((series.bucket?, *series)))
+        .collect();
+    if visible.is_empty() || buckets.len() < visible.len() {
+        let rows = visible
+            .into_iter()
+            .rev()
+            .map(|series| HeatmapRow {
+                label: series.name.clone(),
+                series: vec![series],
+            })
+            .collect();
+        return (rows, false);
+    }
+
+    let y_axis = p.heatmap_options().y_axis;
+    buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut rows: Vec<(f64, HeatmapRow)> = Vec::new();
+    for (bound, series) in buckets {
+        match rows.last_mut() {
+            Some((last, row)) if *last == bound => row.series.push(series),
+            _ => rows.push((
+                bound,
+                HeatmapRow {
+                    label: bucket_label(bound, &y_axis),
+                    series: vec![series],
+                },
+            )),
+        }
+    }
+    (rows.into_iter().map(|(_, row)| row).collect(), true)
+}
+
+/// Row labels of the heatmap of `p`, bottom row first.
+pub(crate) fn heatmap_labels(p: &PanelState) -> Vec<String> {
+    heatmap_rows(p).0.into_iter().map(|row| row.label).collect()
+}
+
+/// Heatmap of the visible series of `p` across `x_bounds`, with `columns`
+/// columns and at most `max_rows` rows, or `None` when no sample falls within
+/// them.
+///
+/// Histogram buckets count every observation up to their bound, so their
+/// rows show the count above the bucket below at each timestamp. Each
+/// column then holds the largest count or sample within it, and every
+/// sample fills the columns until the next one is due. Past `max_rows`,
+/// neighbouring rows merge, adding up bucket counts and keeping the largest
+/// sample otherwise, before the color scale is fitted to the cells.
+pub(crate) fn heatmap_grid(
+    p: &PanelState,
+    x_bounds: [f64; 2],
+    columns: usize,
+    max_rows: usize,
+) -> Option<HeatmapGrid> {
+    if columns == 0 || max_rows == 0 || x_bounds[1] <= x_bounds[0] {
+        return None;
+    }
+    let (rows, buckets) = heatmap_rows(p);
+    let interval = sample_interval(rows.iter().flat_map(|row| row.series.iter().copied()))
+        .unwrap_or((x_bounds[1] - x_bounds[0]) / columns as f64);
+
+    let (mut labels, mut points): (Vec<String>, Vec<Vec<(f64, f64)>>) = rows
+        .into_iter()
+        .map(|row| (row.label, row_points(&row.series)))
+        .unzip();
+    if buckets {
+        points = bucket_counts(&points);
+    }
+
+    let mut cells: Vec<Vec<Option<f64>>> = points
+        .iter()
+        .map(|points| column_values(points, x_bounds, interval, columns))
+        .collect();
+    if buckets {
+        for value in cells.iter_mut().flatten() {
+            *value = value.filter(|count| *count > EMPTY_COUNT);
+        }
+    }
+    if cells.len() > max_rows {
+        let groups: Vec<Range<usize>> = (0..max_rows)
+            .map(|row| row_group(row, max_rows, cells.len()))
+            .collect();
+        // A merged row is labeled after its top bucket or series.
+        labels = groups
+            .iter()
+            .map(|group| labels[group.end - 1].clone())
+            .collect();
+        cells = groups
+            .iter()
+            .map(|group| {
+                (0..columns)
+                    .map(|column| {
+                        let values = cells[group.clone()].iter().filter_map(|row| row[column]);
+                        if buckets {
+                            values.reduce(|sum, value| sum + value)
+                        } else {
+                            values.reduce(f64::max)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    let (low, high) = cells
+        .iter()
+        .flatten()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), value| {
+            (low.min(*value), high.max(*value))
+        });
+    if low > high {
+        return None;
+    }
+    let color = p.heatmap_options().color;
+    Some(HeatmapGrid {
+        labels,
+        cells,
+        buckets,
+        range: [color.min.unwrap_or(low), color.max.unwrap_or(high)],
+    })
+}
+
+/// Grid rows shown on row `row` of `rows`, counted from the bottom. With
+/// more grid rows than rows, neighbours share a row; with fewer, a grid row
+/// spans several.
+pub(super) fn row_group(row: usize, rows: usize, grid_rows: usize) -> Range<usize> {
+    let first = row * grid_rows / rows;
+    let end = ((row + 1) * grid_rows / rows).max(first + 1);
+    first..end
+}
+
+/// Samples of the series of a row in time order, adding up those sharing a
+/// timestamp.
+fn row_points(series: &[&SeriesView]) -> Vec<(f64, f64)> {
+    let mut points: Vec<(f64, f64)> = series
+        .iter()
+        .flat_map(|series| series.points.iter().copied())
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut summed: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    for (ts, value) in points {
+        match summed.last_mut() {
+            Some((last, sum)) if *last == ts => *sum += value,
+            _ => summed.push((ts, value)),
+        }
+    }
+    summed
+}
+
+/// Per-bucket counts from cumulative bucket samples, bottom bucket first:
+/// each sample less the one of the bucket below at the same timestamp.
+fn bucket_counts(cumulative: &[Vec<(f64, f64)>]) -> Vec<Vec<(f64, f64)>> {
+    cumulative
+        .iter()
+        .enumerate()
+        .map(|(index, points)| {
+            let below = index.checked_sub(1).map(|below| &cumulative[below]);
+            points
+                .iter()
+                .map(|&(ts, value)| {
+                    let lower = below
+                        .and_then(|below| {
+                            below
+                                .binary_search_by(|(other, _)| other.total_cmp(&ts))
+                                .ok()
+                                .map(|at| below[at].1)
+                        })
+                        .unwrap_or(0.0);
+                    (ts, (value - lower).max(0.0))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Typical time between samples: the median gap within each series.
+fn sample_interval<'a>(series: impl Iterator<Item = &'a SeriesView>) -> Option<f64> {
+    let mut gaps: Vec<f64> = series
+        .flat_map(|series| series.points.windows(2).map(|pair| pair[1].0 - pair[0].0))
+        .filter(|gap| *gap > 0.0)
+        .collect();
+    gaps.sort_by(f64::total_cmp);
+    gaps.get(gaps.len() / 2).copied()
+}
+
+/// Largest sample of `points` in each column, with every sample filling the
+/// columns up to `interval` after it.
+fn column_values(
+    points: &[(f64, f64)],
+    x_bounds: [f64; 2],
+    interval: f64,
+    columns: usize,
+) -> Vec<Option<f64>> {
+    let mut values: Vec<Option<f64>> = vec![None; columns];
+    let width = (x_bounds[1] - x_bounds[0]) / columns as f64;
+    for &(ts, sample) in points {
+        if ts < x_bounds[0] || ts > x_bounds[1] {
+            continue;
+        }
+        let first = (((ts - x_bounds[0]) / width).floor() as usize).min(columns - 1);
+        let last =
+            (((ts + interval - x_bounds[0]) / width).ceil() as usize).clamp(first + 1, columns);
+        for value in &mut values[first..last] {
+            *value = Some(value.map_or(sample, |value| value.max(sample)));
+        }
+    }
+    values
+}
+
+/// Label of a bucket bound: as Prometheus writes it when short, otherwise
+/// formatted like the y-axis values, e.g. for native histogram bounds.
+fn bucket_label(bound: f64, y_axis: &DisplayFormat) -> String {
+    if bound == f64::INFINITY {
+        return "+Inf".to_string();
+    }
+    let written = bound.to_string();
+    if y_axis.unit.is_none() && y_axis.decimals.is_none() && written.len() <= 6 {
+        written
+    } else {
+        y_axis.format_number(bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{HeatmapColor, HeatmapOptions, PanelOptions};
+
+    fn series(name: &str, bucket: Option<f64>, points: &[(f64, f64)]) -> SeriesView {
+        SeriesView {
+            name: name.to_string(),
+            points: points.to_vec(),
+            visible: true,
+            bucket,
+            ..Default::default()
+        }
+    }
+
+    fn panel(series: Vec<SeriesView>) -> PanelState {
+        PanelState {
+            series,
+            panel_type: crate::app::PanelType::Heatmap,
+            options: PanelOptions::Heatmap(HeatmapOptions::default()),
+            ..crate::ui::panels::test_panel("heatmap")
+        }
+    }
+
+    fn counts(values: [f64; 4]) -> Vec<(f64, f64)> {
+        [0.0, 25.0, 50.0, 75.0].into_iter().zip(values).collect()
+    }
+
+    #[test]
+    fn test_heatmap_grid_sorts_and_deaccumulates_buckets() {
+        let p = panel(vec![
+            series("inf", Some(f64::INFINITY), &counts([5.0, 3.0, 4.0, 10.0])),
+            series("half", Some(0.5), &counts([5.0, 1.0, 4.0, 3.0])),
+            series("tenth-a", Some(0.1), &counts([1.0, 1.0, 1.0, 1.0])),
+            series("tenth-b", Some(0.1), &counts([1.0, 0.0, 1.0, 0.0])),
+        ]);
+
+        let grid = heatmap_grid(&p, [0.0, 100.0], 4, 10).unwrap();
+        assert_eq!(grid.labels, vec!["0.1", "0.5", "+Inf"]);
+        assert!(grid.buckets);
+        assert_eq!(
+            grid.cells,
+            vec![
+                vec![Some(2.0), Some(1.0), Some(2.0), Some(1.0)],
+                vec![Some(3.0), None, Some(2.0), Some(2.0)],
+                vec![None, Some(2.0), None, Some(7.0)],
+            ]
+        );
+        assert_eq!(grid.range, [1.0, 7.0]);
+        assert_eq!(grid.ratio(4.0), 0.5);
+    }
+
+    #[test]
+    fn test_heatmap_grid_deaccumulates_buckets_before_pooling_columns() {
+        let p = panel(vec![
+            series("one", Some(1.0), &counts([10.0, 0.0, 10.0, 0.0])),
+            series("inf", Some(f64::INFINITY), &counts([10.0, 6.0, 10.0, 6.0])),
+        ]);
+
+        // Two samples fall in each column; the +Inf bucket counts 6 at
+        // every other one, even though both buckets peak at 10.
+        let grid = heatmap_grid(&p, [0.0, 100.0], 2, 10).unwrap();
+        assert_eq!(
+            grid.cells,
+            vec![vec![Some(10.0), Some(10.0)], vec![Some(6.0), Some(6.0)]]
+        );
+    }
+
+    #[test]
+    fn test_row_group_spreads_and_merges_rows() {
+        assert_eq!(row_group(0, 6, 3), 0..1);
+        assert_eq!(row_group(1, 6, 3), 0..1);
+        assert_eq!(row_group(2, 6, 3), 1..2);
+        assert_eq!(row_group(0, 2, 5), 0..2);
+        assert_eq!(row_group(1, 2, 5), 2..5);
+    }
+
+    #[test]
+    fn test_heatmap_grid_fits_color_scale_to_merged_rows() {
+        let p = panel(vec![
+            series("tenth", Some(0.1), &counts([1.0, 1.0, 1.0, 1.0])),
+            series("half", Some(0.5), &counts([3.0, 3.0, 3.0, 3.0])),
+            series("one", Some(1.0), &counts([4.0, 4.0, 4.0, 4.0])),
+            series("inf", Some(f64::INFINITY), &counts([8.0, 8.0, 8.0, 8.0])),
+        ]);
+
+        let grid = heatmap_grid(&p, [0.0, 100.0], 1, 2).unwrap();
+        assert_eq!(grid.labels, vec!["0.5", "+Inf"]);
+        assert_eq!(grid.cells, vec![vec![Some(3.0)], vec![Some(5.0)]]);
+        assert_eq!(grid.range, [3.0, 5.0]);
+    }
+
+    #[test]
+    fn test_heatmap_grid_places_series_samples_by_time() {
+        let regular: Vec<_> = (0..10).map(|i| (f64::from(i) * 10.0, 1.0)).collect();
+        let mut p = panel(vec![
+            series("sparse", None, &[(0.0, 4.0), (90.0, 8.0)]),
+            series("regular", None, &regular),
+        ]);
+        p.options = PanelOptions::Heatmap(HeatmapOptions {
+            color: HeatmapColor {
+                max: Some(10.0),
+                ..HeatmapColor::default()
+            },
+            ..HeatmapOptions::default()
+        });
+
+        let grid = heatmap_grid(&p, [0.0, 100.0], 10, 10).unwrap();
+        // The first series is the top row.
+        assert_eq!(grid.labels, vec!["regular", "sparse"]);
+        assert!(!grid.buckets);
+        let sparse = &grid.cells[1];
+        assert_eq!(sparse[0], Some(4.0));
+        assert!(sparse[1..9].iter().all(Option::is_none));
+        assert_eq!(sparse[9], Some(8.0));
+        assert_eq!(grid.range, [1.0, 10.0]);
+
+        assert!(heatmap_grid(&p, [200.0, 300.0], 10, 10).is_none());
+    }
+
+    #[test]
+    fn test_bucket_labels_keep_short_bounds_as_written() {
+        let y_axis = DisplayFormat::default();
+        assert_eq!(bucket_label(0.005, &y_axis), "0.005");
+        assert_eq!(bucket_label(1.0905077326652577, &y_axis), "1.09");
+        assert_eq!(bucket_label(f64::INFINITY, &y_axis), "+Inf");
+
+        let seconds = DisplayFormat {
+            unit: Some("s".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(bucket_label(0.25, &seconds), "0.25s");
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+mod colors;
+mod grid;
+
+use crate::app::{AppState, PanelState};
+use crate::ui::format::format_time;
+use grid::row_group;
+use ratatui::{prelude::*, widgets::Paragraph};
+
+pub(crate) use colors::{heatmap_color, is_heatmap_scheme};
+pub(crate) use grid::{heatmap_grid, heatmap_labels};
+
+/// Widest color scale drawn below the heatmap, in cells.
+const LEGEND_WIDTH: usize = 24;
+
+pub(super) fn render_heatmap(frame: &mut Frame, area: Rect, p: &PanelState, app: &AppState) {
+    let theme = &app.theme;
+    let text = Style::default().fg(theme.text);
+
+    if p.series.is_empty() {
+        frame.render_widget(Paragraph::new("No data").style(text), area);
+        return;
+    }
+    let labels = heatmap_labels(p);
+    if labels.is_empty() {
+        frame.render_widget(Paragraph::new("No visible series").style(text), area);
+        return;
+    }
+
+    // Bucket labels sit left of the cells; time labels and the color scale
+    // take the rows below.
+    let options = p.heatmap_options();
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(usize::from(area.width / 3));
+    let gutter = if label_width > 0 { label_width + 1 } else { 0 };
+    let columns = usize::from(area.width).saturating_sub(gutter);
+    let rows = usize::from(
+        area.height
+            .saturating_sub(1 + u16::from(options.show_legend)),
+    );
+    if columns == 0 || rows == 0 {
+        return;
+    }
+
+    let (start, now) = app.time_bounds();
+    let Some(grid) = heatmap_grid(p, [start, now], columns, rows) else {
+        frame.render_widget(Paragraph::new("No data to display").style(text), area);
+        return;
+    };
+
+    let mut lines = Vec::with_capacity(usize::from(area.height));
+    for row in (0..rows).rev() {
+        // The grid has no more rows than the terminal; a grid row may span
+        // several terminal rows.
+        let grid_row = row_group(row, rows, grid.cells.len()).start;
+        let mut spans = Vec::with_capacity(columns + 1);
+        if gutter > 0 {
+            // Label each bucket once, on the top row it covers.
+            let top =
+                row + 1 == rows || row_group(row + 1, rows, grid.cells.len()).start != grid_row;
+            let label = if top {
+                grid.labels[grid_row].chars().take(label_width).collect()
+            } else {
+                String::new()
+            };
+            spans.push(Span::styled(format!("{label:>label_width$} "), text));
+        }
+        for column in 0..columns {
+            spans.push(match grid.cells[grid_row][column] {
+                Some(value) => Span::styled(
+                    "█",
+                    Style::default().fg(heatmap_color(&options.color, grid.ratio(value))),
+                ),
+                None => Span::raw(" "),
+            });
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let (first, last) = (format_time(start), format_time(now));
+    let padding = columns.saturating_sub(first.len() + last.len());
+    lines.push(Line::styled(
+        if padding > 0 {
+            format!("{:gutter$}{first}{:padding$}{last}", "", "")
+        } else {
+            format!("{:gutter$}{first}", "")
+        },
+        text,
+    ));
+
+    if options.show_legend {
+        let scale = LEGEND_WIDTH.min(columns / 2).max(2);
+        let mut spans = vec![Span::styled(
+            format!("{:gutter$}{} ", "", p.display.format_number(grid.range[0])),
+            text,
+        )];
+        spans.extend((0..scale).map(|cell| {
+            let ratio = cell as f64 / (scale - 1) as f64;
+            Span::styled(
+                "█",
+                Style::default().fg(heatmap_color(&options.color, ratio)),
+            )
+        }));
+        spans.push(Span::styled(
+            format!(" {}", p.display.format_number(grid.range[1])),
+            text,
+        ));
+        lines.push(Line::from(spans));
+    }
+
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{PanelType, SeriesView};
+    use crate::export::ExportOptions;
+    use crate::theme::Theme;
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::time::Duration;
+
+    #[test]
+    fn test_render_heatmap_labels_buckets_and_draws_color_scale() {
+        let mut panel = crate::app::default_queries(vec!["latency_bucket".to_string()]).remove(0);
+        panel.panel_type = PanelType::Heatmap;
+        panel.series = [(f64::INFINITY, 9.0), (0.1, 2.0), (1.0, 5.0)]
+            .into_iter()
+            .map(|(bound, count)| SeriesView {
+                name: format!("le={bound}"),
+                value: Some(count),
+                points: (0..=10).map(|i| (f64::from(i) * 10.0, count)).collect(),
+                visible: true,
+                bucket: Some(bound),
+                ..Default::default()
+            })
+            .collect();
+        let mut app = AppState::new(
+            crate::app::DatasourceRegistry::single(crate::prom::PromClient::new(
+                "http://localhost:9090".to_string(),
+            )),
+            Duration::from_secs(100),
+            Duration::from_secs(5),
+            Duration::from_secs(1),
+            "test".to_string(),
+            vec![panel],
+            0,
+            Theme::default(),
+            "dashed-line".to_string(),
+            ExportOptions::default(),
+        );
+        app.view_end_ts = 100;
+
+        let mut terminal = Terminal::new(TestBackend::new(20, 8)).unwrap();
+        terminal
+            .draw(|frame| render_heatmap(frame, frame.area(), &app.panels[0], &app))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..20)
+                .map(|x| buffer.cell((x, y)).unwrap().symbol())
+                .collect()
+        };
+        // Each bucket spans two rows, labeled on the upper one.
+        assert!(row(0).starts_with("+Inf █"), "{:?}", row(0));
+        assert!(row(1).starts_with("     █"), "{:?}", row(1));
+        assert!(row(2).starts_with("   1 █"), "{:?}", row(2));
+        assert!(row(4).starts_with(" 0.1 █"), "{:?}", row(4));
+        assert!(row(6).starts_with("     00:00:00"), "{:?}", row(6));
+        assert!(row(7).starts_with("     2.00 ██"), "{:?}", row(7));
+        assert_ne!(
+            buffer.cell((5, 0)).unwrap().fg,
+            buffer.cell((5, 4)).unwrap().fg
+        );
+    }
+}
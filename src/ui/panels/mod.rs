@@ -18,8 +18,10 @@ mod bar_gauge;
 mod gauge;
 mod graph;
 mod heatmap;
+mod row;
 mod stat;
 mod table;
+mod text;
 
 use crate::app::{AppState, PanelState, PanelType};
 use ratatui::{
@@ -29,11 +31,33 @@ use ratatui::{
 
 use bar_gauge::render_bar_gauge;
 use gauge::render_gauge;
-pub(crate) use graph::calculate_y_bounds;
 use graph::render_graph_panel;
+pub(crate) use graph::{
+    Legend, LegendRow, axis_layout, axis_value_ticks, build_legend, calculate_right_y_bounds,
+    calculate_y_bounds, interpolate_points, is_right_scaled, line_segments, monotone_tangents,
+    non_finite_samples, remap_position, right_axis_display, scale_points, stack_series,
+    threshold_bands, y_axis_display,
+};
 use heatmap::render_heatmap;
+pub(crate) use heatmap::{heatmap_color, heatmap_grid, heatmap_labels, is_heatmap_scheme};
+use row::render_row;
+pub(crate) use row::row_header;
 use stat::render_stat;
 use table::render_table;
+pub(crate) use text::panel_text;
+use text::render_text_panel;
+
+/// An empty graph panel for renderer tests, which override the fields they
+/// exercise.
+#[cfg(test)]
+fn test_panel(title: &str) -> PanelState {
+    PanelState {
+        exprs: vec![],
+        legends: vec![],
+        query_modes: vec![],
+        ..crate::app::default_queries(vec![title.to_string()]).remove(0)
+    }
+}
 
 /// Renders a single panel.
 ///
@@ -51,6 +75,11 @@ pub(crate) fn render_panel(
     is_selected: bool,
     cursor_x: Option<f64>,
 ) {
+    if p.panel_type == PanelType::Row {
+        render_row(frame, area, p, app, is_selected);
+        return;
+    }
+
     let theme = &app.theme;
     let border_style = if is_selected {
         Style::default().fg(theme.border_selected)
@@ -63,7 +92,7 @@ pub(crate) fn render_panel(
             .borders(Borders::ALL)
             .border_style(border_style)
             .title(Span::styled(
-                format!("{} — ERROR", p.title),
+                format!("{} — ERROR", panel_title(p)),
                 Style::default().fg(theme.title),
             ));
         let para = Paragraph::new(err.clone())
@@ -79,7 +108,7 @@ pub(crate) fn render_panel(
         .borders(Borders::ALL)
         .border_style(border_style)
         .title(Span::styled(
-            p.title.clone(),
+            panel_title(p),
             Style::default().fg(theme.title),
         ));
     frame.render_widget(block.clone(), area);
@@ -105,5 +134,22 @@ pub(crate) fn render_panel(
         PanelType::Heatmap => {
             render_heatmap(frame, inner_area, p, app);
         }
+        PanelType::Text => {
+            render_text_panel(frame, inner_area, p, app);
+        }
+        // Drawn without a panel block above.
+        PanelType::Row => {}
+    }
+}
+
+/// Panel title with loading and stale indicators.
+fn panel_title(p: &PanelState) -> String {
+    let mut title = p.title.clone();
+    if p.stale {
+        title.push_str(" (stale)");
+    }
+    if p.loading {
+        title.push_str(" ⟳");
     }
+    title
 }
@@ -0,0 +1,52 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::{AppState, PanelState};
+use ratatui::{prelude::*, widgets::Paragraph};
+
+/// Text of a row header, e.g. `▼ Title` or `▶ Title` when collapsed.
+pub(crate) fn row_header(p: &PanelState) -> String {
+    let marker = if p.is_collapsed_row() { '▶' } else { '▼' };
+    format!("{marker} {}", p.title)
+}
+
+/// Renders a row header as a single line followed by a horizontal rule.
+pub(super) fn render_row(
+    frame: &mut Frame,
+    area: Rect,
+    p: &PanelState,
+    app: &AppState,
+    is_selected: bool,
+) {
+    let theme = &app.theme;
+    let header = row_header(p);
+    let title_style = if is_selected {
+        Style::default()
+            .fg(theme.border_selected)
+            .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+    } else {
+        Style::default()
+            .fg(theme.title)
+            .add_modifier(Modifier::BOLD)
+    };
+    let rule_width = (area.width as usize).saturating_sub(header.chars().count() + 1);
+    let line = Line::from(vec![
+        Span::styled(header, title_style),
+        Span::raw(" "),
+        Span::styled("─".repeat(rule_width), Style::default().fg(theme.border)),
+    ]);
+    frame.render_widget(Paragraph::new(line), area);
+}
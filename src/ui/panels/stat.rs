@@ -23,10 +23,12 @@ use ratatui::{
 pub(super) fn render_stat(frame: &mut Frame, area: Rect, p: &PanelState, app: &AppState) {
     let theme = &app.theme;
 
-    let visible_series = p.series.iter().find(|s| s.visible);
-    let value = visible_series.and_then(|s| s.value);
-    let color = value
-        .and_then(|value| p.get_color_for_value(value))
+    let reduced = p.reduced_values();
+    let first = reduced.first();
+    let visible_series = first.map(|(s, _)| *s);
+    let value = first.and_then(|(_, values)| values.first().copied().flatten());
+    let color = visible_series
+        .and_then(|s| p.value_color(s, value))
         .unwrap_or(theme.palette[0]);
 
     // Split area into value (top) and sparkline (bottom)
@@ -38,7 +40,7 @@ pub(super) fn render_stat(frame: &mut Frame, area: Rect, p: &PanelState, app: &A
     // A visible series with a null value should use Grafana's noValue text,
     // while no visible series at all remains Grafatui's existing "No data" state.
     let val_str = visible_series
-        .map(|_| p.display.format_value(value))
+        .map(|s| p.series_display(s).format_value(value))
         .unwrap_or_else(|| "No data".to_string());
     let big_value = Paragraph::new(val_str)
         .style(Style::default().fg(color).add_modifier(Modifier::BOLD))
@@ -23,22 +23,27 @@ use ratatui::{
 pub(super) fn render_table(frame: &mut Frame, area: Rect, p: &PanelState, app: &AppState) {
     let theme = &app.theme;
 
-    let header = ["Series", "Value"];
+    let columns = p.reduce_options().column_labels();
+    let header: Vec<String> = std::iter::once("Series".to_string())
+        .chain(columns.iter().cloned())
+        .collect();
     let rows: Vec<Row> = p
-        .series
-        .iter()
-        .filter(|s| s.visible)
-        .map(|s| {
-            let val_str = p.display.format_value(s.value);
-            let color = s
-                .value
-                .and_then(|v| p.get_color_for_value(v))
-                .unwrap_or(theme.text);
+        .reduced_values()
+        .into_iter()
+        .map(|(s, values)| {
+            let display = p.series_display(s);
+            let cells = values.into_iter().map(|value| {
+                let color = p.value_color(s, value).unwrap_or(theme.text);
+                Span::styled(display.format_value(value), Style::default().fg(color))
+            });
 
-            Row::new(vec![
-                Span::styled(s.name.clone(), Style::default().fg(theme.text)),
-                Span::styled(val_str, Style::default().fg(color)),
-            ])
+            Row::new(
+                std::iter::once(Span::styled(
+                    s.name.clone(),
+                    Style::default().fg(theme.text),
+                ))
+                .chain(cells),
+            )
         })
         .collect();
 
@@ -48,21 +53,21 @@ pub(super) fn render_table(frame: &mut Frame, area: Rect, p: &PanelState, app: &
         return;
     }
 
-    let table = Table::new(
-        rows,
-        [Constraint::Percentage(70), Constraint::Percentage(30)],
-    )
-    .header(
-        Row::new(header)
-            .style(
-                Style::default()
-                    .fg(theme.title)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .bottom_margin(1),
-    )
-    .block(Block::default().borders(Borders::NONE))
-    .column_spacing(1);
+    let series_width = if columns.len() == 1 { 70 } else { 40 };
+    let widths = std::iter::once(Constraint::Percentage(series_width))
+        .chain(columns.iter().map(|_| Constraint::Fill(1)));
+    let table = Table::new(rows, widths)
+        .header(
+            Row::new(header)
+                .style(
+                    Style::default()
+                        .fg(theme.title)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::NONE))
+        .column_spacing(1);
 
     frame.render_widget(table, area);
 }
@@ -0,0 +1,105 @@
+/*
+ * Copyright 2026 Federico D'Ambrosio
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::app::{AppState, PanelOptions, PanelState, interpolate_text};
+use crate::theme::Theme;
+use crate::ui::markdown::{LineKind, SpanStyle, TextLine, render_text};
+use ratatui::{
+    prelude::*,
+    widgets::{Paragraph, Wrap},
+};
+
+/// Content of a text panel with variables interpolated, as styled lines.
+pub(crate) fn panel_text(p: &PanelState, app: &AppState) -> Vec<TextLine> {
+    let PanelOptions::Text(options) = &p.options else {
+        return Vec::new();
+    };
+    let content = interpolate_text(&options.content, options.mode, |name| {
+        p.scoped_vars.get(name).or_else(|| app.vars.get(name))
+    });
+    render_text(&content, options.mode)
+}
+
+pub(super) fn render_text_panel(frame: &mut Frame, area: Rect, p: &PanelState, app: &AppState) {
+    let theme = &app.theme;
+    let lines: Vec<Line> = panel_text(p, app)
+        .into_iter()
+        .map(|line| text_line(line, theme, area.width))
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .style(Style::default().fg(theme.text));
+    frame.render_widget(paragraph, area);
+}
+
+fn text_line(line: TextLine, theme: &Theme, width: u16) -> Line<'static> {
+    let mut spans = Vec::with_capacity(line.spans.len() + 1);
+    match line.kind {
+        LineKind::Rule => {
+            return Line::styled(
+                "─".repeat(width as usize),
+                Style::default().fg(theme.border),
+            );
+        }
+        LineKind::Quote => spans.push(Span::styled("│ ", Style::default().fg(theme.legend_dim))),
+        _ if line.indent > 0 => spans.push(Span::raw("  ".repeat(line.indent))),
+        _ => {}
+    }
+
+    let base = match line.kind {
+        LineKind::Heading(level) => {
+            let style = Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD);
+            if level == 1 {
+                style.add_modifier(Modifier::UNDERLINED)
+            } else {
+                style
+            }
+        }
+        LineKind::Quote => Style::default().add_modifier(Modifier::ITALIC),
+        _ => Style::default(),
+    };
+    spans.extend(
+        line.spans
+            .into_iter()
+            .map(|span| Span::styled(span.text, base.patch(span_style(span.style, theme)))),
+    );
+    Line::from(spans)
+}
+
+fn span_style(style: SpanStyle, theme: &Theme) -> Style {
+    let mut out = Style::default();
+    if style.bold {
+        out = out.add_modifier(Modifier::BOLD);
+    }
+    if style.italic {
+        out = out.add_modifier(Modifier::ITALIC);
+    }
+    if style.strike {
+        out = out.add_modifier(Modifier::CROSSED_OUT);
+    }
+    if style.code {
+        out = out.fg(theme.palette[0]);
+    }
+    if style.link {
+        out = out.fg(theme.title).add_modifier(Modifier::UNDERLINED);
+    }
+    if style.dim {
+        out = out.fg(theme.legend_dim);
+    }
+    out
+}
@@ -20,7 +20,7 @@ fn validate_strict_exits_nonzero_when_warnings_exist() {
         r#"{
             "title": "Warnings",
             "panels": [
-                { "type": "text", "title": "Notes" }
+                { "type": "dashlist", "title": "Notes" }
             ]
         }"#,
     );
@@ -45,7 +45,7 @@ fn validate_json_outputs_machine_readable_summary() {
         r#"{
             "title": "JSON Warnings",
             "panels": [
-                { "type": "text", "title": "Notes" },
+                { "type": "dashlist", "title": "Notes" },
                 {
                     "type": "timeseries",
                     "title": "CPU",